@@ -0,0 +1,20 @@
+extern crate device_query;
+
+#[cfg(target_os = "linux")]
+fn main() {
+    use device_query::{DeviceQuery, DeviceState, LinuxBackend};
+
+    let device_state = DeviceState::with_backend(LinuxBackend::Evdev)
+        .expect("Couldn't open any input device, is the user in the input group?");
+    let mut prev_keys = vec![];
+    loop {
+        let keys = device_state.get_keys();
+        if keys != prev_keys {
+            println!("{:?}", keys);
+        }
+        prev_keys = keys;
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}
@@ -5,7 +5,8 @@ use std::thread;
 use std::time::Duration;
 
 fn main() {
-    let device_state = DeviceEventsHandler::new(Duration::from_millis(10)).expect("Couldn't start event loop");
+    let device_state =
+        DeviceEventsHandler::new(Duration::from_millis(10)).expect("Couldn't start event loop");
     let _guard = device_state.on_mouse_move(|position| {
         println!("Position: {:#?}", position);
     });
@@ -15,6 +16,12 @@ fn main() {
     let _guard = device_state.on_mouse_up(|button| {
         println!("Up: {:#?}", button);
     });
+    let _guard = device_state.on_scroll(|delta| {
+        println!("Scroll: {:#?}", delta);
+    });
+    let _guard = device_state.on_mouse_motion(|dx, dy| {
+        println!("Motion: {}, {}", dx, dy);
+    });
 
     loop {
         thread::sleep(Duration::from_secs(1000));
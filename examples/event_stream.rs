@@ -0,0 +1,12 @@
+extern crate device_query;
+
+use device_query::{DeviceEvents, DeviceEventsHandler};
+use std::time::Duration;
+
+fn main() {
+    let device_state =
+        DeviceEventsHandler::new(Duration::from_millis(10)).expect("Couldn't start event loop");
+    for event in device_state.subscribe() {
+        println!("{:?}", event);
+    }
+}
@@ -0,0 +1,18 @@
+extern crate device_query;
+
+use device_query::{DeviceQuery, DeviceState, PointerLocation};
+
+fn main() {
+    let device_state = DeviceState::new();
+    for (index, monitor) in device_state.get_monitors().iter().enumerate() {
+        println!("{}: {:?}", index, monitor);
+    }
+    let mut prev_location = PointerLocation::default();
+    loop {
+        let location = device_state.get_pointer_location();
+        if location != prev_location {
+            println!("{:?}", location);
+        }
+        prev_location = location;
+    }
+}
@@ -0,0 +1,279 @@
+//! Set of mouse buttons stored as a bitset.
+
+use std::fmt;
+use std::iter::FromIterator;
+use MouseButton;
+
+/// A set of mouse buttons, with one bit per [`MouseButton`]. Unlike [`KeySet`](crate::KeySet),
+/// it grows to hold the `Other` buttons of any number, and isn't `Copy`. Its buttons are always
+/// in `MouseButton` order.
+///
+/// ```
+/// use device_query::{ButtonSet, MouseButton};
+///
+/// let previous: ButtonSet = vec![MouseButton::Left].into_iter().collect();
+/// let current: ButtonSet = vec![MouseButton::Left, MouseButton::Other(7)].into_iter().collect();
+/// assert!(current.contains(MouseButton::Other(7)));
+/// assert_eq!(
+///     current.difference(&previous).iter().collect::<Vec<_>>(),
+///     [MouseButton::Other(7)]
+/// );
+/// assert!(previous.difference(&current).is_empty());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct ButtonSet {
+    // Trailing zero words are removed, so that equal sets have equal bits.
+    bits: Vec<u64>,
+}
+
+impl ButtonSet {
+    /// Creates an empty set.
+    pub const fn new() -> ButtonSet {
+        ButtonSet { bits: Vec::new() }
+    }
+
+    fn position(button: MouseButton) -> (usize, u64) {
+        let index = button.index();
+        (index / 64, 1 << (index % 64))
+    }
+
+    /// Removes the trailing zero words.
+    fn trim(&mut self) {
+        while self.bits.last() == Some(&0) {
+            self.bits.pop();
+        }
+    }
+
+    /// Adds `button`. Returns whether it wasn't already in the set.
+    pub fn insert(&mut self, button: MouseButton) -> bool {
+        let (word, bit) = Self::position(button);
+        if word >= self.bits.len() {
+            self.bits.resize(word + 1, 0);
+        }
+        let inserted = self.bits[word] & bit == 0;
+        self.bits[word] |= bit;
+        inserted
+    }
+
+    /// Removes `button`. Returns whether it was in the set.
+    pub fn remove(&mut self, button: MouseButton) -> bool {
+        let (word, bit) = Self::position(button);
+        let Some(bits) = self.bits.get_mut(word) else {
+            return false;
+        };
+        let removed = *bits & bit != 0;
+        *bits &= !bit;
+        self.trim();
+        removed
+    }
+
+    /// Whether `button` is in the set.
+    pub fn contains(&self, button: MouseButton) -> bool {
+        let (word, bit) = Self::position(button);
+        self.bits.get(word).is_some_and(|bits| bits & bit != 0)
+    }
+
+    /// Number of buttons in the set.
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Removes every button.
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// The buttons in `self` but not in `other`.
+    pub fn difference(&self, other: &ButtonSet) -> ButtonSet {
+        let mut difference = self.clone();
+        for (word, other_word) in difference.bits.iter_mut().zip(other.bits.iter()) {
+            *word &= !other_word;
+        }
+        difference.trim();
+        difference
+    }
+
+    /// The buttons in `self`, `other` or both.
+    pub fn union(&self, other: &ButtonSet) -> ButtonSet {
+        let (mut union, smaller) = if self.bits.len() >= other.bits.len() {
+            (self.clone(), other)
+        } else {
+            (other.clone(), self)
+        };
+        for (word, other_word) in union.bits.iter_mut().zip(smaller.bits.iter()) {
+            *word |= other_word;
+        }
+        union
+    }
+
+    /// The buttons in both `self` and `other`.
+    pub fn intersection(&self, other: &ButtonSet) -> ButtonSet {
+        let mut intersection = self.clone();
+        intersection.bits.truncate(other.bits.len());
+        for (word, other_word) in intersection.bits.iter_mut().zip(other.bits.iter()) {
+            *word &= other_word;
+        }
+        intersection.trim();
+        intersection
+    }
+
+    /// Iterates over the buttons, in `MouseButton` order.
+    pub fn iter(&self) -> Buttons<'_> {
+        Buttons {
+            bits: &self.bits,
+            word: 0,
+            current: self.bits.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Iterator over the buttons of a [`ButtonSet`].
+#[derive(Debug, Clone)]
+pub struct Buttons<'a> {
+    bits: &'a [u64],
+    word: usize,
+    // The bits of `word` not iterated over yet.
+    current: u64,
+}
+
+impl Iterator for Buttons<'_> {
+    type Item = MouseButton;
+
+    fn next(&mut self) -> Option<MouseButton> {
+        while self.current == 0 {
+            self.word += 1;
+            self.current = *self.bits.get(self.word)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // Clears the lowest set bit.
+        self.current &= self.current - 1;
+        MouseButton::from_index(self.word * 64 + bit)
+    }
+}
+
+impl<'a> IntoIterator for &'a ButtonSet {
+    type Item = MouseButton;
+    type IntoIter = Buttons<'a>;
+
+    fn into_iter(self) -> Buttons<'a> {
+        self.iter()
+    }
+}
+
+impl FromIterator<MouseButton> for ButtonSet {
+    fn from_iter<I: IntoIterator<Item = MouseButton>>(iter: I) -> ButtonSet {
+        let mut set = ButtonSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<MouseButton> for ButtonSet {
+    fn extend<I: IntoIterator<Item = MouseButton>>(&mut self, iter: I) {
+        for button in iter {
+            self.insert(button);
+        }
+    }
+}
+
+impl fmt::Debug for ButtonSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(buttons: &[MouseButton]) -> ButtonSet {
+        buttons.iter().copied().collect()
+    }
+
+    #[test]
+    fn grows_past_the_first_word() {
+        let mut buttons = set(&[MouseButton::Left]);
+        assert_eq!(buttons.bits.len(), 1);
+        // `Other(58)` is the last button of the first word.
+        assert!(buttons.insert(MouseButton::Other(58)));
+        assert_eq!(buttons.bits.len(), 1);
+        assert!(buttons.insert(MouseButton::Other(59)));
+        assert!(!buttons.insert(MouseButton::Other(59)));
+        assert_eq!(buttons.bits.len(), 2);
+        assert_eq!(buttons.len(), 3);
+        assert!(buttons.contains(MouseButton::Other(59)));
+
+        // Removing the buttons past the first word shrinks the set again.
+        assert!(buttons.remove(MouseButton::Other(59)));
+        assert!(!buttons.remove(MouseButton::Other(59)));
+        assert_eq!(buttons.bits.len(), 1);
+        assert_eq!(buttons, set(&[MouseButton::Left, MouseButton::Other(58)]));
+    }
+
+    #[test]
+    fn holds_the_largest_other_button() {
+        let mut buttons = ButtonSet::new();
+        assert!(!buttons.contains(MouseButton::Other(255)));
+        assert!(!buttons.remove(MouseButton::Other(255)));
+        assert!(buttons.insert(MouseButton::Other(255)));
+        assert!(buttons.insert(MouseButton::Back));
+        assert_eq!(
+            buttons.iter().collect::<Vec<_>>(),
+            [MouseButton::Back, MouseButton::Other(255)]
+        );
+        assert!(buttons.remove(MouseButton::Other(255)));
+        assert!(buttons.remove(MouseButton::Back));
+        assert!(buttons.is_empty());
+        assert_eq!(buttons, ButtonSet::new());
+    }
+
+    #[test]
+    fn set_operations_between_sizes() {
+        let small = set(&[MouseButton::Left, MouseButton::Forward]);
+        let large = set(&[MouseButton::Forward, MouseButton::Other(200)]);
+        let union = set(&[
+            MouseButton::Left,
+            MouseButton::Forward,
+            MouseButton::Other(200),
+        ]);
+        assert_eq!(small.union(&large), union);
+        assert_eq!(large.union(&small), union);
+        assert_eq!(small.intersection(&large), set(&[MouseButton::Forward]));
+        assert_eq!(large.intersection(&small), set(&[MouseButton::Forward]));
+        assert_eq!(small.difference(&large), set(&[MouseButton::Left]));
+        assert_eq!(large.difference(&small), set(&[MouseButton::Other(200)]));
+        // Results are trimmed, so that they equal the sets built directly.
+        assert_eq!(large.difference(&large).bits, []);
+        assert_eq!(large.intersection(&small).bits.len(), 1);
+    }
+
+    #[test]
+    fn buttons_iterate_in_order() {
+        let buttons = set(&[
+            MouseButton::Other(100),
+            MouseButton::Back,
+            MouseButton::Other(0),
+            MouseButton::Left,
+            MouseButton::Right,
+        ]);
+        assert_eq!(
+            buttons.iter().collect::<Vec<_>>(),
+            [
+                MouseButton::Left,
+                MouseButton::Right,
+                MouseButton::Back,
+                MouseButton::Other(0),
+                MouseButton::Other(100),
+            ]
+        );
+        assert_eq!(format!("{:?}", set(&[MouseButton::Right])), "{Right}");
+    }
+}
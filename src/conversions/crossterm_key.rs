@@ -0,0 +1,329 @@
+//! Conversions between `Keycode` and `crossterm::event::KeyCode`, behind the `crossterm`
+//! feature.
+//!
+//! Crossterm key codes are the characters typed rather than keys. Keycodes convert to the
+//! character they type on a US layout without modifiers, and characters convert back to the key
+//! typing them, or its unshifted character for letters. The numeric keypad converts to the same
+//! key codes as the main keyboard, which convert back to the keys of the main keyboard.
+//!
+//! ```
+//! # extern crate crossterm;
+//! # extern crate device_query;
+//! use crossterm::event::{KeyCode, MediaKeyCode, ModifierKeyCode};
+//! use device_query::Keycode;
+//! use std::convert::TryFrom;
+//!
+//! # fn main() {
+//! assert_eq!(KeyCode::try_from(Keycode::A), Ok(KeyCode::Char('a')));
+//! assert_eq!(Keycode::try_from(KeyCode::Char('A')), Ok(Keycode::A));
+//! assert_eq!(KeyCode::try_from(Keycode::Numpad1), Ok(KeyCode::Char('1')));
+//!
+//! let without_key_code: Vec<Keycode> = Keycode::ALL
+//!     .iter()
+//!     .copied()
+//!     .filter(|key| KeyCode::try_from(*key).is_err())
+//!     .collect();
+//! assert_eq!(
+//!     without_key_code,
+//!     [
+//!         Keycode::BrowserBack,
+//!         Keycode::BrowserForward,
+//!         Keycode::BrowserRefresh,
+//!         Keycode::BrowserStop,
+//!         Keycode::BrowserSearch,
+//!         Keycode::BrowserFavorites,
+//!         Keycode::BrowserHome,
+//!         Keycode::LaunchMail,
+//!         Keycode::IntlBackslash,
+//!         Keycode::IntlRo,
+//!         Keycode::IntlYen,
+//!         Keycode::Lang1,
+//!         Keycode::Lang2,
+//!         Keycode::Convert,
+//!         Keycode::NonConvert,
+//!         Keycode::KanaMode,
+//!         Keycode::Katakana,
+//!         Keycode::Hiragana,
+//!         Keycode::ZenkakuHankaku,
+//!     ]
+//! );
+//!
+//! for key in Keycode::ALL.iter().filter(|key| !key.is_numpad()) {
+//!     if let Ok(code) = KeyCode::try_from(*key) {
+//!         let converted = Keycode::try_from(code).unwrap();
+//!         match key {
+//!             Keycode::LOption => assert_eq!(converted, Keycode::LAlt),
+//!             Keycode::ROption => assert_eq!(converted, Keycode::RAlt),
+//!             Keycode::Command => assert_eq!(converted, Keycode::LMeta),
+//!             key => assert_eq!(converted, *key),
+//!         }
+//!     }
+//! }
+//!
+//! // The crossterm key codes without a counterpart, besides the characters typed with shift
+//! // or not on a US keyboard.
+//! let without_keycode = vec![
+//!     KeyCode::Char('!'),
+//!     KeyCode::Char('é'),
+//!     KeyCode::F(25),
+//!     KeyCode::Null,
+//!     KeyCode::Media(MediaKeyCode::Play),
+//!     KeyCode::Media(MediaKeyCode::Pause),
+//!     KeyCode::Media(MediaKeyCode::Reverse),
+//!     KeyCode::Media(MediaKeyCode::FastForward),
+//!     KeyCode::Media(MediaKeyCode::Rewind),
+//!     KeyCode::Media(MediaKeyCode::Record),
+//!     KeyCode::Modifier(ModifierKeyCode::LeftHyper),
+//!     KeyCode::Modifier(ModifierKeyCode::LeftMeta),
+//!     KeyCode::Modifier(ModifierKeyCode::RightHyper),
+//!     KeyCode::Modifier(ModifierKeyCode::RightMeta),
+//!     KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift),
+//!     KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift),
+//! ];
+//! for code in without_keycode {
+//!     assert!(Keycode::try_from(code).is_err());
+//! }
+//! # }
+//! ```
+
+extern crate crossterm;
+
+use self::crossterm::event::{KeyCode, MediaKeyCode, ModifierKeyCode};
+use super::{pc_modifier, NoCounterpart};
+use std::convert::TryFrom;
+use Keycode;
+
+/// The character typed by each key of the main keyboard typing one, on a US layout.
+const CHARS: [(Keycode, char); 48] = [
+    (Keycode::Key0, '0'),
+    (Keycode::Key1, '1'),
+    (Keycode::Key2, '2'),
+    (Keycode::Key3, '3'),
+    (Keycode::Key4, '4'),
+    (Keycode::Key5, '5'),
+    (Keycode::Key6, '6'),
+    (Keycode::Key7, '7'),
+    (Keycode::Key8, '8'),
+    (Keycode::Key9, '9'),
+    (Keycode::A, 'a'),
+    (Keycode::B, 'b'),
+    (Keycode::C, 'c'),
+    (Keycode::D, 'd'),
+    (Keycode::E, 'e'),
+    (Keycode::F, 'f'),
+    (Keycode::G, 'g'),
+    (Keycode::H, 'h'),
+    (Keycode::I, 'i'),
+    (Keycode::J, 'j'),
+    (Keycode::K, 'k'),
+    (Keycode::L, 'l'),
+    (Keycode::M, 'm'),
+    (Keycode::N, 'n'),
+    (Keycode::O, 'o'),
+    (Keycode::P, 'p'),
+    (Keycode::Q, 'q'),
+    (Keycode::R, 'r'),
+    (Keycode::S, 's'),
+    (Keycode::T, 't'),
+    (Keycode::U, 'u'),
+    (Keycode::V, 'v'),
+    (Keycode::W, 'w'),
+    (Keycode::X, 'x'),
+    (Keycode::Y, 'y'),
+    (Keycode::Z, 'z'),
+    (Keycode::Space, ' '),
+    (Keycode::Grave, '`'),
+    (Keycode::Minus, '-'),
+    (Keycode::Equal, '='),
+    (Keycode::LeftBracket, '['),
+    (Keycode::RightBracket, ']'),
+    (Keycode::BackSlash, '\\'),
+    (Keycode::Semicolon, ';'),
+    (Keycode::Apostrophe, '\''),
+    (Keycode::Comma, ','),
+    (Keycode::Dot, '.'),
+    (Keycode::Slash, '/'),
+];
+
+/// The character typed by each key of the numeric keypad typing one.
+const NUMPAD_CHARS: [(Keycode, char); 17] = [
+    (Keycode::Numpad0, '0'),
+    (Keycode::Numpad1, '1'),
+    (Keycode::Numpad2, '2'),
+    (Keycode::Numpad3, '3'),
+    (Keycode::Numpad4, '4'),
+    (Keycode::Numpad5, '5'),
+    (Keycode::Numpad6, '6'),
+    (Keycode::Numpad7, '7'),
+    (Keycode::Numpad8, '8'),
+    (Keycode::Numpad9, '9'),
+    (Keycode::NumpadSubtract, '-'),
+    (Keycode::NumpadAdd, '+'),
+    (Keycode::NumpadDivide, '/'),
+    (Keycode::NumpadMultiply, '*'),
+    (Keycode::NumpadEquals, '='),
+    (Keycode::NumpadDecimal, '.'),
+    (Keycode::NumpadComma, ','),
+];
+
+/// `F1` to `F24`, for `KeyCode::F(1)` to `KeyCode::F(24)`.
+const FUNCTION_KEYS: [Keycode; 24] = [
+    Keycode::F1,
+    Keycode::F2,
+    Keycode::F3,
+    Keycode::F4,
+    Keycode::F5,
+    Keycode::F6,
+    Keycode::F7,
+    Keycode::F8,
+    Keycode::F9,
+    Keycode::F10,
+    Keycode::F11,
+    Keycode::F12,
+    Keycode::F13,
+    Keycode::F14,
+    Keycode::F15,
+    Keycode::F16,
+    Keycode::F17,
+    Keycode::F18,
+    Keycode::F19,
+    Keycode::F20,
+    Keycode::F21,
+    Keycode::F22,
+    Keycode::F23,
+    Keycode::F24,
+];
+
+impl TryFrom<KeyCode> for Keycode {
+    type Error = NoCounterpart<KeyCode>;
+    fn try_from(code: KeyCode) -> Result<Self, Self::Error> {
+        let modifier = |modifier| match modifier {
+            ModifierKeyCode::LeftShift => Some(Keycode::LShift),
+            ModifierKeyCode::LeftControl => Some(Keycode::LControl),
+            ModifierKeyCode::LeftAlt => Some(Keycode::LAlt),
+            ModifierKeyCode::LeftSuper => Some(Keycode::LMeta),
+            ModifierKeyCode::RightShift => Some(Keycode::RShift),
+            ModifierKeyCode::RightControl => Some(Keycode::RControl),
+            ModifierKeyCode::RightAlt => Some(Keycode::RAlt),
+            ModifierKeyCode::RightSuper => Some(Keycode::RMeta),
+            ModifierKeyCode::LeftHyper
+            | ModifierKeyCode::LeftMeta
+            | ModifierKeyCode::RightHyper
+            | ModifierKeyCode::RightMeta
+            | ModifierKeyCode::IsoLevel3Shift
+            | ModifierKeyCode::IsoLevel5Shift => None,
+        };
+        let media = |media| match media {
+            MediaKeyCode::PlayPause => Some(Keycode::MediaPlayPause),
+            MediaKeyCode::Stop => Some(Keycode::MediaStop),
+            MediaKeyCode::TrackNext => Some(Keycode::MediaNextTrack),
+            MediaKeyCode::TrackPrevious => Some(Keycode::MediaPrevTrack),
+            MediaKeyCode::LowerVolume => Some(Keycode::VolumeDown),
+            MediaKeyCode::RaiseVolume => Some(Keycode::VolumeUp),
+            MediaKeyCode::MuteVolume => Some(Keycode::VolumeMute),
+            MediaKeyCode::Play
+            | MediaKeyCode::Pause
+            | MediaKeyCode::Reverse
+            | MediaKeyCode::FastForward
+            | MediaKeyCode::Rewind
+            | MediaKeyCode::Record => None,
+        };
+        let key = match code {
+            KeyCode::Backspace => Some(Keycode::Backspace),
+            KeyCode::Enter => Some(Keycode::Enter),
+            KeyCode::Left => Some(Keycode::Left),
+            KeyCode::Right => Some(Keycode::Right),
+            KeyCode::Up => Some(Keycode::Up),
+            KeyCode::Down => Some(Keycode::Down),
+            KeyCode::Home => Some(Keycode::Home),
+            KeyCode::End => Some(Keycode::End),
+            KeyCode::PageUp => Some(Keycode::PageUp),
+            KeyCode::PageDown => Some(Keycode::PageDown),
+            // Shift+Tab.
+            KeyCode::Tab | KeyCode::BackTab => Some(Keycode::Tab),
+            KeyCode::Delete => Some(Keycode::Delete),
+            KeyCode::Insert => Some(Keycode::Insert),
+            KeyCode::F(n) => (n as usize)
+                .checked_sub(1)
+                .and_then(|index| FUNCTION_KEYS.get(index))
+                .copied(),
+            KeyCode::Char(c) => {
+                let c = c.to_ascii_lowercase();
+                CHARS
+                    .iter()
+                    .find(|(_, key_char)| *key_char == c)
+                    .map(|(key, _)| *key)
+            }
+            KeyCode::Null => None,
+            KeyCode::Esc => Some(Keycode::Escape),
+            KeyCode::CapsLock => Some(Keycode::CapsLock),
+            KeyCode::ScrollLock => Some(Keycode::ScrollLock),
+            KeyCode::NumLock => Some(Keycode::NumLock),
+            KeyCode::PrintScreen => Some(Keycode::PrintScreen),
+            KeyCode::Pause => Some(Keycode::Pause),
+            KeyCode::Menu => Some(Keycode::Menu),
+            // The 5 of the keypad, with num lock off.
+            KeyCode::KeypadBegin => Some(Keycode::Numpad5),
+            KeyCode::Media(media_code) => media(media_code),
+            KeyCode::Modifier(modifier_code) => modifier(modifier_code),
+        };
+        key.ok_or(NoCounterpart(code))
+    }
+}
+
+impl TryFrom<Keycode> for KeyCode {
+    type Error = NoCounterpart<Keycode>;
+    fn try_from(key: Keycode) -> Result<Self, Self::Error> {
+        let code = match pc_modifier(key) {
+            Keycode::Escape => Some(KeyCode::Esc),
+            Keycode::Enter | Keycode::NumpadEnter => Some(KeyCode::Enter),
+            Keycode::Up => Some(KeyCode::Up),
+            Keycode::Down => Some(KeyCode::Down),
+            Keycode::Left => Some(KeyCode::Left),
+            Keycode::Right => Some(KeyCode::Right),
+            Keycode::Backspace => Some(KeyCode::Backspace),
+            Keycode::CapsLock => Some(KeyCode::CapsLock),
+            Keycode::Tab => Some(KeyCode::Tab),
+            Keycode::Home => Some(KeyCode::Home),
+            Keycode::End => Some(KeyCode::End),
+            Keycode::PageUp => Some(KeyCode::PageUp),
+            Keycode::PageDown => Some(KeyCode::PageDown),
+            Keycode::Insert => Some(KeyCode::Insert),
+            Keycode::Delete => Some(KeyCode::Delete),
+            Keycode::NumLock => Some(KeyCode::NumLock),
+            Keycode::ScrollLock => Some(KeyCode::ScrollLock),
+            Keycode::PrintScreen => Some(KeyCode::PrintScreen),
+            Keycode::Pause => Some(KeyCode::Pause),
+            Keycode::Menu => Some(KeyCode::Menu),
+            Keycode::LControl => Some(KeyCode::Modifier(ModifierKeyCode::LeftControl)),
+            Keycode::RControl => Some(KeyCode::Modifier(ModifierKeyCode::RightControl)),
+            Keycode::LShift => Some(KeyCode::Modifier(ModifierKeyCode::LeftShift)),
+            Keycode::RShift => Some(KeyCode::Modifier(ModifierKeyCode::RightShift)),
+            Keycode::LAlt => Some(KeyCode::Modifier(ModifierKeyCode::LeftAlt)),
+            Keycode::RAlt => Some(KeyCode::Modifier(ModifierKeyCode::RightAlt)),
+            Keycode::LMeta => Some(KeyCode::Modifier(ModifierKeyCode::LeftSuper)),
+            Keycode::RMeta => Some(KeyCode::Modifier(ModifierKeyCode::RightSuper)),
+            Keycode::VolumeUp => Some(KeyCode::Media(MediaKeyCode::RaiseVolume)),
+            Keycode::VolumeDown => Some(KeyCode::Media(MediaKeyCode::LowerVolume)),
+            Keycode::VolumeMute => Some(KeyCode::Media(MediaKeyCode::MuteVolume)),
+            Keycode::MediaPlayPause => Some(KeyCode::Media(MediaKeyCode::PlayPause)),
+            Keycode::MediaStop => Some(KeyCode::Media(MediaKeyCode::Stop)),
+            Keycode::MediaNextTrack => Some(KeyCode::Media(MediaKeyCode::TrackNext)),
+            Keycode::MediaPrevTrack => Some(KeyCode::Media(MediaKeyCode::TrackPrevious)),
+            key => {
+                let function_key = FUNCTION_KEYS
+                    .iter()
+                    .position(|function_key| *function_key == key)
+                    .map(|index| KeyCode::F(index as u8 + 1));
+                function_key.or_else(|| {
+                    CHARS
+                        .iter()
+                        .chain(NUMPAD_CHARS.iter())
+                        .find(|(char_key, _)| *char_key == key)
+                        .map(|(_, c)| KeyCode::Char(*c))
+                })
+            }
+        };
+        code.ok_or(NoCounterpart(key))
+    }
+}
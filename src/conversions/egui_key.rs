@@ -0,0 +1,247 @@
+//! Conversions between `Keycode` and `egui::Key`, behind the `egui` feature.
+//!
+//! egui keys are named after the keys of a US keyboard, like keycodes. The numeric keypad
+//! converts to the keys typing the same character on the main keyboard, which convert back to
+//! the keys of the main keyboard.
+//!
+//! ```
+//! # extern crate device_query;
+//! # extern crate egui;
+//! use device_query::Keycode;
+//! use egui::Key;
+//! use std::convert::TryFrom;
+//!
+//! # fn main() {
+//! let without_key: Vec<Keycode> = Keycode::ALL
+//!     .iter()
+//!     .copied()
+//!     .filter(|key| Key::try_from(*key).is_err())
+//!     .collect();
+//! assert_eq!(
+//!     without_key,
+//!     [
+//!         Keycode::LControl,
+//!         Keycode::RControl,
+//!         Keycode::LShift,
+//!         Keycode::RShift,
+//!         Keycode::LAlt,
+//!         Keycode::RAlt,
+//!         Keycode::Command,
+//!         Keycode::LOption,
+//!         Keycode::ROption,
+//!         Keycode::LMeta,
+//!         Keycode::RMeta,
+//!         Keycode::CapsLock,
+//!         Keycode::NumpadMultiply,
+//!         Keycode::NumLock,
+//!         Keycode::ScrollLock,
+//!         Keycode::PrintScreen,
+//!         Keycode::Pause,
+//!         Keycode::Menu,
+//!         Keycode::VolumeUp,
+//!         Keycode::VolumeDown,
+//!         Keycode::VolumeMute,
+//!         Keycode::MediaPlayPause,
+//!         Keycode::MediaStop,
+//!         Keycode::MediaNextTrack,
+//!         Keycode::MediaPrevTrack,
+//!         Keycode::BrowserForward,
+//!         Keycode::BrowserRefresh,
+//!         Keycode::BrowserStop,
+//!         Keycode::BrowserSearch,
+//!         Keycode::BrowserFavorites,
+//!         Keycode::BrowserHome,
+//!         Keycode::LaunchMail,
+//!         Keycode::IntlBackslash,
+//!         Keycode::IntlRo,
+//!         Keycode::IntlYen,
+//!         Keycode::Lang1,
+//!         Keycode::Lang2,
+//!         Keycode::Convert,
+//!         Keycode::NonConvert,
+//!         Keycode::KanaMode,
+//!         Keycode::Katakana,
+//!         Keycode::Hiragana,
+//!         Keycode::ZenkakuHankaku,
+//!     ]
+//! );
+//!
+//! let without_keycode: Vec<Key> = Key::ALL
+//!     .iter()
+//!     .copied()
+//!     .filter(|key| Keycode::try_from(*key).is_err())
+//!     .collect();
+//! assert_eq!(
+//!     without_keycode,
+//!     [
+//!         Key::Copy,
+//!         Key::Cut,
+//!         Key::Paste,
+//!         Key::Colon,
+//!         Key::Plus,
+//!         Key::OpenCurlyBracket,
+//!         Key::CloseCurlyBracket,
+//!         Key::Pipe,
+//!         Key::Questionmark,
+//!         Key::Exclamationmark,
+//!         Key::F25,
+//!         Key::F26,
+//!         Key::F27,
+//!         Key::F28,
+//!         Key::F29,
+//!         Key::F30,
+//!         Key::F31,
+//!         Key::F32,
+//!         Key::F33,
+//!         Key::F34,
+//!         Key::F35,
+//!     ]
+//! );
+//!
+//! for key in Key::ALL.iter() {
+//!     if let Ok(keycode) = Keycode::try_from(*key) {
+//!         assert_eq!(Key::try_from(keycode), Ok(*key));
+//!     }
+//! }
+//! # }
+//! ```
+
+extern crate egui;
+
+use self::egui::Key;
+use super::NoCounterpart;
+use std::convert::TryFrom;
+use Keycode;
+
+/// The egui key of each keycode of the main keyboard that has one.
+const KEYS: [(Keycode, Key); 87] = [
+    (Keycode::Up, Key::ArrowUp),
+    (Keycode::Down, Key::ArrowDown),
+    (Keycode::Left, Key::ArrowLeft),
+    (Keycode::Right, Key::ArrowRight),
+    (Keycode::Escape, Key::Escape),
+    (Keycode::Tab, Key::Tab),
+    (Keycode::Backspace, Key::Backspace),
+    (Keycode::Enter, Key::Enter),
+    (Keycode::Space, Key::Space),
+    (Keycode::Insert, Key::Insert),
+    (Keycode::Delete, Key::Delete),
+    (Keycode::Home, Key::Home),
+    (Keycode::End, Key::End),
+    (Keycode::PageUp, Key::PageUp),
+    (Keycode::PageDown, Key::PageDown),
+    (Keycode::Comma, Key::Comma),
+    (Keycode::BackSlash, Key::Backslash),
+    (Keycode::Slash, Key::Slash),
+    (Keycode::LeftBracket, Key::OpenBracket),
+    (Keycode::RightBracket, Key::CloseBracket),
+    (Keycode::Grave, Key::Backtick),
+    (Keycode::Minus, Key::Minus),
+    (Keycode::Dot, Key::Period),
+    (Keycode::Equal, Key::Equals),
+    (Keycode::Semicolon, Key::Semicolon),
+    (Keycode::Apostrophe, Key::Quote),
+    (Keycode::Key0, Key::Num0),
+    (Keycode::Key1, Key::Num1),
+    (Keycode::Key2, Key::Num2),
+    (Keycode::Key3, Key::Num3),
+    (Keycode::Key4, Key::Num4),
+    (Keycode::Key5, Key::Num5),
+    (Keycode::Key6, Key::Num6),
+    (Keycode::Key7, Key::Num7),
+    (Keycode::Key8, Key::Num8),
+    (Keycode::Key9, Key::Num9),
+    (Keycode::A, Key::A),
+    (Keycode::B, Key::B),
+    (Keycode::C, Key::C),
+    (Keycode::D, Key::D),
+    (Keycode::E, Key::E),
+    (Keycode::F, Key::F),
+    (Keycode::G, Key::G),
+    (Keycode::H, Key::H),
+    (Keycode::I, Key::I),
+    (Keycode::J, Key::J),
+    (Keycode::K, Key::K),
+    (Keycode::L, Key::L),
+    (Keycode::M, Key::M),
+    (Keycode::N, Key::N),
+    (Keycode::O, Key::O),
+    (Keycode::P, Key::P),
+    (Keycode::Q, Key::Q),
+    (Keycode::R, Key::R),
+    (Keycode::S, Key::S),
+    (Keycode::T, Key::T),
+    (Keycode::U, Key::U),
+    (Keycode::V, Key::V),
+    (Keycode::W, Key::W),
+    (Keycode::X, Key::X),
+    (Keycode::Y, Key::Y),
+    (Keycode::Z, Key::Z),
+    (Keycode::F1, Key::F1),
+    (Keycode::F2, Key::F2),
+    (Keycode::F3, Key::F3),
+    (Keycode::F4, Key::F4),
+    (Keycode::F5, Key::F5),
+    (Keycode::F6, Key::F6),
+    (Keycode::F7, Key::F7),
+    (Keycode::F8, Key::F8),
+    (Keycode::F9, Key::F9),
+    (Keycode::F10, Key::F10),
+    (Keycode::F11, Key::F11),
+    (Keycode::F12, Key::F12),
+    (Keycode::F13, Key::F13),
+    (Keycode::F14, Key::F14),
+    (Keycode::F15, Key::F15),
+    (Keycode::F16, Key::F16),
+    (Keycode::F17, Key::F17),
+    (Keycode::F18, Key::F18),
+    (Keycode::F19, Key::F19),
+    (Keycode::F20, Key::F20),
+    (Keycode::F21, Key::F21),
+    (Keycode::F22, Key::F22),
+    (Keycode::F23, Key::F23),
+    (Keycode::F24, Key::F24),
+    (Keycode::BrowserBack, Key::BrowserBack),
+];
+
+/// The egui key of each key of the numeric keypad that has one.
+const NUMPAD_KEYS: [(Keycode, Key); 17] = [
+    (Keycode::Numpad0, Key::Num0),
+    (Keycode::Numpad1, Key::Num1),
+    (Keycode::Numpad2, Key::Num2),
+    (Keycode::Numpad3, Key::Num3),
+    (Keycode::Numpad4, Key::Num4),
+    (Keycode::Numpad5, Key::Num5),
+    (Keycode::Numpad6, Key::Num6),
+    (Keycode::Numpad7, Key::Num7),
+    (Keycode::Numpad8, Key::Num8),
+    (Keycode::Numpad9, Key::Num9),
+    (Keycode::NumpadSubtract, Key::Minus),
+    (Keycode::NumpadAdd, Key::Plus),
+    (Keycode::NumpadDivide, Key::Slash),
+    (Keycode::NumpadEquals, Key::Equals),
+    (Keycode::NumpadEnter, Key::Enter),
+    (Keycode::NumpadDecimal, Key::Period),
+    (Keycode::NumpadComma, Key::Comma),
+];
+
+impl TryFrom<Key> for Keycode {
+    type Error = NoCounterpart<Key>;
+    fn try_from(key: Key) -> Result<Self, Self::Error> {
+        KEYS.iter()
+            .find(|(_, egui_key)| *egui_key == key)
+            .map(|(keycode, _)| *keycode)
+            .ok_or(NoCounterpart(key))
+    }
+}
+
+impl TryFrom<Keycode> for Key {
+    type Error = NoCounterpart<Keycode>;
+    fn try_from(keycode: Keycode) -> Result<Self, Self::Error> {
+        KEYS.iter()
+            .chain(NUMPAD_KEYS.iter())
+            .find(|(egui_keycode, _)| *egui_keycode == keycode)
+            .map(|(_, key)| *key)
+            .ok_or(NoCounterpart(keycode))
+    }
+}
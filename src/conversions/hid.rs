@@ -0,0 +1,218 @@
+//! USB HID usage IDs of the keyboard/keypad page (0x07).
+//!
+//! Every keycode has a usage ID but the media, browser and mail keys, which are on the consumer
+//! page instead:
+//!
+//! ```
+//! use device_query::Keycode;
+//!
+//! let without_usage: Vec<Keycode> = Keycode::ALL
+//!     .iter()
+//!     .copied()
+//!     .filter(|key| key.hid_usage().is_none())
+//!     .collect();
+//! assert_eq!(
+//!     without_usage,
+//!     [
+//!         Keycode::MediaPlayPause,
+//!         Keycode::MediaStop,
+//!         Keycode::MediaNextTrack,
+//!         Keycode::MediaPrevTrack,
+//!         Keycode::BrowserBack,
+//!         Keycode::BrowserForward,
+//!         Keycode::BrowserRefresh,
+//!         Keycode::BrowserStop,
+//!         Keycode::BrowserSearch,
+//!         Keycode::BrowserFavorites,
+//!         Keycode::BrowserHome,
+//!         Keycode::LaunchMail,
+//!     ]
+//! );
+//!
+//! // Keys convert back to themselves, but the macOS modifiers, which share the usages of the
+//! // Alt and GUI keys.
+//! for key in Keycode::ALL.iter() {
+//!     if let Some(usage) = key.hid_usage() {
+//!         let converted = Keycode::from_hid_usage(usage).unwrap();
+//!         match key {
+//!             Keycode::LOption => assert_eq!(converted, Keycode::LAlt),
+//!             Keycode::ROption => assert_eq!(converted, Keycode::RAlt),
+//!             Keycode::Command => assert_eq!(converted, Keycode::LMeta),
+//!             key => assert_eq!(converted, *key),
+//!         }
+//!     }
+//! }
+//! ```
+
+use super::pc_modifier;
+use Keycode;
+
+/// The usage ID of each keycode that has one. Usage IDs and keycodes appear at most once.
+const HID_USAGES: [(u16, Keycode); 132] = [
+    (0x04, Keycode::A),
+    (0x05, Keycode::B),
+    (0x06, Keycode::C),
+    (0x07, Keycode::D),
+    (0x08, Keycode::E),
+    (0x09, Keycode::F),
+    (0x0a, Keycode::G),
+    (0x0b, Keycode::H),
+    (0x0c, Keycode::I),
+    (0x0d, Keycode::J),
+    (0x0e, Keycode::K),
+    (0x0f, Keycode::L),
+    (0x10, Keycode::M),
+    (0x11, Keycode::N),
+    (0x12, Keycode::O),
+    (0x13, Keycode::P),
+    (0x14, Keycode::Q),
+    (0x15, Keycode::R),
+    (0x16, Keycode::S),
+    (0x17, Keycode::T),
+    (0x18, Keycode::U),
+    (0x19, Keycode::V),
+    (0x1a, Keycode::W),
+    (0x1b, Keycode::X),
+    (0x1c, Keycode::Y),
+    (0x1d, Keycode::Z),
+    (0x1e, Keycode::Key1),
+    (0x1f, Keycode::Key2),
+    (0x20, Keycode::Key3),
+    (0x21, Keycode::Key4),
+    (0x22, Keycode::Key5),
+    (0x23, Keycode::Key6),
+    (0x24, Keycode::Key7),
+    (0x25, Keycode::Key8),
+    (0x26, Keycode::Key9),
+    (0x27, Keycode::Key0),
+    (0x28, Keycode::Enter),
+    (0x29, Keycode::Escape),
+    (0x2a, Keycode::Backspace),
+    (0x2b, Keycode::Tab),
+    (0x2c, Keycode::Space),
+    (0x2d, Keycode::Minus),
+    (0x2e, Keycode::Equal),
+    (0x2f, Keycode::LeftBracket),
+    (0x30, Keycode::RightBracket),
+    (0x31, Keycode::BackSlash),
+    (0x33, Keycode::Semicolon),
+    (0x34, Keycode::Apostrophe),
+    (0x35, Keycode::Grave),
+    (0x36, Keycode::Comma),
+    (0x37, Keycode::Dot),
+    (0x38, Keycode::Slash),
+    (0x39, Keycode::CapsLock),
+    (0x3a, Keycode::F1),
+    (0x3b, Keycode::F2),
+    (0x3c, Keycode::F3),
+    (0x3d, Keycode::F4),
+    (0x3e, Keycode::F5),
+    (0x3f, Keycode::F6),
+    (0x40, Keycode::F7),
+    (0x41, Keycode::F8),
+    (0x42, Keycode::F9),
+    (0x43, Keycode::F10),
+    (0x44, Keycode::F11),
+    (0x45, Keycode::F12),
+    (0x46, Keycode::PrintScreen),
+    (0x47, Keycode::ScrollLock),
+    (0x48, Keycode::Pause),
+    (0x49, Keycode::Insert),
+    (0x4a, Keycode::Home),
+    (0x4b, Keycode::PageUp),
+    (0x4c, Keycode::Delete),
+    (0x4d, Keycode::End),
+    (0x4e, Keycode::PageDown),
+    (0x4f, Keycode::Right),
+    (0x50, Keycode::Left),
+    (0x51, Keycode::Down),
+    (0x52, Keycode::Up),
+    (0x53, Keycode::NumLock),
+    (0x54, Keycode::NumpadDivide),
+    (0x55, Keycode::NumpadMultiply),
+    (0x56, Keycode::NumpadSubtract),
+    (0x57, Keycode::NumpadAdd),
+    (0x58, Keycode::NumpadEnter),
+    (0x59, Keycode::Numpad1),
+    (0x5a, Keycode::Numpad2),
+    (0x5b, Keycode::Numpad3),
+    (0x5c, Keycode::Numpad4),
+    (0x5d, Keycode::Numpad5),
+    (0x5e, Keycode::Numpad6),
+    (0x5f, Keycode::Numpad7),
+    (0x60, Keycode::Numpad8),
+    (0x61, Keycode::Numpad9),
+    (0x62, Keycode::Numpad0),
+    (0x63, Keycode::NumpadDecimal),
+    (0x64, Keycode::IntlBackslash),
+    (0x65, Keycode::Menu),
+    (0x67, Keycode::NumpadEquals),
+    (0x68, Keycode::F13),
+    (0x69, Keycode::F14),
+    (0x6a, Keycode::F15),
+    (0x6b, Keycode::F16),
+    (0x6c, Keycode::F17),
+    (0x6d, Keycode::F18),
+    (0x6e, Keycode::F19),
+    (0x6f, Keycode::F20),
+    (0x70, Keycode::F21),
+    (0x71, Keycode::F22),
+    (0x72, Keycode::F23),
+    (0x73, Keycode::F24),
+    (0x7f, Keycode::VolumeMute),
+    (0x80, Keycode::VolumeUp),
+    (0x81, Keycode::VolumeDown),
+    (0x85, Keycode::NumpadComma),
+    (0x87, Keycode::IntlRo),
+    (0x88, Keycode::KanaMode),
+    (0x89, Keycode::IntlYen),
+    (0x8a, Keycode::Convert),
+    (0x8b, Keycode::NonConvert),
+    (0x90, Keycode::Lang1),
+    (0x91, Keycode::Lang2),
+    (0x92, Keycode::Katakana),
+    (0x93, Keycode::Hiragana),
+    (0x94, Keycode::ZenkakuHankaku),
+    (0xe0, Keycode::LControl),
+    (0xe1, Keycode::LShift),
+    (0xe2, Keycode::LAlt),
+    (0xe3, Keycode::LMeta),
+    (0xe4, Keycode::RControl),
+    (0xe5, Keycode::RShift),
+    (0xe6, Keycode::RAlt),
+    (0xe7, Keycode::RMeta),
+];
+
+impl Keycode {
+    /// The keycode of a USB HID usage ID of the keyboard/keypad page (0x07).
+    ///
+    /// The non-US `#` key (0x32) is the `BackSlash` key of ISO keyboards.
+    ///
+    /// ```
+    /// use device_query::Keycode;
+    ///
+    /// assert_eq!(Keycode::from_hid_usage(0x04), Some(Keycode::A));
+    /// assert_eq!(Keycode::from_hid_usage(0xe1), Some(Keycode::LShift));
+    /// ```
+    pub fn from_hid_usage(usage: u16) -> Option<Keycode> {
+        if usage == 0x32 {
+            return Some(Keycode::BackSlash);
+        }
+        HID_USAGES
+            .iter()
+            .find(|(hid_usage, _)| *hid_usage == usage)
+            .map(|(_, key)| *key)
+    }
+
+    /// The USB HID usage ID of this keycode in the keyboard/keypad page (0x07), if it has one.
+    ///
+    /// `LOption` and `ROption` are the Alt keys and `Command` the left GUI key, which convert
+    /// back to `LAlt`, `RAlt` and `LMeta`.
+    pub fn hid_usage(self) -> Option<u16> {
+        let key = pc_modifier(self);
+        HID_USAGES
+            .iter()
+            .find(|(_, hid_key)| *hid_key == key)
+            .map(|(usage, _)| *usage)
+    }
+}
@@ -0,0 +1,49 @@
+//! Conversions between [`Keycode`] and the key codes of other crates and standards, and between
+//! [`MouseButton`](crate::MouseButton) and the platform button numbers.
+//!
+//! USB HID usage IDs are always available, with [`Keycode::from_hid_usage`] and
+//! [`Keycode::hid_usage`]. With the `winit`, `crossterm` and `egui` features, `Keycode`
+//! converts from and to `winit::keyboard::KeyCode`, `crossterm::event::KeyCode` and
+//! `egui::Key` with `TryFrom`, failing with [`NoCounterpart`] for keys the other type can't
+//! represent.
+//!
+//! Keys are converted by position on a US keyboard, like `Keycode` names them: `Keycode::A`
+//! converts to `crossterm::event::KeyCode::Char('a')` and `egui::Key::A` whatever the layout.
+//!
+//! Mouse buttons convert from and to the X11, evdev, Windows and macOS button numbers on every
+//! platform, as listed in the [`MouseButton`](crate::MouseButton) documentation.
+
+#[cfg(feature = "crossterm")]
+mod crossterm_key;
+#[cfg(feature = "egui")]
+mod egui_key;
+mod hid;
+mod mouse_button;
+#[cfg(feature = "winit")]
+mod winit_key;
+
+use std::error::Error;
+use std::fmt;
+use Keycode;
+
+/// The error of a key conversion: the key has no counterpart in the target type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NoCounterpart<K>(pub K);
+
+impl<K: fmt::Debug> fmt::Display for NoCounterpart<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} has no counterpart", self.0)
+    }
+}
+
+impl<K: fmt::Debug> Error for NoCounterpart<K> {}
+
+/// The PC modifier on the physical key of a macOS modifier, any other key unchanged.
+fn pc_modifier(key: Keycode) -> Keycode {
+    match key {
+        Keycode::LOption => Keycode::LAlt,
+        Keycode::ROption => Keycode::RAlt,
+        Keycode::Command => Keycode::LMeta,
+        key => key,
+    }
+}
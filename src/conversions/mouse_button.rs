@@ -0,0 +1,150 @@
+//! The platform buttons of each [`MouseButton`], used by the backends of every platform:
+//!
+//! ```
+//! use device_query::MouseButton;
+//!
+//! let buttons = [
+//!     MouseButton::Left,
+//!     MouseButton::Right,
+//!     MouseButton::Middle,
+//!     MouseButton::Back,
+//!     MouseButton::Forward,
+//!     MouseButton::Other(0),
+//!     MouseButton::Other(10),
+//! ];
+//! for button in buttons.iter().copied() {
+//!     // X11 and evdev.
+//!     let x11_button = button.x11_button();
+//!     assert_eq!(MouseButton::from_x11_button(x11_button), Some(button));
+//!     let evdev_code = button.evdev_code().unwrap();
+//!     assert_eq!(MouseButton::from_evdev_code(evdev_code), Some(button));
+//!     // macOS.
+//!     let macos_button = button.macos_button().unwrap();
+//!     assert_eq!(MouseButton::from_macos_button(macos_button), Some(button));
+//! }
+//! assert_eq!(MouseButton::Other(255).x11_button(), 265);
+//! assert_eq!(MouseButton::Other(11).evdev_code(), None);
+//! assert_eq!(MouseButton::Other(27).macos_button(), None);
+//! for wheel in 4..=7 {
+//!     assert_eq!(MouseButton::from_x11_button(wheel), None);
+//! }
+//!
+//! // Windows.
+//! for button in buttons[..5].iter().copied() {
+//!     let virtual_key = button.windows_virtual_key().unwrap();
+//!     assert_eq!(MouseButton::from_windows_virtual_key(virtual_key), Some(button));
+//! }
+//! assert_eq!(MouseButton::Other(0).windows_virtual_key(), None);
+//! ```
+
+use std::convert::TryFrom;
+use MouseButton;
+
+const BTN_LEFT: u16 = 0x110;
+const BTN_RIGHT: u16 = 0x111;
+const BTN_MIDDLE: u16 = 0x112;
+const BTN_SIDE: u16 = 0x113;
+const BTN_EXTRA: u16 = 0x114;
+const BTN_FORWARD: u16 = 0x115;
+/// The last code of the mouse button range, `BTN_JOYSTICK - 1`.
+const BTN_MOUSE_LAST: u16 = 0x11f;
+
+const VK_LBUTTON: u16 = 0x01;
+const VK_RBUTTON: u16 = 0x02;
+const VK_MBUTTON: u16 = 0x04;
+const VK_XBUTTON1: u16 = 0x05;
+const VK_XBUTTON2: u16 = 0x06;
+
+/// Number of buttons macOS reports, `CGMouseButton` 0 to 31.
+const MACOS_BUTTONS: u32 = 32;
+
+impl MouseButton {
+    /// The button of an X11 button number, or `None` for the scroll wheel buttons 4 to 7.
+    pub fn from_x11_button(button: u32) -> Option<MouseButton> {
+        match button {
+            1 => Some(MouseButton::Left),
+            2 => Some(MouseButton::Middle),
+            3 => Some(MouseButton::Right),
+            8 => Some(MouseButton::Back),
+            9 => Some(MouseButton::Forward),
+            button if button >= 10 => u8::try_from(button - 10).ok().map(MouseButton::Other),
+            _ => None,
+        }
+    }
+
+    /// The X11 button number of this button.
+    pub fn x11_button(self) -> u32 {
+        match self {
+            MouseButton::Left => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Right => 3,
+            MouseButton::Back => 8,
+            MouseButton::Forward => 9,
+            MouseButton::Other(number) => 10 + u32::from(number),
+        }
+    }
+
+    /// The button of a kernel input event code, if it is in the mouse button range.
+    pub fn from_evdev_code(code: u16) -> Option<MouseButton> {
+        match code {
+            BTN_LEFT => Some(MouseButton::Left),
+            BTN_RIGHT => Some(MouseButton::Right),
+            BTN_MIDDLE => Some(MouseButton::Middle),
+            BTN_SIDE => Some(MouseButton::Back),
+            BTN_EXTRA => Some(MouseButton::Forward),
+            BTN_FORWARD..=BTN_MOUSE_LAST => Some(MouseButton::Other((code - BTN_FORWARD) as u8)),
+            _ => None,
+        }
+    }
+
+    /// The kernel input event code of this button, if it is in the mouse button range.
+    pub fn evdev_code(self) -> Option<u16> {
+        match self {
+            MouseButton::Left => Some(BTN_LEFT),
+            MouseButton::Right => Some(BTN_RIGHT),
+            MouseButton::Middle => Some(BTN_MIDDLE),
+            MouseButton::Back => Some(BTN_SIDE),
+            MouseButton::Forward => Some(BTN_EXTRA),
+            MouseButton::Other(number) => {
+                Some(BTN_FORWARD + u16::from(number)).filter(|code| *code <= BTN_MOUSE_LAST)
+            }
+        }
+    }
+
+    /// The button of a Windows virtual key code.
+    pub fn from_windows_virtual_key(virtual_key: u16) -> Option<MouseButton> {
+        match virtual_key {
+            VK_LBUTTON => Some(MouseButton::Left),
+            VK_RBUTTON => Some(MouseButton::Right),
+            VK_MBUTTON => Some(MouseButton::Middle),
+            VK_XBUTTON1 => Some(MouseButton::Back),
+            VK_XBUTTON2 => Some(MouseButton::Forward),
+            _ => None,
+        }
+    }
+
+    /// The Windows virtual key code of this button. Windows has no `Other` buttons.
+    pub fn windows_virtual_key(self) -> Option<u16> {
+        match self {
+            MouseButton::Left => Some(VK_LBUTTON),
+            MouseButton::Right => Some(VK_RBUTTON),
+            MouseButton::Middle => Some(VK_MBUTTON),
+            MouseButton::Back => Some(VK_XBUTTON1),
+            MouseButton::Forward => Some(VK_XBUTTON2),
+            MouseButton::Other(_) => None,
+        }
+    }
+
+    /// The button of a macOS `CGMouseButton` number.
+    pub fn from_macos_button(button: u32) -> Option<MouseButton> {
+        if button >= MACOS_BUTTONS {
+            return None;
+        }
+        MouseButton::from_index(button as usize)
+    }
+
+    /// The macOS `CGMouseButton` number of this button, if it is below 32.
+    pub fn macos_button(self) -> Option<u32> {
+        Some(self.index() as u32).filter(|button| *button < MACOS_BUTTONS)
+    }
+}
@@ -0,0 +1,279 @@
+//! Conversions between `Keycode` and `winit::keyboard::KeyCode`, behind the `winit` feature.
+//!
+//! Every keycode but `Unknown` has a winit counterpart. `LOption`, `ROption` and `Command`
+//! convert to the Alt and Super keys, which convert back to `LAlt`, `RAlt` and `LMeta`; winit's
+//! `Lang3`, `Lang4` and `Lang5` are `Katakana`, `Hiragana` and `ZenkakuHankaku`.
+//!
+//! ```
+//! # extern crate device_query;
+//! # extern crate winit;
+//! use device_query::Keycode;
+//! use std::convert::TryFrom;
+//! use winit::keyboard::{KeyCode, NativeKeyCode, PhysicalKey};
+//!
+//! # fn main() {
+//! for key in Keycode::ALL.iter() {
+//!     let converted = Keycode::try_from(KeyCode::try_from(*key).unwrap()).unwrap();
+//!     match key {
+//!         Keycode::LOption => assert_eq!(converted, Keycode::LAlt),
+//!         Keycode::ROption => assert_eq!(converted, Keycode::RAlt),
+//!         Keycode::Command => assert_eq!(converted, Keycode::LMeta),
+//!         key => assert_eq!(converted, *key),
+//!     }
+//! }
+//! assert!(KeyCode::try_from(Keycode::Unknown { platform_code: 0x1d3 }).is_err());
+//!
+//! let physical_key = PhysicalKey::Code(KeyCode::KeyA);
+//! assert_eq!(Keycode::try_from(physical_key), Ok(Keycode::A));
+//! let unidentified = PhysicalKey::Unidentified(NativeKeyCode::Xkb(0x1d3));
+//! assert!(Keycode::try_from(unidentified).is_err());
+//!
+//! // The winit key codes without a counterpart.
+//! let without_keycode = [
+//!     KeyCode::Fn,
+//!     KeyCode::FnLock,
+//!     KeyCode::Help,
+//!     KeyCode::NumpadBackspace,
+//!     KeyCode::NumpadClear,
+//!     KeyCode::NumpadClearEntry,
+//!     KeyCode::NumpadHash,
+//!     KeyCode::NumpadMemoryAdd,
+//!     KeyCode::NumpadMemoryClear,
+//!     KeyCode::NumpadMemoryRecall,
+//!     KeyCode::NumpadMemoryStore,
+//!     KeyCode::NumpadMemorySubtract,
+//!     KeyCode::NumpadParenLeft,
+//!     KeyCode::NumpadParenRight,
+//!     KeyCode::NumpadStar,
+//!     KeyCode::Eject,
+//!     KeyCode::LaunchApp1,
+//!     KeyCode::LaunchApp2,
+//!     KeyCode::MediaSelect,
+//!     KeyCode::Power,
+//!     KeyCode::Sleep,
+//!     KeyCode::WakeUp,
+//!     KeyCode::Meta,
+//!     KeyCode::Hyper,
+//!     KeyCode::Turbo,
+//!     KeyCode::Abort,
+//!     KeyCode::Resume,
+//!     KeyCode::Suspend,
+//!     KeyCode::Again,
+//!     KeyCode::Copy,
+//!     KeyCode::Cut,
+//!     KeyCode::Find,
+//!     KeyCode::Open,
+//!     KeyCode::Paste,
+//!     KeyCode::Props,
+//!     KeyCode::Select,
+//!     KeyCode::Undo,
+//!     KeyCode::F25,
+//!     KeyCode::F26,
+//!     KeyCode::F27,
+//!     KeyCode::F28,
+//!     KeyCode::F29,
+//!     KeyCode::F30,
+//!     KeyCode::F31,
+//!     KeyCode::F32,
+//!     KeyCode::F33,
+//!     KeyCode::F34,
+//!     KeyCode::F35,
+//! ];
+//! for code in without_keycode.iter() {
+//!     assert!(Keycode::try_from(*code).is_err());
+//! }
+//! # }
+//! ```
+
+extern crate winit;
+
+use self::winit::keyboard::{KeyCode, PhysicalKey};
+use super::{pc_modifier, NoCounterpart};
+use std::convert::TryFrom;
+use Keycode;
+
+/// The winit key code of each keycode but `Unknown` and the macOS modifiers.
+const KEYS: [(Keycode, KeyCode); 144] = [
+    (Keycode::Key0, KeyCode::Digit0),
+    (Keycode::Key1, KeyCode::Digit1),
+    (Keycode::Key2, KeyCode::Digit2),
+    (Keycode::Key3, KeyCode::Digit3),
+    (Keycode::Key4, KeyCode::Digit4),
+    (Keycode::Key5, KeyCode::Digit5),
+    (Keycode::Key6, KeyCode::Digit6),
+    (Keycode::Key7, KeyCode::Digit7),
+    (Keycode::Key8, KeyCode::Digit8),
+    (Keycode::Key9, KeyCode::Digit9),
+    (Keycode::A, KeyCode::KeyA),
+    (Keycode::B, KeyCode::KeyB),
+    (Keycode::C, KeyCode::KeyC),
+    (Keycode::D, KeyCode::KeyD),
+    (Keycode::E, KeyCode::KeyE),
+    (Keycode::F, KeyCode::KeyF),
+    (Keycode::G, KeyCode::KeyG),
+    (Keycode::H, KeyCode::KeyH),
+    (Keycode::I, KeyCode::KeyI),
+    (Keycode::J, KeyCode::KeyJ),
+    (Keycode::K, KeyCode::KeyK),
+    (Keycode::L, KeyCode::KeyL),
+    (Keycode::M, KeyCode::KeyM),
+    (Keycode::N, KeyCode::KeyN),
+    (Keycode::O, KeyCode::KeyO),
+    (Keycode::P, KeyCode::KeyP),
+    (Keycode::Q, KeyCode::KeyQ),
+    (Keycode::R, KeyCode::KeyR),
+    (Keycode::S, KeyCode::KeyS),
+    (Keycode::T, KeyCode::KeyT),
+    (Keycode::U, KeyCode::KeyU),
+    (Keycode::V, KeyCode::KeyV),
+    (Keycode::W, KeyCode::KeyW),
+    (Keycode::X, KeyCode::KeyX),
+    (Keycode::Y, KeyCode::KeyY),
+    (Keycode::Z, KeyCode::KeyZ),
+    (Keycode::F1, KeyCode::F1),
+    (Keycode::F2, KeyCode::F2),
+    (Keycode::F3, KeyCode::F3),
+    (Keycode::F4, KeyCode::F4),
+    (Keycode::F5, KeyCode::F5),
+    (Keycode::F6, KeyCode::F6),
+    (Keycode::F7, KeyCode::F7),
+    (Keycode::F8, KeyCode::F8),
+    (Keycode::F9, KeyCode::F9),
+    (Keycode::F10, KeyCode::F10),
+    (Keycode::F11, KeyCode::F11),
+    (Keycode::F12, KeyCode::F12),
+    (Keycode::F13, KeyCode::F13),
+    (Keycode::F14, KeyCode::F14),
+    (Keycode::F15, KeyCode::F15),
+    (Keycode::F16, KeyCode::F16),
+    (Keycode::F17, KeyCode::F17),
+    (Keycode::F18, KeyCode::F18),
+    (Keycode::F19, KeyCode::F19),
+    (Keycode::F20, KeyCode::F20),
+    (Keycode::Escape, KeyCode::Escape),
+    (Keycode::Space, KeyCode::Space),
+    (Keycode::LControl, KeyCode::ControlLeft),
+    (Keycode::RControl, KeyCode::ControlRight),
+    (Keycode::LShift, KeyCode::ShiftLeft),
+    (Keycode::RShift, KeyCode::ShiftRight),
+    (Keycode::LAlt, KeyCode::AltLeft),
+    (Keycode::RAlt, KeyCode::AltRight),
+    (Keycode::LMeta, KeyCode::SuperLeft),
+    (Keycode::RMeta, KeyCode::SuperRight),
+    (Keycode::Enter, KeyCode::Enter),
+    (Keycode::Up, KeyCode::ArrowUp),
+    (Keycode::Down, KeyCode::ArrowDown),
+    (Keycode::Left, KeyCode::ArrowLeft),
+    (Keycode::Right, KeyCode::ArrowRight),
+    (Keycode::Backspace, KeyCode::Backspace),
+    (Keycode::CapsLock, KeyCode::CapsLock),
+    (Keycode::Tab, KeyCode::Tab),
+    (Keycode::Home, KeyCode::Home),
+    (Keycode::End, KeyCode::End),
+    (Keycode::PageUp, KeyCode::PageUp),
+    (Keycode::PageDown, KeyCode::PageDown),
+    (Keycode::Insert, KeyCode::Insert),
+    (Keycode::Delete, KeyCode::Delete),
+    (Keycode::Numpad0, KeyCode::Numpad0),
+    (Keycode::Numpad1, KeyCode::Numpad1),
+    (Keycode::Numpad2, KeyCode::Numpad2),
+    (Keycode::Numpad3, KeyCode::Numpad3),
+    (Keycode::Numpad4, KeyCode::Numpad4),
+    (Keycode::Numpad5, KeyCode::Numpad5),
+    (Keycode::Numpad6, KeyCode::Numpad6),
+    (Keycode::Numpad7, KeyCode::Numpad7),
+    (Keycode::Numpad8, KeyCode::Numpad8),
+    (Keycode::Numpad9, KeyCode::Numpad9),
+    (Keycode::NumpadSubtract, KeyCode::NumpadSubtract),
+    (Keycode::NumpadAdd, KeyCode::NumpadAdd),
+    (Keycode::NumpadDivide, KeyCode::NumpadDivide),
+    (Keycode::NumpadMultiply, KeyCode::NumpadMultiply),
+    (Keycode::NumpadEquals, KeyCode::NumpadEqual),
+    (Keycode::NumpadEnter, KeyCode::NumpadEnter),
+    (Keycode::NumpadDecimal, KeyCode::NumpadDecimal),
+    (Keycode::Grave, KeyCode::Backquote),
+    (Keycode::Minus, KeyCode::Minus),
+    (Keycode::Equal, KeyCode::Equal),
+    (Keycode::LeftBracket, KeyCode::BracketLeft),
+    (Keycode::RightBracket, KeyCode::BracketRight),
+    (Keycode::BackSlash, KeyCode::Backslash),
+    (Keycode::Semicolon, KeyCode::Semicolon),
+    (Keycode::Apostrophe, KeyCode::Quote),
+    (Keycode::Comma, KeyCode::Comma),
+    (Keycode::Dot, KeyCode::Period),
+    (Keycode::Slash, KeyCode::Slash),
+    (Keycode::NumLock, KeyCode::NumLock),
+    (Keycode::ScrollLock, KeyCode::ScrollLock),
+    (Keycode::PrintScreen, KeyCode::PrintScreen),
+    (Keycode::Pause, KeyCode::Pause),
+    (Keycode::Menu, KeyCode::ContextMenu),
+    (Keycode::F21, KeyCode::F21),
+    (Keycode::F22, KeyCode::F22),
+    (Keycode::F23, KeyCode::F23),
+    (Keycode::F24, KeyCode::F24),
+    (Keycode::VolumeUp, KeyCode::AudioVolumeUp),
+    (Keycode::VolumeDown, KeyCode::AudioVolumeDown),
+    (Keycode::VolumeMute, KeyCode::AudioVolumeMute),
+    (Keycode::MediaPlayPause, KeyCode::MediaPlayPause),
+    (Keycode::MediaStop, KeyCode::MediaStop),
+    (Keycode::MediaNextTrack, KeyCode::MediaTrackNext),
+    (Keycode::MediaPrevTrack, KeyCode::MediaTrackPrevious),
+    (Keycode::BrowserBack, KeyCode::BrowserBack),
+    (Keycode::BrowserForward, KeyCode::BrowserForward),
+    (Keycode::BrowserRefresh, KeyCode::BrowserRefresh),
+    (Keycode::BrowserStop, KeyCode::BrowserStop),
+    (Keycode::BrowserSearch, KeyCode::BrowserSearch),
+    (Keycode::BrowserFavorites, KeyCode::BrowserFavorites),
+    (Keycode::BrowserHome, KeyCode::BrowserHome),
+    (Keycode::LaunchMail, KeyCode::LaunchMail),
+    (Keycode::IntlBackslash, KeyCode::IntlBackslash),
+    (Keycode::IntlRo, KeyCode::IntlRo),
+    (Keycode::IntlYen, KeyCode::IntlYen),
+    (Keycode::NumpadComma, KeyCode::NumpadComma),
+    (Keycode::Lang1, KeyCode::Lang1),
+    (Keycode::Lang2, KeyCode::Lang2),
+    (Keycode::Convert, KeyCode::Convert),
+    (Keycode::NonConvert, KeyCode::NonConvert),
+    (Keycode::KanaMode, KeyCode::KanaMode),
+    (Keycode::Katakana, KeyCode::Katakana),
+    (Keycode::Hiragana, KeyCode::Hiragana),
+    (Keycode::ZenkakuHankaku, KeyCode::Lang5),
+];
+
+impl TryFrom<KeyCode> for Keycode {
+    type Error = NoCounterpart<KeyCode>;
+    fn try_from(code: KeyCode) -> Result<Self, Self::Error> {
+        let code = match code {
+            KeyCode::Lang3 => KeyCode::Katakana,
+            KeyCode::Lang4 => KeyCode::Hiragana,
+            code => code,
+        };
+        KEYS.iter()
+            .find(|(_, winit_code)| *winit_code == code)
+            .map(|(key, _)| *key)
+            .ok_or(NoCounterpart(code))
+    }
+}
+
+impl TryFrom<PhysicalKey> for Keycode {
+    type Error = NoCounterpart<PhysicalKey>;
+    fn try_from(physical_key: PhysicalKey) -> Result<Self, Self::Error> {
+        match physical_key {
+            PhysicalKey::Code(code) => {
+                Keycode::try_from(code).map_err(|_| NoCounterpart(physical_key))
+            }
+            PhysicalKey::Unidentified(_) => Err(NoCounterpart(physical_key)),
+        }
+    }
+}
+
+impl TryFrom<Keycode> for KeyCode {
+    type Error = NoCounterpart<Keycode>;
+    fn try_from(key: Keycode) -> Result<Self, Self::Error> {
+        let pc_key = pc_modifier(key);
+        KEYS.iter()
+            .find(|(keycode, _)| *keycode == pc_key)
+            .map(|(_, code)| *code)
+            .ok_or(NoCounterpart(key))
+    }
+}
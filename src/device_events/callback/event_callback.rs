@@ -0,0 +1,38 @@
+//! Timed event callback.
+
+use crate::device_events::utils;
+use std::ops::DerefMut;
+use std::sync::{Arc, Mutex, Weak};
+use TimedEvent;
+
+/// Timed event callback.
+pub trait EventCallback: Fn(TimedEvent) + Send + Sync + 'static {}
+impl<F: Fn(TimedEvent) + Send + Sync + 'static> EventCallback for F {}
+
+/// Timed event callbacks.
+#[derive(Default)]
+pub(crate) struct EventCallbacks {
+    event: Mutex<Vec<Weak<dyn EventCallback>>>,
+}
+
+impl EventCallbacks {
+    pub fn push_event(&self, callback: &Arc<impl EventCallback>) {
+        if let Ok(mut callbacks) = self.event.lock() {
+            let callback = Arc::downgrade(callback);
+            callbacks.push(callback)
+        }
+    }
+
+    pub fn run_event(&self, event: TimedEvent) {
+        if let Ok(mut callbacks) = self.event.lock() {
+            utils::DrainFilter::drain_filter(callbacks.deref_mut(), |callback| {
+                callback.upgrade().is_none()
+            });
+            for callback in callbacks.iter() {
+                if let Some(callback) = callback.upgrade() {
+                    callback(event);
+                }
+            }
+        }
+    }
+}
@@ -4,7 +4,6 @@ use std::sync::{Arc, Mutex, Weak};
 use Keycode;
 
 /// Keyboard callback.
-
 pub trait KeyboardCallback: Fn(Keycode) + Send + Sync + 'static {}
 impl<F: Fn(Keycode) + Send + Sync + 'static> KeyboardCallback for F {}
 
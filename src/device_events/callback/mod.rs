@@ -1,7 +1,9 @@
 mod callback_guard;
+mod event_callback;
 mod keyboard_callback;
 mod mouse_callback;
 
 pub use self::callback_guard::*;
+pub use self::event_callback::*;
 pub use self::keyboard_callback::*;
 pub use self::mouse_callback::*;
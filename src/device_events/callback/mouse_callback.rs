@@ -1,26 +1,48 @@
 //! Mouse callback.
 
+use crate::device_events::gesture::{Gesture, GestureKind, GestureRecognizer};
 use crate::device_events::utils;
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex, Weak};
 use MouseButton;
+use MouseDelta;
 use MousePosition;
+use ScrollDelta;
+use TimedEvent;
 
 /// Mouse move callback.
 pub trait MouseMoveCallback: Fn(MousePosition) + Sync + Send + 'static {}
 impl<F: Fn(MousePosition) + Sync + Send + 'static> MouseMoveCallback for F {}
 
 /// Mouse button callback.
-
 pub trait MouseButtonCallback: Fn(MouseButton) + Sync + Send + 'static {}
 impl<F: Fn(MouseButton) + Sync + Send + 'static> MouseButtonCallback for F {}
 
+/// Mouse motion callback, receiving the motion as a single tuple.
+pub trait MouseMotionCallback: Fn(MouseDelta) + Sync + Send + 'static {}
+impl<F: Fn(MouseDelta) + Sync + Send + 'static> MouseMotionCallback for F {}
+
+/// Click, double-click and drag callback, receiving the button and the position as a single
+/// tuple.
+pub trait MouseGestureCallback: Fn((MouseButton, MousePosition)) + Sync + Send + 'static {}
+impl<F: Fn((MouseButton, MousePosition)) + Sync + Send + 'static> MouseGestureCallback for F {}
+
+/// Scroll callback.
+pub trait ScrollCallback: Fn(ScrollDelta) + Sync + Send + 'static {}
+impl<F: Fn(ScrollDelta) + Sync + Send + 'static> ScrollCallback for F {}
+
 /// Mouse callbacks.
 #[derive(Default)]
 pub(crate) struct MouseCallbacks {
     pub mouse_move: Mutex<Vec<Weak<dyn MouseMoveCallback>>>,
     pub mouse_up: Mutex<Vec<Weak<dyn MouseButtonCallback>>>,
     pub mouse_down: Mutex<Vec<Weak<dyn MouseButtonCallback>>>,
+    pub scroll: Mutex<Vec<Weak<dyn ScrollCallback>>>,
+    pub mouse_motion: Mutex<Vec<Weak<dyn MouseMotionCallback>>>,
+    pub gestures: Mutex<Vec<(GestureKind, Weak<dyn MouseGestureCallback>)>>,
+    /// Fed by the mouse move, down and up events. The gestures they make are run after their
+    /// callbacks.
+    pub gesture_recognizer: Mutex<GestureRecognizer>,
 }
 
 impl MouseCallbacks {
@@ -45,7 +67,33 @@ impl MouseCallbacks {
         }
     }
 
+    pub fn push_scroll(&self, callback: &Arc<impl ScrollCallback>) {
+        if let Ok(mut callbacks) = self.scroll.lock() {
+            let callback = Arc::downgrade(callback);
+            callbacks.push(callback)
+        }
+    }
+
+    pub fn push_mouse_motion(&self, callback: &Arc<impl MouseMotionCallback>) {
+        if let Ok(mut callbacks) = self.mouse_motion.lock() {
+            let callback = Arc::downgrade(callback);
+            callbacks.push(callback)
+        }
+    }
+
+    pub fn push_gesture(&self, kind: GestureKind, callback: &Arc<impl MouseGestureCallback>) {
+        if let Ok(mut callbacks) = self.gestures.lock() {
+            let callback = Arc::downgrade(callback);
+            callbacks.push((kind, callback as Weak<dyn MouseGestureCallback>))
+        }
+    }
+
     pub fn run_mouse_move(&self, position: MousePosition) {
+        let gestures = self
+            .gesture_recognizer
+            .lock()
+            .map(|mut recognizer| recognizer.mouse_move(position))
+            .unwrap_or_default();
         if let Ok(mut callbacks) = self.mouse_move.lock() {
             utils::DrainFilter::drain_filter(callbacks.deref_mut(), |callback| {
                 callback.upgrade().is_none()
@@ -56,9 +104,13 @@ impl MouseCallbacks {
                 }
             }
         }
+        self.run_gestures(&gestures);
     }
 
     pub fn run_mouse_down(&self, button: MouseButton) {
+        if let Ok(mut recognizer) = self.gesture_recognizer.lock() {
+            recognizer.mouse_down(button);
+        }
         if let Ok(mut callbacks) = self.mouse_down.lock() {
             utils::DrainFilter::drain_filter(callbacks.deref_mut(), |callback| {
                 callback.upgrade().is_none()
@@ -71,7 +123,12 @@ impl MouseCallbacks {
         }
     }
 
-    pub fn run_mouse_up(&self, button: MouseButton) {
+    pub fn run_mouse_up(&self, button: MouseButton, released: &TimedEvent) {
+        let gestures = self
+            .gesture_recognizer
+            .lock()
+            .map(|mut recognizer| recognizer.mouse_up(button, released))
+            .unwrap_or_default();
         if let Ok(mut callbacks) = self.mouse_up.lock() {
             utils::DrainFilter::drain_filter(callbacks.deref_mut(), |callback| {
                 callback.upgrade().is_none()
@@ -82,5 +139,50 @@ impl MouseCallbacks {
                 }
             }
         }
+        self.run_gestures(&gestures);
+    }
+
+    fn run_gestures(&self, gestures: &[Option<Gesture>]) {
+        for gesture in gestures.iter().flatten() {
+            if let Ok(mut callbacks) = self.gestures.lock() {
+                utils::DrainFilter::drain_filter(callbacks.deref_mut(), |(_, callback)| {
+                    callback.upgrade().is_none()
+                });
+                for (kind, callback) in callbacks.iter() {
+                    if *kind != gesture.kind {
+                        continue;
+                    }
+                    if let Some(callback) = callback.upgrade() {
+                        callback((gesture.button, gesture.position));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn run_scroll(&self, delta: ScrollDelta) {
+        if let Ok(mut callbacks) = self.scroll.lock() {
+            utils::DrainFilter::drain_filter(callbacks.deref_mut(), |callback| {
+                callback.upgrade().is_none()
+            });
+            for callback in callbacks.iter() {
+                if let Some(callback) = callback.upgrade() {
+                    callback(delta);
+                }
+            }
+        }
+    }
+
+    pub fn run_mouse_motion(&self, delta: MouseDelta) {
+        if let Ok(mut callbacks) = self.mouse_motion.lock() {
+            utils::DrainFilter::drain_filter(callbacks.deref_mut(), |callback| {
+                callback.upgrade().is_none()
+            });
+            for callback in callbacks.iter() {
+                if let Some(callback) = callback.upgrade() {
+                    callback(delta);
+                }
+            }
+        }
     }
 }
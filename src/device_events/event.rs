@@ -0,0 +1,53 @@
+//! Device event description.
+
+use std::time::{Duration, Instant};
+use {Keycode, MouseButton, MouseDelta, MousePosition, ScrollDelta};
+
+/// A change of keyboard or mouse state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[non_exhaustive]
+pub enum DeviceEvent {
+    /// A key was pressed.
+    KeyDown(Keycode),
+    /// A key was released.
+    KeyUp(Keycode),
+    /// The mouse moved to a new position.
+    MouseMove(MousePosition),
+    /// A mouse button was pressed.
+    MouseDown(MouseButton),
+    /// A mouse button was released.
+    MouseUp(MouseButton),
+    /// The scroll wheel or the touchpad scrolled.
+    Scroll(ScrollDelta),
+    /// The mouse device moved, in device units before pointer acceleration. Reported even when
+    /// the pointer is stopped by the edge of the screen, by the XInput2 and evdev backends only.
+    MouseMotion(MouseDelta),
+}
+
+/// A [`DeviceEvent`] and when it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct TimedEvent {
+    /// The event.
+    pub event: DeviceEvent,
+    /// When the event was read from the backend. Polled backends report the time of the query
+    /// that observed the change. Not serialized: deserialized events are observed when they
+    /// are deserialized.
+    #[cfg_attr(feature = "serde", serde(skip, default = "Instant::now"))]
+    pub observed: Instant,
+    /// The timestamp the X server or the kernel attached to the event, if the backend provides
+    /// one. Only the differences between the device times of a single backend are meaningful.
+    pub device_time: Option<Duration>,
+}
+
+impl TimedEvent {
+    /// Creates an event observed now.
+    pub fn new(event: DeviceEvent, device_time: Option<Duration>) -> TimedEvent {
+        TimedEvent {
+            event,
+            observed: Instant::now(),
+            device_time,
+        }
+    }
+}
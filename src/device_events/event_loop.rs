@@ -1,143 +1,278 @@
-use super::{CallbackGuard, KeyboardCallbacks};
+#[cfg(feature = "stream")]
+use super::DeviceEventStream;
+use super::{CallbackGuard, EventListeners, GestureConfig};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
 use std::sync::{Arc, LazyLock, Mutex, Weak};
 use std::thread::{sleep, spawn, JoinHandle};
-use std::time::Duration;
-use MouseState;
-use {DeviceQuery, MouseCallbacks};
-use {DeviceState, Keycode};
-use {MouseButton, MousePosition};
+use std::time::{Duration, Instant};
+#[cfg(target_os = "linux")]
+use LinuxBackend;
+use {DeviceBackend, DeviceEvent, DeviceQueryError, TimedEvent};
+use {DeviceQuery, DeviceState, Keycode};
+use {KeySet, MouseState};
+use {MouseButton, MouseDelta, MousePosition, ScrollDelta};
+
+/// Creates the `DeviceState` the event loop thread queries.
+pub(crate) type BackendFactory =
+    Arc<dyn Fn() -> Result<DeviceState, DeviceQueryError> + Send + Sync>;
+
+/// How long event-driven backends block before the thread checks whether it should stop.
+const EVENT_WAIT: Duration = Duration::from_millis(100);
+
+/// The backend used by default: XInput2 raw events if available, polling Xlib otherwise.
+#[cfg(target_os = "linux")]
+pub(crate) fn default_backend() -> Result<DeviceState, DeviceQueryError> {
+    DeviceState::with_backend(LinuxBackend::XInput2).or_else(|_| DeviceState::try_new())
+}
+
+/// The backend used by default.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn default_backend() -> Result<DeviceState, DeviceQueryError> {
+    DeviceState::try_new()
+}
 
 pub(crate) struct EventLoop {
-    keyboard_callbacks: Arc<KeyboardCallbacks>,
-    mouse_callbacks: Arc<MouseCallbacks>,
-    _keyboard_thread: JoinHandle<()>,
-    _mouse_thread: JoinHandle<()>,
+    listeners: Arc<EventListeners>,
+    _thread: JoinHandle<()>,
+}
+
+/// Appends the key down and key up events for the keys that changed between two queries.
+pub(crate) fn diff_keys(events: &mut Vec<DeviceEvent>, prev_keys: &KeySet, keys: &KeySet) {
+    events.extend(keys.difference(prev_keys).iter().map(DeviceEvent::KeyDown));
+    events.extend(prev_keys.difference(keys).iter().map(DeviceEvent::KeyUp));
+}
+
+/// Appends the mouse events for the position and buttons that changed between two queries. The
+/// move comes first, so that buttons are reported where the pointer was when they were observed.
+pub(crate) fn diff_mouse(
+    events: &mut Vec<DeviceEvent>,
+    previous_mouse_state: &MouseState,
+    mouse_state: &MouseState,
+) {
+    if mouse_state.coords != previous_mouse_state.coords {
+        events.push(DeviceEvent::MouseMove(mouse_state.coords));
+    }
+    let (previous_buttons, buttons) = (
+        &previous_mouse_state.button_pressed,
+        &mouse_state.button_pressed,
+    );
+    events.extend(
+        buttons
+            .difference(previous_buttons)
+            .iter()
+            .map(DeviceEvent::MouseDown),
+    );
+    events.extend(
+        previous_buttons
+            .difference(buttons)
+            .iter()
+            .map(DeviceEvent::MouseUp),
+    );
+}
+
+/// Appends a mouse motion event, unless `delta` is zero.
+pub(crate) fn push_motion(events: &mut Vec<DeviceEvent>, delta: MouseDelta) {
+    if delta != (0, 0) {
+        events.push(DeviceEvent::MouseMotion(delta));
+    }
+}
+
+/// Appends a scroll event, unless `delta` is zero.
+pub(crate) fn push_scroll(events: &mut Vec<DeviceEvent>, delta: ScrollDelta) {
+    if !delta.is_zero() {
+        events.push(DeviceEvent::Scroll(delta));
+    }
 }
 
-fn keyboard_thread(callbacks: Weak<KeyboardCallbacks>, sleep_dur: Duration) -> JoinHandle<()> {
+/// Reads keyboard and mouse events from a single backend, so that listeners receive them in the
+/// order they happened. Whether the backend could be created is sent to `ready`.
+fn event_thread(
+    listeners: Weak<EventListeners>,
+    sleep_dur: Duration,
+    backend: BackendFactory,
+    ready: SyncSender<Result<(), DeviceQueryError>>,
+) -> JoinHandle<()> {
     spawn(move || {
-        let device_state = DeviceState::new();
-        let mut prev_keys = vec![];
-        while let Some(callbacks) = callbacks.upgrade() {
-            let keys = device_state.get_keys();
-            for key_state in keys.iter().copied() {
-                if !prev_keys.contains(&key_state) {
-                    callbacks.run_key_down(key_state);
-                }
+        let device_state = match backend() {
+            Ok(device_state) => {
+                let _ = ready.send(Ok(()));
+                device_state
             }
-            for key_state in prev_keys.drain(..) {
-                if !keys.contains(&key_state) {
-                    callbacks.run_key_up(key_state);
-                }
+            Err(error) => {
+                let _ = ready.send(Err(error));
+                return;
             }
-            prev_keys = keys;
-            sleep(sleep_dur);
+        };
+        if let Some(listeners) = listeners.upgrade() {
+            listeners.set_pointer_position(device_state.get_mouse().coords);
         }
-    })
-}
-
-fn mouse_thread(callbacks: Weak<MouseCallbacks>, sleep_dur: Duration) -> JoinHandle<()> {
-    spawn(move || {
-        let device_state = DeviceState::new();
+        let mut prev_keys = KeySet::new();
         let mut previous_mouse_state = MouseState::default();
-        while let Some(callbacks) = callbacks.upgrade() {
-            let mouse_state = device_state.get_mouse();
-            for (index, (previous_state, current_state)) in previous_mouse_state
-                .button_pressed
-                .iter()
-                .zip(mouse_state.button_pressed.iter())
-                .enumerate()
-            {
-                if !(*previous_state) && *current_state {
-                    callbacks.run_mouse_down(index);
-                } else if *previous_state && !(*current_state) {
-                    callbacks.run_mouse_up(index);
-                }
+        let mut events = vec![];
+        let mut timed_events = vec![];
+        while let Some(listeners) = listeners.upgrade() {
+            let event_driven = device_state.read_events(EVENT_WAIT, &mut timed_events);
+            if !event_driven {
+                let observed = Instant::now();
+                let keys = device_state.get_key_set();
+                diff_keys(&mut events, &prev_keys, &keys);
+                prev_keys = keys;
+                let mouse_state = device_state.get_mouse();
+                diff_mouse(&mut events, &previous_mouse_state, &mouse_state);
+                previous_mouse_state = mouse_state;
+                push_motion(&mut events, device_state.query_motion());
+                push_scroll(&mut events, device_state.query_scroll());
+                timed_events.extend(events.drain(..).map(|event| TimedEvent {
+                    event,
+                    observed,
+                    device_time: None,
+                }));
             }
-            if mouse_state.coords != previous_mouse_state.coords {
-                callbacks.run_mouse_move(mouse_state.coords);
+            for event in timed_events.drain(..) {
+                listeners.dispatch(event);
+            }
+            if !event_driven {
+                sleep(sleep_dur);
             }
-            previous_mouse_state = mouse_state;
-            sleep(sleep_dur);
         }
     })
 }
 
-impl Default for EventLoop {
-    fn default() -> Self {
-        Self::new(Duration::from_micros(100))
+impl EventLoop {
+    /// Starts the event thread, once its backend was created.
+    fn new(sleep_dur: Duration, backend: BackendFactory) -> Result<Self, DeviceQueryError> {
+        let listeners = Arc::new(EventListeners::default());
+        let (ready, backend_created) = sync_channel(1);
+        let _thread = event_thread(Arc::downgrade(&listeners), sleep_dur, backend, ready);
+        backend_created.recv().unwrap_or_else(|_| {
+            Err(DeviceQueryError::BackendUnsupported(String::from(
+                "the backend panicked",
+            )))
+        })?;
+        Ok(Self { listeners, _thread })
     }
-}
 
-impl EventLoop {
-    fn new(sleep_dur: Duration) -> Self {
-        let keyboard_callbacks = Arc::new(KeyboardCallbacks::default());
-        let mouse_callbacks = Arc::new(MouseCallbacks::default());
-        let _keyboard_thread = keyboard_thread(Arc::downgrade(&keyboard_callbacks), sleep_dur);
-        let _mouse_thread = mouse_thread(Arc::downgrade(&mouse_callbacks), sleep_dur);
-        Self {
-            keyboard_callbacks,
-            mouse_callbacks,
-            _keyboard_thread,
-            _mouse_thread,
-        }
+    pub fn subscribe(&mut self) -> Receiver<DeviceEvent> {
+        self.listeners.subscribe()
+    }
+
+    pub fn subscribe_timed(&mut self) -> Receiver<TimedEvent> {
+        self.listeners.subscribe_timed()
+    }
+
+    #[cfg(feature = "stream")]
+    pub fn stream(&mut self) -> DeviceEventStream {
+        self.listeners.stream()
+    }
+
+    pub fn on_event<Callback: Fn(TimedEvent) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<TimedEvent> {
+        self.listeners.on_event(callback)
     }
 
     pub fn on_key_down<Callback: Fn(Keycode) + Send + Sync + 'static>(
         &mut self,
         callback: Callback,
     ) -> CallbackGuard<Keycode> {
-        let _callback = Arc::new(callback);
-        self.keyboard_callbacks.push_key_down(&_callback);
-        CallbackGuard { _callback }
+        self.listeners.on_key_down(callback)
     }
 
     pub fn on_key_up<Callback: Fn(Keycode) + Send + Sync + 'static>(
         &mut self,
         callback: Callback,
     ) -> CallbackGuard<Keycode> {
-        let _callback = Arc::new(callback);
-        self.keyboard_callbacks.push_key_up(&_callback);
-        CallbackGuard { _callback }
+        self.listeners.on_key_up(callback)
     }
 
     pub fn on_mouse_move<Callback: Fn(MousePosition) + Send + Sync + 'static>(
         &mut self,
         callback: Callback,
     ) -> CallbackGuard<MousePosition> {
-        let _callback = Arc::new(callback);
-        self.mouse_callbacks.push_mouse_move(&_callback);
-        CallbackGuard { _callback }
+        self.listeners.on_mouse_move(callback)
     }
 
     pub fn on_mouse_up<Callback: Fn(MouseButton) + Send + Sync + 'static>(
         &mut self,
         callback: Callback,
     ) -> CallbackGuard<MouseButton> {
-        let _callback = Arc::new(callback);
-        self.mouse_callbacks.push_mouse_up(&_callback);
-        CallbackGuard { _callback }
+        self.listeners.on_mouse_up(callback)
     }
 
     pub fn on_mouse_down<Callback: Fn(MouseButton) + Send + Sync + 'static>(
         &mut self,
         callback: Callback,
     ) -> CallbackGuard<MouseButton> {
-        let _callback = Arc::new(callback);
-        self.mouse_callbacks.push_mouse_down(&_callback);
-        CallbackGuard { _callback }
+        self.listeners.on_mouse_down(callback)
+    }
+
+    pub fn on_mouse_motion<Callback: Fn(i32, i32) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseDelta> {
+        self.listeners.on_mouse_motion(callback)
+    }
+
+    pub fn on_scroll<Callback: Fn(ScrollDelta) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<ScrollDelta> {
+        self.listeners.on_scroll(callback)
+    }
+
+    pub fn on_click<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_click(callback)
+    }
+
+    pub fn on_double_click<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_double_click(callback)
+    }
+
+    pub fn on_drag_start<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_drag_start(callback)
+    }
+
+    pub fn on_drag_move<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_drag_move(callback)
+    }
+
+    pub fn on_drag_end<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &mut self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_drag_end(callback)
+    }
+
+    pub fn set_gesture_config(&mut self, config: GestureConfig) {
+        self.listeners.set_gesture_config(config)
     }
 }
 
-pub static EVENT_LOOP: LazyLock<Mutex<Option<EventLoop>>> = LazyLock::new(|| Default::default());
+pub static EVENT_LOOP: LazyLock<Mutex<Option<EventLoop>>> = LazyLock::new(Default::default);
 
-pub(crate) fn init_event_loop(sleep_dur: Duration) -> bool {
+pub(crate) fn init_event_loop(
+    sleep_dur: Duration,
+    backend: BackendFactory,
+) -> Result<(), DeviceQueryError> {
     let Ok(mut lock) = EVENT_LOOP.lock() else {
-        return false;
+        return Err(DeviceQueryError::LockPoisoned);
     };
     if lock.is_some() {
-        return false;
+        return Err(DeviceQueryError::EventLoopAlreadyRunning);
     }
-    *lock = Some(EventLoop::new(sleep_dur));
-    true
-}
\ No newline at end of file
+    *lock = Some(EventLoop::new(sleep_dur, backend)?);
+    Ok(())
+}
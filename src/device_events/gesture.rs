@@ -0,0 +1,186 @@
+//! Click, double-click and drag recognition from mouse button and move events.
+
+use std::time::Duration;
+use {MouseButton, MousePosition, TimedEvent};
+
+/// Thresholds of the click, double-click and drag recognition of an events handler, set with
+/// [`set_gesture_config`](crate::DeviceEvents::set_gesture_config).
+///
+/// Distances are measured on each axis separately, like the Windows double-click and drag
+/// rectangles: a distance of 4 allows the pointer to stay within a 9x9 pixels square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureConfig {
+    /// The longest time between the releases of the two clicks of a double-click.
+    pub double_click_time: Duration,
+    /// How far apart the two clicks of a double-click can be, in pixels.
+    pub double_click_distance: u32,
+    /// How far the pointer must move from where a button was pressed for a drag to start, in
+    /// pixels. Releasing the button before that is a click.
+    pub drag_distance: u32,
+}
+
+impl Default for GestureConfig {
+    /// The Windows defaults: 500 ms double-clicks, and 4 pixels for both distances.
+    fn default() -> GestureConfig {
+        GestureConfig {
+            double_click_time: Duration::from_millis(500),
+            double_click_distance: 4,
+            drag_distance: 4,
+        }
+    }
+}
+
+/// The kinds of gestures, each with its own callbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GestureKind {
+    Click,
+    DoubleClick,
+    DragStart,
+    DragMove,
+    DragEnd,
+}
+
+/// A recognized gesture: its button, and where the pointer was. Drags start where the button
+/// was pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Gesture {
+    pub kind: GestureKind,
+    pub button: MouseButton,
+    pub position: MousePosition,
+}
+
+impl Gesture {
+    fn new(kind: GestureKind, button: MouseButton, position: MousePosition) -> Gesture {
+        Gesture {
+            kind,
+            button,
+            position,
+        }
+    }
+}
+
+/// The button being held, from which a click or a drag is recognized.
+#[derive(Debug)]
+struct Press {
+    button: MouseButton,
+    origin: MousePosition,
+    dragging: bool,
+}
+
+/// A click that can become a double-click.
+#[derive(Debug)]
+struct Click {
+    button: MouseButton,
+    position: MousePosition,
+    released: TimedEvent,
+}
+
+/// Recognizes gestures from the mouse events of an events handler. Only the first button
+/// pressed makes a gesture: other buttons pressed while it is held are ignored.
+#[derive(Debug, Default)]
+pub(crate) struct GestureRecognizer {
+    config: GestureConfig,
+    position: MousePosition,
+    press: Option<Press>,
+    last_click: Option<Click>,
+}
+
+/// The time from `earlier` to `later`, from their device times if both have one.
+pub(super) fn time_between(earlier: &TimedEvent, later: &TimedEvent) -> Duration {
+    match (earlier.device_time, later.device_time) {
+        (Some(earlier), Some(later)) => later.saturating_sub(earlier),
+        _ => later.observed.saturating_duration_since(earlier.observed),
+    }
+}
+
+/// Whether `a` and `b` are at most `distance` apart on each axis.
+fn within(a: MousePosition, b: MousePosition, distance: u32) -> bool {
+    let distance = i64::from(distance);
+    (i64::from(a.0) - i64::from(b.0)).abs() <= distance
+        && (i64::from(a.1) - i64::from(b.1)).abs() <= distance
+}
+
+impl GestureRecognizer {
+    /// Sets where the pointer is, before any move was reported.
+    pub fn set_position(&mut self, position: MousePosition) {
+        self.position = position;
+    }
+
+    pub fn set_config(&mut self, config: GestureConfig) {
+        self.config = config;
+    }
+
+    pub fn mouse_down(&mut self, button: MouseButton) {
+        if self.press.is_none() {
+            self.press = Some(Press {
+                button,
+                origin: self.position,
+                dragging: false,
+            });
+        }
+    }
+
+    /// Returns the drag start and drag move gestures `position` makes.
+    pub fn mouse_move(&mut self, position: MousePosition) -> [Option<Gesture>; 2] {
+        self.position = position;
+        let Some(press) = self.press.as_mut() else {
+            return [None, None];
+        };
+        let mut start = None;
+        if !press.dragging {
+            if within(press.origin, position, self.config.drag_distance) {
+                return [None, None];
+            }
+            press.dragging = true;
+            start = Some(Gesture::new(
+                GestureKind::DragStart,
+                press.button,
+                press.origin,
+            ));
+        }
+        [
+            start,
+            Some(Gesture::new(GestureKind::DragMove, press.button, position)),
+        ]
+    }
+
+    /// Returns the click and double-click, or the drag end, releasing `button` makes. The time
+    /// between clicks is measured from the `released` events.
+    pub fn mouse_up(&mut self, button: MouseButton, released: &TimedEvent) -> [Option<Gesture>; 2] {
+        let position = self.position;
+        match self.press.take() {
+            Some(press) if press.button == button => {
+                if press.dragging {
+                    self.last_click = None;
+                    return [
+                        Some(Gesture::new(GestureKind::DragEnd, button, position)),
+                        None,
+                    ];
+                }
+
+                let double = self.last_click.take().is_some_and(|click| {
+                    click.button == button
+                        && time_between(&click.released, released) <= self.config.double_click_time
+                        && within(click.position, position, self.config.double_click_distance)
+                });
+                // The click after a double-click starts a new one.
+                if !double {
+                    self.last_click = Some(Click {
+                        button,
+                        position,
+                        released: *released,
+                    });
+                }
+                [
+                    Some(Gesture::new(GestureKind::Click, button, position)),
+                    Some(Gesture::new(GestureKind::DoubleClick, button, position))
+                        .filter(|_| double),
+                ]
+            }
+            press => {
+                self.press = press;
+                [None, None]
+            }
+        }
+    }
+}
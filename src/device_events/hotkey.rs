@@ -0,0 +1,264 @@
+//! Hotkeys: callbacks for key combinations.
+
+use super::{CallbackGuard, DeviceEvents};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use {Keycode, Modifier};
+
+/// Hotkey callback.
+pub trait HotkeyCallback: Fn(()) + Send + Sync + 'static {}
+impl<F: Fn(()) + Send + Sync + 'static> HotkeyCallback for F {}
+
+/// The left modifier for either side's modifier, any other key unchanged.
+fn left_side(key: Keycode) -> Keycode {
+    key.modifier().map_or(key, Modifier::left)
+}
+
+/// `key`, or its left side if sides are equivalent.
+fn side(key: Keycode, sides_equivalent: bool) -> Keycode {
+    if sides_equivalent {
+        left_side(key)
+    } else {
+        key
+    }
+}
+
+/// A key pressed while holding a set of modifiers, such as `Ctrl+Shift+K`.
+///
+/// Hotkeys are parsed from `+` separated key names, the last one being the key triggering the
+/// hotkey. Keys are named as in [`Keycode::from_str`], and `Ctrl`, `Control`, `Shift`, `Alt`,
+/// `Meta`, `Super` and `Cmd` are accepted as modifier names. Sided modifier names like `Ctrl`
+/// stand for the left key, which also matches the right one unless the [`HotkeyManager`]
+/// distinguishes sides.
+///
+/// ```
+/// use device_query::{Hotkey, Keycode};
+///
+/// let hotkey: Hotkey = "Ctrl+Shift+K".parse().unwrap();
+/// assert_eq!(hotkey.modifiers(), [Keycode::LControl, Keycode::LShift]);
+/// assert_eq!(hotkey.key(), Keycode::K);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    modifiers: Vec<Keycode>,
+    key: Keycode,
+}
+
+impl Hotkey {
+    /// Creates a hotkey triggered by pressing `key` while holding `modifiers`.
+    pub fn new(modifiers: &[Keycode], key: Keycode) -> Hotkey {
+        let mut hotkey_modifiers = vec![];
+        for modifier in modifiers {
+            if !hotkey_modifiers.contains(modifier) {
+                hotkey_modifiers.push(*modifier);
+            }
+        }
+        Hotkey {
+            modifiers: hotkey_modifiers,
+            key,
+        }
+    }
+
+    /// The modifiers to hold.
+    pub fn modifiers(&self) -> &[Keycode] {
+        &self.modifiers
+    }
+
+    /// The key triggering the hotkey.
+    pub fn key(&self) -> Keycode {
+        self.key
+    }
+
+    /// Whether `key` is the key triggering this hotkey, such as `RShift` for `Ctrl+Shift` when
+    /// sides are equivalent.
+    pub(crate) fn is_triggered_by(&self, key: Keycode, sides_equivalent: bool) -> bool {
+        side(key, sides_equivalent) == side(self.key, sides_equivalent)
+    }
+
+    /// Whether `held` are exactly the modifiers of this hotkey. Non-modifier keys are ignored.
+    pub(crate) fn matches(&self, held: &[Keycode], sides_equivalent: bool) -> bool {
+        let side = |key: Keycode| side(key, sides_equivalent);
+        let held_modifiers: Vec<Keycode> = held
+            .iter()
+            .copied()
+            .filter(|key| !self.is_triggered_by(*key, sides_equivalent) && key.is_modifier())
+            .map(side)
+            .collect();
+        let required: Vec<Keycode> = self.modifiers.iter().copied().map(side).collect();
+        required.iter().all(|key| held_modifiers.contains(key))
+            && held_modifiers.iter().all(|key| required.contains(key))
+    }
+}
+
+fn parse_key(name: &str) -> Result<Keycode, String> {
+    match name {
+        "Ctrl" | "Control" => Ok(Keycode::LControl),
+        "Shift" => Ok(Keycode::LShift),
+        "Alt" => Ok(Keycode::LAlt),
+        "Meta" | "Super" => Ok(Keycode::LMeta),
+        "Cmd" => Ok(Keycode::Command),
+        name => Keycode::from_str(name),
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split('+')
+            .map(|name| parse_key(name.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (key, modifiers) = keys.split_last().expect("split yields at least one name");
+        if let Some(key) = modifiers.iter().find(|key| !key.is_modifier()) {
+            return Err(format!("{} is not a modifier", key));
+        }
+        Ok(Hotkey::new(modifiers, *key))
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+struct RegisteredHotkey {
+    hotkey: Hotkey,
+    callback: Weak<dyn HotkeyCallback>,
+    /// Set while the key is held after triggering the hotkey, so it fires once per activation.
+    active: bool,
+}
+
+#[derive(Default)]
+struct HotkeyState {
+    held: Vec<Keycode>,
+    distinguish_sides: bool,
+    hotkeys: Vec<RegisteredHotkey>,
+}
+
+impl HotkeyState {
+    /// The callbacks of the hotkeys `key` triggers.
+    fn key_down(&mut self, key: Keycode) -> Vec<Arc<dyn HotkeyCallback>> {
+        if !self.held.contains(&key) {
+            self.held.push(key);
+        }
+        self.hotkeys
+            .retain(|registered| registered.callback.strong_count() > 0);
+        let mut triggered = vec![];
+        for registered in &mut self.hotkeys {
+            if registered.active
+                || !registered
+                    .hotkey
+                    .is_triggered_by(key, !self.distinguish_sides)
+            {
+                continue;
+            }
+            if registered
+                .hotkey
+                .matches(&self.held, !self.distinguish_sides)
+            {
+                registered.active = true;
+                triggered.extend(registered.callback.upgrade());
+            }
+        }
+        triggered
+    }
+
+    fn key_up(&mut self, key: Keycode) {
+        self.held.retain(|held| *held != key);
+        for registered in &mut self.hotkeys {
+            if registered
+                .hotkey
+                .is_triggered_by(key, !self.distinguish_sides)
+            {
+                registered.active = false;
+            }
+        }
+    }
+}
+
+/// Runs callbacks when key combinations are pressed, on top of the key callbacks of a
+/// [`DeviceEvents`] implementation.
+///
+/// A hotkey fires when its key goes down while exactly its modifiers are held, and fires again
+/// only after its key was released. Keys that aren't modifiers may be held as well.
+///
+/// ```no_run
+/// use device_query::{DeviceEventsHandler, HotkeyManager};
+/// use std::time::Duration;
+///
+/// let event_handler = DeviceEventsHandler::new(Duration::from_millis(10)).unwrap();
+/// let hotkeys = HotkeyManager::new(&event_handler);
+/// let _guard = hotkeys.register(&"Ctrl+Shift+K".parse().unwrap(), || println!("Ctrl+Shift+K"));
+///
+/// loop {}
+/// ```
+pub struct HotkeyManager {
+    state: Arc<Mutex<HotkeyState>>,
+    _key_down: CallbackGuard<Keycode>,
+    _key_up: CallbackGuard<Keycode>,
+}
+
+impl HotkeyManager {
+    /// Creates a manager listening to the key events of `events`. Hotkeys stop firing when the
+    /// manager is dropped.
+    pub fn new<E: DeviceEvents>(events: &E) -> HotkeyManager {
+        let state = Arc::new(Mutex::new(HotkeyState::default()));
+        let _key_down = events.on_key_down({
+            let state = state.clone();
+            move |key| {
+                let triggered = match state.lock() {
+                    Ok(mut state) => state.key_down(key),
+                    Err(_) => return,
+                };
+                // Called without the lock held, so that callbacks can register hotkeys.
+                for callback in triggered {
+                    callback(());
+                }
+            }
+        });
+        let _key_up = events.on_key_up({
+            let state = state.clone();
+            move |key| {
+                if let Ok(mut state) = state.lock() {
+                    state.key_up(key);
+                }
+            }
+        });
+        HotkeyManager {
+            state,
+            _key_down,
+            _key_up,
+        }
+    }
+
+    /// Sets whether left and right modifiers are told apart, so that `LControl+K` isn't
+    /// triggered by holding `RControl`. Sides are equivalent by default.
+    pub fn distinguish_sides(&self, distinguish_sides: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.distinguish_sides = distinguish_sides;
+        }
+    }
+
+    /// Registers a callback for `hotkey`, removed when the returned guard is dropped.
+    pub fn register<Callback: Fn() + Send + Sync + 'static>(
+        &self,
+        hotkey: &Hotkey,
+        callback: Callback,
+    ) -> CallbackGuard<()> {
+        let _callback = Arc::new(move |()| callback());
+        if let Ok(mut state) = self.state.lock() {
+            let callback = Arc::downgrade(&_callback);
+            state.hotkeys.push(RegisteredHotkey {
+                hotkey: hotkey.clone(),
+                callback,
+                active: false,
+            });
+        }
+        CallbackGuard { _callback }
+    }
+}
@@ -0,0 +1,222 @@
+//! Callbacks and subscribers notified of device events.
+
+#[cfg(feature = "stream")]
+use super::stream::{DeviceEventStream, StreamQueue};
+use super::{CallbackGuard, EventCallbacks, GestureConfig, GestureKind};
+use super::{KeyboardCallbacks, MouseCallbacks};
+use std::sync::mpsc::{channel, Receiver, Sender};
+#[cfg(feature = "stream")]
+use std::sync::Weak;
+use std::sync::{Arc, Mutex};
+use {DeviceEvent, Keycode, MouseButton, MouseDelta, MousePosition, ScrollDelta, TimedEvent};
+
+/// Everything listening to the events of an events handler.
+#[derive(Default)]
+pub(crate) struct EventListeners {
+    keyboard_callbacks: KeyboardCallbacks,
+    mouse_callbacks: MouseCallbacks,
+    event_callbacks: EventCallbacks,
+    subscribers: Mutex<Vec<Sender<DeviceEvent>>>,
+    timed_subscribers: Mutex<Vec<Sender<TimedEvent>>>,
+    #[cfg(feature = "stream")]
+    streams: Mutex<Vec<Weak<StreamQueue>>>,
+}
+
+impl EventListeners {
+    /// Runs the callbacks matching `timed`, then sends it to the subscribers.
+    pub fn dispatch(&self, timed: TimedEvent) {
+        let event = timed.event;
+        match event {
+            DeviceEvent::KeyDown(key) => self.keyboard_callbacks.run_key_down(key),
+            DeviceEvent::KeyUp(key) => self.keyboard_callbacks.run_key_up(key),
+            DeviceEvent::MouseMove(position) => self.mouse_callbacks.run_mouse_move(position),
+            DeviceEvent::MouseDown(button) => self.mouse_callbacks.run_mouse_down(button),
+            DeviceEvent::MouseUp(button) => self.mouse_callbacks.run_mouse_up(button, &timed),
+            DeviceEvent::Scroll(delta) => self.mouse_callbacks.run_scroll(delta),
+            DeviceEvent::MouseMotion(delta) => self.mouse_callbacks.run_mouse_motion(delta),
+        }
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            // Subscribers whose receiver was dropped are removed.
+            subscribers.retain(|subscriber| subscriber.send(event).is_ok());
+        }
+        self.event_callbacks.run_event(timed);
+        if let Ok(mut subscribers) = self.timed_subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(timed).is_ok());
+        }
+        #[cfg(feature = "stream")]
+        if let Ok(mut streams) = self.streams.lock() {
+            // Streams that were dropped are removed.
+            streams.retain(|stream| match stream.upgrade() {
+                Some(stream) => {
+                    stream.push(event);
+                    true
+                }
+                None => false,
+            });
+        }
+    }
+
+    pub fn subscribe(&self) -> Receiver<DeviceEvent> {
+        let (sender, receiver) = channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+
+    pub fn subscribe_timed(&self) -> Receiver<TimedEvent> {
+        let (sender, receiver) = channel();
+        if let Ok(mut subscribers) = self.timed_subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+
+    #[cfg(feature = "stream")]
+    pub fn stream(&self) -> DeviceEventStream {
+        let queue = Arc::new(StreamQueue::default());
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.push(Arc::downgrade(&queue));
+        }
+        DeviceEventStream::new(queue)
+    }
+
+    /// The number of streams registered, including dropped streams not removed by a dispatch
+    /// yet.
+    #[cfg(all(test, feature = "testing", feature = "stream"))]
+    pub fn stream_count(&self) -> usize {
+        self.streams.lock().map_or(0, |streams| streams.len())
+    }
+
+    pub fn on_event<Callback: Fn(TimedEvent) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<TimedEvent> {
+        let _callback = Arc::new(callback);
+        self.event_callbacks.push_event(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_key_down<Callback: Fn(Keycode) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<Keycode> {
+        let _callback = Arc::new(callback);
+        self.keyboard_callbacks.push_key_down(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_key_up<Callback: Fn(Keycode) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<Keycode> {
+        let _callback = Arc::new(callback);
+        self.keyboard_callbacks.push_key_up(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_mouse_move<Callback: Fn(MousePosition) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MousePosition> {
+        let _callback = Arc::new(callback);
+        self.mouse_callbacks.push_mouse_move(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_mouse_up<Callback: Fn(MouseButton) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseButton> {
+        let _callback = Arc::new(callback);
+        self.mouse_callbacks.push_mouse_up(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_mouse_down<Callback: Fn(MouseButton) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseButton> {
+        let _callback = Arc::new(callback);
+        self.mouse_callbacks.push_mouse_down(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_scroll<Callback: Fn(ScrollDelta) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<ScrollDelta> {
+        let _callback = Arc::new(callback);
+        self.mouse_callbacks.push_scroll(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_mouse_motion<Callback: Fn(i32, i32) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseDelta> {
+        let _callback = Arc::new(move |(dx, dy): MouseDelta| callback(dx, dy));
+        self.mouse_callbacks.push_mouse_motion(&_callback);
+        CallbackGuard { _callback }
+    }
+
+    fn on_gesture<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &self,
+        kind: GestureKind,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        let _callback = Arc::new(move |(button, position): (MouseButton, MousePosition)| {
+            callback(button, position)
+        });
+        self.mouse_callbacks.push_gesture(kind, &_callback);
+        CallbackGuard { _callback }
+    }
+
+    pub fn on_click<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.on_gesture(GestureKind::Click, callback)
+    }
+
+    pub fn on_double_click<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.on_gesture(GestureKind::DoubleClick, callback)
+    }
+
+    pub fn on_drag_start<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.on_gesture(GestureKind::DragStart, callback)
+    }
+
+    pub fn on_drag_move<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.on_gesture(GestureKind::DragMove, callback)
+    }
+
+    pub fn on_drag_end<Callback: Fn(MouseButton, MousePosition) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.on_gesture(GestureKind::DragEnd, callback)
+    }
+
+    /// Sets where the pointer is before any move is dispatched, where gestures start until then.
+    pub fn set_pointer_position(&self, position: MousePosition) {
+        if let Ok(mut recognizer) = self.mouse_callbacks.gesture_recognizer.lock() {
+            recognizer.set_position(position);
+        }
+    }
+
+    pub fn set_gesture_config(&self, config: GestureConfig) {
+        if let Ok(mut recognizer) = self.mouse_callbacks.gesture_recognizer.lock() {
+            recognizer.set_config(config);
+        }
+    }
+}
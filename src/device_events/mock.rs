@@ -0,0 +1,230 @@
+//! Synchronous events handler driven by a `MockDeviceState`.
+
+use super::event_loop::{diff_keys, diff_mouse, push_motion, push_scroll};
+#[cfg(feature = "stream")]
+use super::DeviceEventStream;
+use super::{CallbackGuard, DeviceEvents, EventListeners, GestureConfig};
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+use {DeviceBackend, DeviceEvent, MockDeviceState, TimedEvent};
+use {KeySet, Keycode, MouseButton, MouseDelta, MousePosition, MouseState, ScrollDelta};
+
+#[derive(Default)]
+struct PreviousState {
+    keys: KeySet,
+    mouse: MouseState,
+}
+
+/// Events handler that runs its callbacks on the calling thread when [`step`](Self::step) is
+/// called, instead of polling from background threads. Changes are detected with the same diffing
+/// the event loop uses, so tests observe exactly what `DeviceEventsHandler` would report.
+///
+/// Unlike `DeviceEventsHandler`, any number of handlers can exist at once.
+///
+/// ```
+/// use device_query::{DeviceEvents, Keycode, MockDeviceState, MockEventsHandler};
+/// use std::sync::{Arc, Mutex};
+///
+/// let mock = MockDeviceState::new();
+/// let handler = MockEventsHandler::new(mock.clone());
+/// let pressed = Arc::new(Mutex::new(vec![]));
+/// let _guard = handler.on_key_down({
+///     let pressed = pressed.clone();
+///     move |key| pressed.lock().unwrap().push(key)
+/// });
+///
+/// mock.press(Keycode::A);
+/// handler.step();
+/// handler.step();
+/// assert_eq!(*pressed.lock().unwrap(), vec![Keycode::A]);
+/// ```
+///
+/// Clicks and drags are recognized from the button and move events:
+///
+/// ```
+/// use device_query::{DeviceEvents, MockDeviceState, MockEventsHandler, MouseButton};
+/// use std::sync::{Arc, Mutex};
+///
+/// let mock = MockDeviceState::new();
+/// let handler = MockEventsHandler::new(mock.clone());
+/// let gestures = Arc::new(Mutex::new(vec![]));
+/// let record = |name| {
+///     let gestures = gestures.clone();
+///     move |button, position| gestures.lock().unwrap().push((name, button, position))
+/// };
+/// let _click = handler.on_click(record("click"));
+/// let _double_click = handler.on_double_click(record("double click"));
+/// let _drag_start = handler.on_drag_start(record("drag start"));
+/// let _drag_end = handler.on_drag_end(record("drag end"));
+///
+/// for _ in 0..2 {
+///     mock.press_button(MouseButton::Left);
+///     handler.step();
+///     mock.release_button(MouseButton::Left);
+///     handler.step();
+/// }
+/// mock.press_button(MouseButton::Left);
+/// handler.step();
+/// mock.move_to((40, 0));
+/// handler.step();
+/// mock.release_button(MouseButton::Left);
+/// handler.step();
+///
+/// let left = MouseButton::Left;
+/// assert_eq!(
+///     *gestures.lock().unwrap(),
+///     [
+///         ("click", left, (0, 0)),
+///         ("click", left, (0, 0)),
+///         ("double click", left, (0, 0)),
+///         ("drag start", left, (0, 0)),
+///         ("drag end", left, (40, 0)),
+///     ]
+/// );
+/// ```
+pub struct MockEventsHandler {
+    device_state: MockDeviceState,
+    listeners: EventListeners,
+    previous: Mutex<PreviousState>,
+}
+
+impl MockEventsHandler {
+    /// Creates a handler reporting the changes made to `device_state`.
+    pub fn new(device_state: MockDeviceState) -> MockEventsHandler {
+        let listeners = EventListeners::default();
+        listeners.set_pointer_position(device_state.query_pointer().coords);
+        MockEventsHandler {
+            device_state,
+            listeners,
+            previous: Mutex::new(PreviousState::default()),
+        }
+    }
+
+    /// Queries the mock once and notifies the listeners of everything that changed since the
+    /// previous step, like one iteration of the event loop thread.
+    pub fn step(&self) {
+        let mut previous = self.previous.lock().expect("Couldn't lock previous state");
+        let mut events = vec![];
+        let keys = self.device_state.query_key_set();
+        diff_keys(&mut events, &previous.keys, &keys);
+        previous.keys = keys;
+        let mouse = self.device_state.query_pointer();
+        diff_mouse(&mut events, &previous.mouse, &mouse);
+        previous.mouse = mouse;
+        push_motion(&mut events, self.device_state.query_motion());
+        push_scroll(&mut events, self.device_state.query_scroll());
+        for event in events {
+            self.listeners.dispatch(TimedEvent::new(event, None));
+        }
+    }
+}
+
+impl DeviceEvents for MockEventsHandler {
+    fn subscribe(&self) -> Receiver<DeviceEvent> {
+        self.listeners.subscribe()
+    }
+
+    fn subscribe_timed(&self) -> Receiver<TimedEvent> {
+        self.listeners.subscribe_timed()
+    }
+
+    #[cfg(feature = "stream")]
+    fn stream(&self) -> DeviceEventStream {
+        self.listeners.stream()
+    }
+
+    fn on_event<Callback: Fn(TimedEvent) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<TimedEvent> {
+        self.listeners.on_event(callback)
+    }
+
+    fn on_key_down<Callback: Fn(Keycode) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<Keycode> {
+        self.listeners.on_key_down(callback)
+    }
+
+    fn on_key_up<Callback: Fn(Keycode) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<Keycode> {
+        self.listeners.on_key_up(callback)
+    }
+
+    fn on_mouse_move<Callback: Fn(MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MousePosition> {
+        self.listeners.on_mouse_move(callback)
+    }
+
+    fn on_mouse_down<Callback: Fn(MouseButton) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseButton> {
+        self.listeners.on_mouse_down(callback)
+    }
+
+    fn on_mouse_up<Callback: Fn(MouseButton) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseButton> {
+        self.listeners.on_mouse_up(callback)
+    }
+
+    fn on_scroll<Callback: Fn(ScrollDelta) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<ScrollDelta> {
+        self.listeners.on_scroll(callback)
+    }
+
+    fn on_mouse_motion<Callback: Fn(i32, i32) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseDelta> {
+        self.listeners.on_mouse_motion(callback)
+    }
+
+    fn set_gesture_config(&self, config: GestureConfig) {
+        self.listeners.set_gesture_config(config)
+    }
+
+    fn on_click<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_click(callback)
+    }
+
+    fn on_double_click<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_double_click(callback)
+    }
+
+    fn on_drag_start<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_drag_start(callback)
+    }
+
+    fn on_drag_move<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_drag_move(callback)
+    }
+
+    fn on_drag_end<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        self.listeners.on_drag_end(callback)
+    }
+}
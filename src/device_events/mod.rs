@@ -1,21 +1,64 @@
 //! Devices events listeners.
 
 mod callback;
+mod event;
 mod event_loop;
+mod gesture;
+mod hotkey;
+mod listeners;
+#[cfg(feature = "testing")]
+mod mock;
+mod sequence;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(all(test, feature = "testing"))]
+mod tests;
+mod text;
 mod utils;
 
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
 use std::time::Duration;
 
-use crate::MousePosition;
+use crate::{MouseDelta, MousePosition, ScrollDelta};
 
 pub use self::callback::*;
+pub use self::event::{DeviceEvent, TimedEvent};
 use self::event_loop::*;
+pub use self::gesture::GestureConfig;
+use self::gesture::GestureKind;
+pub use self::hotkey::{Hotkey, HotkeyCallback, HotkeyManager};
+use self::listeners::EventListeners;
+#[cfg(feature = "testing")]
+pub use self::mock::MockEventsHandler;
+pub use self::sequence::{KeySequence, PrefixCallback, SequenceMatcher};
+#[cfg(feature = "stream")]
+pub use self::stream::DeviceEventStream;
+pub use self::text::TextInput;
 
 use Keycode;
 use MouseButton;
+use {DeviceBackend, DeviceQueryError, DeviceState};
 
 /// All the supported devices events.
 pub trait DeviceEvents {
+    /// Subscribe to all events. The receiver gets every event in the order it happened, and is
+    /// unsubscribed when dropped.
+    fn subscribe(&self) -> Receiver<DeviceEvent>;
+
+    /// Like [`subscribe`](Self::subscribe), with the time of each event.
+    fn subscribe_timed(&self) -> Receiver<TimedEvent>;
+
+    /// Like [`subscribe`](Self::subscribe), as an async stream.
+    #[cfg(feature = "stream")]
+    fn stream(&self) -> DeviceEventStream;
+
+    /// Register a callback receiving every event with its time.
+    fn on_event<Callback: Fn(TimedEvent) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<TimedEvent>;
+
     /// Register an on key down event callback.
     fn on_key_down<Callback: Fn(Keycode) + Sync + Send + 'static>(
         &self,
@@ -42,15 +85,95 @@ pub trait DeviceEvents {
         &self,
         callback: Callback,
     ) -> CallbackGuard<MouseButton>;
+
+    /// Register an on scroll event callback.
+    fn on_scroll<Callback: Fn(ScrollDelta) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<ScrollDelta>;
+
+    /// Register an on mouse motion event callback, receiving the horizontal and vertical motion
+    /// of the mouse device before pointer acceleration. See
+    /// [`DeviceEvent::MouseMotion`](crate::DeviceEvent::MouseMotion).
+    fn on_mouse_motion<Callback: Fn(i32, i32) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseDelta>;
+
+    /// Set the thresholds of the click, double-click and drag recognition. Clicks and drags are
+    /// recognized from the mouse button and move events, only for the first button pressed.
+    fn set_gesture_config(&self, config: GestureConfig);
+
+    /// Register an on click event callback, receiving the button and where it was released
+    /// without having been dragged.
+    fn on_click<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)>;
+    /// Register an on double-click event callback, called after the click callbacks of the
+    /// second click.
+    fn on_double_click<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)>;
+    /// Register an on drag start event callback, receiving the button and where it was pressed.
+    fn on_drag_start<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)>;
+    /// Register an on drag move event callback, receiving the button and the pointer position.
+    fn on_drag_move<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)>;
+    /// Register an on drag end event callback, receiving the button and where it was released.
+    fn on_drag_end<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)>;
 }
 
 pub struct DeviceEventsHandler;
 
 impl DeviceEventsHandler {
     /// Attempts to start event loop with the given sleep duration.
+    /// On Linux, XInput2 raw events are used instead of polling when the X server supports them.
     /// Returns None if the event loop is already running.
     pub fn new(sleep_dur: Duration) -> Option<Self> {
-        event_loop::init_event_loop(sleep_dur).then_some(DeviceEventsHandler)
+        Self::try_new(sleep_dur).ok()
+    }
+
+    /// Attempts to start event loop with the given sleep duration, like [`new`](Self::new).
+    /// Fails if the event loop is already running or if the platform backend can't be created.
+    pub fn try_new(sleep_dur: Duration) -> Result<Self, DeviceQueryError> {
+        event_loop::init_event_loop(sleep_dur, Arc::new(default_backend))?;
+        Ok(DeviceEventsHandler)
+    }
+
+    /// Attempts to start event loop with the given sleep duration, reading input from the
+    /// backend created by `backend`. The event loop thread calls `backend` once, on that
+    /// thread.
+    /// Fails if the event loop is already running or if `backend` fails.
+    pub fn with_backend<B, F>(sleep_dur: Duration, backend: F) -> Result<Self, DeviceQueryError>
+    where
+        B: DeviceBackend + 'static,
+        F: Fn() -> Result<B, DeviceQueryError> + Send + Sync + 'static,
+    {
+        let backend = move || backend().map(DeviceState::from_backend);
+        event_loop::init_event_loop(sleep_dur, Arc::new(backend))?;
+        Ok(DeviceEventsHandler)
+    }
+
+    /// Attempts to start event loop with the given sleep duration, reading input from
+    /// `device_state`, which can still be queried from other threads.
+    /// Fails if the event loop is already running.
+    pub fn with_device_state(
+        sleep_dur: Duration,
+        device_state: DeviceState,
+    ) -> Result<Self, DeviceQueryError> {
+        let backend = move || Ok(device_state.clone());
+        event_loop::init_event_loop(sleep_dur, Arc::new(backend))?;
+        Ok(DeviceEventsHandler)
     }
 }
 
@@ -69,6 +192,26 @@ macro_rules! get_event_loop {
 }
 
 impl DeviceEvents for DeviceEventsHandler {
+    fn subscribe(&self) -> Receiver<DeviceEvent> {
+        get_event_loop!().subscribe()
+    }
+
+    fn subscribe_timed(&self) -> Receiver<TimedEvent> {
+        get_event_loop!().subscribe_timed()
+    }
+
+    #[cfg(feature = "stream")]
+    fn stream(&self) -> DeviceEventStream {
+        get_event_loop!().stream()
+    }
+
+    fn on_event<Callback: Fn(TimedEvent) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<TimedEvent> {
+        get_event_loop!().on_event(callback)
+    }
+
     fn on_key_down<Callback: Fn(Keycode) + Sync + Send + 'static>(
         &self,
         callback: Callback,
@@ -103,4 +246,57 @@ impl DeviceEvents for DeviceEventsHandler {
     ) -> CallbackGuard<MouseButton> {
         get_event_loop!().on_mouse_up(callback)
     }
+
+    fn on_scroll<Callback: Fn(ScrollDelta) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<ScrollDelta> {
+        get_event_loop!().on_scroll(callback)
+    }
+
+    fn on_mouse_motion<Callback: Fn(i32, i32) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<MouseDelta> {
+        get_event_loop!().on_mouse_motion(callback)
+    }
+
+    fn set_gesture_config(&self, config: GestureConfig) {
+        get_event_loop!().set_gesture_config(config)
+    }
+
+    fn on_click<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        get_event_loop!().on_click(callback)
+    }
+
+    fn on_double_click<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        get_event_loop!().on_double_click(callback)
+    }
+
+    fn on_drag_start<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        get_event_loop!().on_drag_start(callback)
+    }
+
+    fn on_drag_move<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        get_event_loop!().on_drag_move(callback)
+    }
+
+    fn on_drag_end<Callback: Fn(MouseButton, MousePosition) + Sync + Send + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<(MouseButton, MousePosition)> {
+        get_event_loop!().on_drag_end(callback)
+    }
 }
@@ -0,0 +1,461 @@
+//! Key sequences: callbacks for successive hotkeys, like Emacs' `C-x C-s`.
+
+use super::gesture::time_between;
+use super::{CallbackGuard, DeviceEvents, Hotkey, HotkeyCallback};
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use {DeviceEvent, Keycode, TimedEvent};
+
+/// Pending prefix callback.
+pub trait PrefixCallback: Fn(KeySequence) + Send + Sync + 'static {}
+impl<F: Fn(KeySequence) + Send + Sync + 'static> PrefixCallback for F {}
+
+/// Hotkeys pressed one after the other, such as `Ctrl+X Ctrl+S`.
+///
+/// Sequences are parsed from whitespace separated hotkeys, each parsed as a [`Hotkey`].
+///
+/// ```
+/// use device_query::{Hotkey, KeySequence};
+///
+/// let sequence: KeySequence = "Ctrl+X Ctrl+S".parse().unwrap();
+/// assert_eq!(sequence.steps()[1], "Ctrl+S".parse::<Hotkey>().unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence {
+    steps: Vec<Hotkey>,
+}
+
+impl KeySequence {
+    /// Creates a sequence of `steps`. Returns `None` if `steps` is empty.
+    pub fn new(steps: &[Hotkey]) -> Option<KeySequence> {
+        if steps.is_empty() {
+            return None;
+        }
+        Some(KeySequence {
+            steps: steps.to_vec(),
+        })
+    }
+
+    /// The hotkeys to press, in order.
+    pub fn steps(&self) -> &[Hotkey] {
+        &self.steps
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let steps = s
+            .split_whitespace()
+            .map(Hotkey::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        KeySequence::new(&steps).ok_or_else(|| String::from("empty key sequence"))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// The root of the trie.
+const ROOT: usize = 0;
+
+/// A trie node: the sequences registered so far share the nodes of their common prefix.
+#[derive(Default)]
+struct Node {
+    children: Vec<(Hotkey, usize)>,
+    callbacks: Vec<Weak<dyn HotkeyCallback>>,
+}
+
+/// The callbacks to run after a key down, once the lock is released.
+#[derive(Default)]
+struct Step {
+    completed: Vec<Arc<dyn HotkeyCallback>>,
+    prefix: Option<KeySequence>,
+}
+
+struct SequenceState {
+    held: Vec<Keycode>,
+    distinguish_sides: bool,
+    timeout: Duration,
+    nodes: Vec<Node>,
+    prefix_callbacks: Vec<Weak<dyn PrefixCallback>>,
+    /// The node reached by the pending prefix.
+    current: usize,
+    pending: Vec<Hotkey>,
+    last_step: Option<TimedEvent>,
+}
+
+impl SequenceState {
+    fn new(timeout: Duration) -> SequenceState {
+        SequenceState {
+            held: vec![],
+            distinguish_sides: false,
+            timeout,
+            nodes: vec![Node::default()],
+            prefix_callbacks: vec![],
+            current: ROOT,
+            pending: vec![],
+            last_step: None,
+        }
+    }
+
+    /// Cancels the pending prefix, and removes the sequences that were dropped meanwhile.
+    fn reset(&mut self) {
+        self.current = ROOT;
+        self.pending.clear();
+        self.last_step = None;
+        self.prune();
+    }
+
+    /// Cancels the pending prefix if more than the timeout elapsed since its last step, as
+    /// measured by `since_last_step`.
+    fn reset_if_timed_out(&mut self, since_last_step: impl FnOnce(&TimedEvent) -> Duration) {
+        if let Some(last_step) = &self.last_step {
+            if since_last_step(last_step) > self.timeout {
+                self.reset();
+            }
+        }
+    }
+
+    /// Removes the nodes left without callbacks after them, once no prefix is pending.
+    fn prune(&mut self) {
+        if self.current != ROOT {
+            return;
+        }
+        let mut nodes = vec![];
+        self.move_live(ROOT, &mut nodes);
+        self.nodes = nodes;
+    }
+
+    /// Moves `node` and its live children to `nodes`. Returns the index of `node` there.
+    fn move_live(&mut self, node: usize, nodes: &mut Vec<Node>) -> usize {
+        let mut moved = mem::take(&mut self.nodes[node]);
+        moved
+            .callbacks
+            .retain(|callback| callback.strong_count() > 0);
+        let children = mem::take(&mut moved.children);
+        let index = nodes.len();
+        nodes.push(moved);
+        for (hotkey, child) in children {
+            if self.is_live(child) {
+                let child = self.move_live(child, nodes);
+                nodes[index].children.push((hotkey, child));
+            }
+        }
+        index
+    }
+
+    /// Whether a callback of `node` or of a node after it is still registered.
+    fn is_live(&self, node: usize) -> bool {
+        let node = &self.nodes[node];
+        node.callbacks
+            .iter()
+            .any(|callback| callback.strong_count() > 0)
+            || node.children.iter().any(|(_, child)| self.is_live(*child))
+    }
+
+    fn has_live_children(&self, node: usize) -> bool {
+        self.nodes[node]
+            .children
+            .iter()
+            .any(|(_, child)| self.is_live(*child))
+    }
+
+    /// The child of `node` matching `key` pressed with the held modifiers.
+    fn find_child(&self, node: usize, key: Keycode) -> Option<(Hotkey, usize)> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|(hotkey, child)| {
+                hotkey.is_triggered_by(key, !self.distinguish_sides)
+                    && hotkey.matches(&self.held, !self.distinguish_sides)
+                    && self.is_live(*child)
+            })
+            .cloned()
+    }
+
+    fn key_down(&mut self, key: Keycode, event: &TimedEvent) -> Step {
+        if !self.held.contains(&key) {
+            self.held.push(key);
+        }
+        self.reset_if_timed_out(|last_step| time_between(last_step, event));
+        let mut next = self.find_child(self.current, key);
+        if next.is_none() && !key.is_modifier() && self.current != ROOT {
+            // A wrong key cancels the pending prefix, and may start another sequence.
+            self.reset();
+            next = self.find_child(ROOT, key);
+        }
+        let Some((hotkey, node)) = next else {
+            return Step::default();
+        };
+        self.current = node;
+        self.pending.push(hotkey);
+        self.last_step = Some(*event);
+
+        self.nodes[node]
+            .callbacks
+            .retain(|callback| callback.strong_count() > 0);
+        let completed = self.nodes[node]
+            .callbacks
+            .iter()
+            .filter_map(|callback| callback.upgrade())
+            .collect();
+        let prefix = if self.has_live_children(node) {
+            KeySequence::new(&self.pending)
+        } else {
+            self.reset();
+            None
+        };
+        Step { completed, prefix }
+    }
+
+    fn key_up(&mut self, key: Keycode) {
+        self.held.retain(|held| *held != key);
+    }
+
+    /// The node reached by `sequence`, created if needed.
+    fn insert(&mut self, sequence: &KeySequence) -> usize {
+        self.prune();
+        let mut node = ROOT;
+        for step in &sequence.steps {
+            let existing = self.nodes[node]
+                .children
+                .iter()
+                .find(|(hotkey, _)| hotkey == step)
+                .map(|(_, child)| *child);
+            node = match existing {
+                Some(child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.push((step.clone(), child));
+                    child
+                }
+            };
+        }
+        node
+    }
+}
+
+/// Runs callbacks when key sequences are pressed, on top of the key callbacks of a
+/// [`DeviceEvents`] implementation.
+///
+/// Each step of a sequence matches like a [`Hotkey`]. The pending prefix is cancelled when more
+/// than the timeout elapses between two steps, or when a key that doesn't continue any sequence
+/// is pressed; that key may then start a sequence itself. Modifiers pressed on their own never
+/// cancel the prefix.
+///
+/// A sequence that is the prefix of another one fires as soon as it is completed, and the longer
+/// sequence can still be continued.
+///
+/// ```no_run
+/// use device_query::{DeviceEventsHandler, SequenceMatcher};
+/// use std::time::Duration;
+///
+/// let event_handler = DeviceEventsHandler::new(Duration::from_millis(10)).unwrap();
+/// let sequences = SequenceMatcher::new(&event_handler, Duration::from_secs(1));
+/// let _save = sequences.register(&"Ctrl+X Ctrl+S".parse().unwrap(), || println!("Save"));
+/// let _quit = sequences.register(&"Ctrl+X Ctrl+C".parse().unwrap(), || println!("Quit"));
+/// let _prefix = sequences.on_prefix(|prefix| println!("{}-", prefix));
+///
+/// loop {}
+/// ```
+pub struct SequenceMatcher {
+    state: Arc<Mutex<SequenceState>>,
+    _events: CallbackGuard<TimedEvent>,
+}
+
+impl SequenceMatcher {
+    /// Creates a matcher listening to the key events of `events`, allowing up to `timeout`
+    /// between two steps of a sequence. Sequences stop firing when the matcher is dropped.
+    pub fn new<E: DeviceEvents>(events: &E, timeout: Duration) -> SequenceMatcher {
+        let state = Arc::new(Mutex::new(SequenceState::new(timeout)));
+        // Timed events, so that steps are timed from when the keys were pressed.
+        let _events = events.on_event({
+            let state = state.clone();
+            move |event| {
+                let key = match event.event {
+                    DeviceEvent::KeyDown(key) => key,
+                    DeviceEvent::KeyUp(key) => {
+                        if let Ok(mut state) = state.lock() {
+                            state.key_up(key);
+                        }
+                        return;
+                    }
+                    _ => return,
+                };
+                let (step, prefix_callbacks) = match state.lock() {
+                    Ok(mut state) => {
+                        let step = state.key_down(key, &event);
+                        state
+                            .prefix_callbacks
+                            .retain(|callback| callback.strong_count() > 0);
+                        let prefix_callbacks: Vec<_> = state
+                            .prefix_callbacks
+                            .iter()
+                            .filter_map(|callback| callback.upgrade())
+                            .collect();
+                        (step, prefix_callbacks)
+                    }
+                    Err(_) => return,
+                };
+                // Called without the lock held, so that callbacks can register sequences.
+                for callback in step.completed {
+                    callback(());
+                }
+                if let Some(prefix) = step.prefix {
+                    for callback in prefix_callbacks {
+                        callback(prefix.clone());
+                    }
+                }
+            }
+        });
+        SequenceMatcher { state, _events }
+    }
+
+    /// Sets whether left and right modifiers are told apart, as in
+    /// [`HotkeyManager::distinguish_sides`](super::HotkeyManager::distinguish_sides).
+    pub fn distinguish_sides(&self, distinguish_sides: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.distinguish_sides = distinguish_sides;
+        }
+    }
+
+    /// Registers a callback for `sequence`, removed when the returned guard is dropped.
+    pub fn register<Callback: Fn() + Send + Sync + 'static>(
+        &self,
+        sequence: &KeySequence,
+        callback: Callback,
+    ) -> CallbackGuard<()> {
+        let _callback = Arc::new(move |()| callback());
+        if let Ok(mut state) = self.state.lock() {
+            let node = state.insert(sequence);
+            let callback = Arc::downgrade(&_callback);
+            state.nodes[node].callbacks.push(callback);
+        }
+        CallbackGuard { _callback }
+    }
+
+    /// Registers a callback receiving the pending prefix each time it grows, removed when the
+    /// returned guard is dropped.
+    pub fn on_prefix<Callback: Fn(KeySequence) + Send + Sync + 'static>(
+        &self,
+        callback: Callback,
+    ) -> CallbackGuard<KeySequence> {
+        let _callback = Arc::new(callback);
+        if let Ok(mut state) = self.state.lock() {
+            let callback = Arc::downgrade(&_callback);
+            state.prefix_callbacks.push(callback);
+        }
+        CallbackGuard { _callback }
+    }
+
+    /// The prefix of a registered sequence pressed so far, or `None` if no sequence is under
+    /// way.
+    pub fn pending(&self) -> Option<KeySequence> {
+        let mut state = self.state.lock().ok()?;
+        state.reset_if_timed_out(|last_step| last_step.observed.elapsed());
+        KeySequence::new(&state.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn register(state: &mut SequenceState, sequence: &str) -> Arc<dyn HotkeyCallback> {
+        let callback: Arc<dyn HotkeyCallback> = Arc::new(|()| {});
+        let node = state.insert(&sequence.parse().unwrap());
+        state.nodes[node].callbacks.push(Arc::downgrade(&callback));
+        callback
+    }
+
+    /// Presses `keys` then releases them, the last one at `event`. Returns whether a sequence
+    /// was completed.
+    fn press(state: &mut SequenceState, keys: &[Keycode], event: &TimedEvent) -> bool {
+        let mut step = Step::default();
+        for key in keys {
+            step = state.key_down(*key, event);
+        }
+        for key in keys {
+            state.key_up(*key);
+        }
+        !step.completed.is_empty()
+    }
+
+    fn key_down_at(observed: Instant, device_time: Option<u64>) -> TimedEvent {
+        TimedEvent {
+            event: DeviceEvent::KeyDown(Keycode::A),
+            observed,
+            device_time: device_time.map(Duration::from_millis),
+        }
+    }
+
+    #[test]
+    fn dropped_sequences_are_pruned() {
+        let mut state = SequenceState::new(Duration::from_secs(60));
+        let save = register(&mut state, "Ctrl+X Ctrl+S");
+        let quit = register(&mut state, "Ctrl+X Ctrl+C");
+        assert_eq!(state.nodes.len(), 4);
+
+        drop(quit);
+        let open = register(&mut state, "Ctrl+O");
+        assert_eq!(state.nodes.len(), 4);
+        drop(save);
+        drop(open);
+        state.reset();
+        assert_eq!(state.nodes.len(), 1);
+        assert!(state.nodes[ROOT].children.is_empty());
+    }
+
+    #[test]
+    fn sequences_still_match_after_pruning() {
+        let mut state = SequenceState::new(Duration::from_secs(60));
+        let now = key_down_at(Instant::now(), None);
+        let dropped = register(&mut state, "Ctrl+A Ctrl+B");
+        let _save = register(&mut state, "Ctrl+X Ctrl+S");
+        drop(dropped);
+        let _quit = register(&mut state, "Ctrl+X Ctrl+C");
+        assert_eq!(state.nodes.len(), 4);
+
+        assert!(!press(&mut state, &[Keycode::LControl, Keycode::X], &now));
+        assert!(press(&mut state, &[Keycode::LControl, Keycode::C], &now));
+        assert!(!press(&mut state, &[Keycode::LControl, Keycode::A], &now));
+        assert!(!press(&mut state, &[Keycode::LControl, Keycode::B], &now));
+    }
+
+    #[test]
+    fn steps_are_timed_by_the_device() {
+        let mut state = SequenceState::new(Duration::from_secs(1));
+        let _save = register(&mut state, "Ctrl+X Ctrl+S");
+        let now = Instant::now();
+        let ctrl_x = [Keycode::LControl, Keycode::X];
+        let ctrl_s = [Keycode::LControl, Keycode::S];
+
+        // Dispatched right away, but pressed two seconds apart on the device.
+        assert!(!press(&mut state, &ctrl_x, &key_down_at(now, Some(0))));
+        assert!(!press(&mut state, &ctrl_s, &key_down_at(now, Some(2000))));
+
+        // Dispatched two seconds apart, but pressed right after each other.
+        let later = now + Duration::from_secs(2);
+        assert!(!press(&mut state, &ctrl_x, &key_down_at(now, Some(3000))));
+        assert!(press(&mut state, &ctrl_s, &key_down_at(later, Some(3100))));
+
+        // Without device times, steps are timed from when they were observed.
+        assert!(!press(&mut state, &ctrl_x, &key_down_at(now, None)));
+        assert!(!press(&mut state, &ctrl_s, &key_down_at(later, None)));
+    }
+}
@@ -0,0 +1,88 @@
+//! Async stream of device events.
+
+extern crate futures_core;
+
+use self::futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use DeviceEvent;
+
+#[derive(Default)]
+struct Queue {
+    events: VecDeque<DeviceEvent>,
+    waker: Option<Waker>,
+}
+
+/// The state shared between a stream and the listeners feeding it.
+#[derive(Default)]
+pub(crate) struct StreamQueue {
+    queue: Mutex<Queue>,
+}
+
+impl StreamQueue {
+    /// Queues `event` and wakes the task polling the stream.
+    pub fn push(&self, event: DeviceEvent) {
+        let waker = {
+            let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+            queue.events.push_back(event);
+            queue.waker.take()
+        };
+        // Woken without the lock held, since the waker may poll the stream right away.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A [`Stream`] of every device event, in the order they happened.
+///
+/// The stream never ends. Dropping it unsubscribes it from the events handler.
+///
+/// Like the receivers of [`subscribe`](crate::DeviceEvents::subscribe), the stream buffers
+/// every event until it is polled, without bound: a stream that isn't polled should be dropped.
+///
+/// ```no_run
+/// # extern crate futures;
+/// # extern crate device_query;
+/// use device_query::{DeviceEvents, DeviceEventsHandler};
+/// use futures::executor::block_on_stream;
+/// use std::time::Duration;
+///
+/// # fn main() {
+/// let event_handler = DeviceEventsHandler::new(Duration::from_millis(100)).unwrap();
+/// for event in block_on_stream(event_handler.stream()) {
+///     println!("{:?}", event);
+/// }
+/// # }
+/// ```
+pub struct DeviceEventStream {
+    queue: Arc<StreamQueue>,
+}
+
+impl DeviceEventStream {
+    pub(crate) fn new(queue: Arc<StreamQueue>) -> DeviceEventStream {
+        DeviceEventStream { queue }
+    }
+}
+
+impl Stream for DeviceEventStream {
+    type Item = DeviceEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<DeviceEvent>> {
+        // The queue is never left half updated, so a poisoned lock is still usable.
+        let mut queue = self
+            .queue
+            .queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match queue.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,776 @@
+//! Event handling driven through `MockEventsHandler`, and the start of the event loop.
+
+use super::{DeviceEvents, DeviceEventsHandler, EventListeners, GestureConfig, HotkeyManager};
+use super::{MockEventsHandler, SequenceMatcher};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::Duration;
+use DeviceQueryError;
+use ScrollDelta;
+use TimedEvent;
+use {DeviceEvent, DeviceSimulate, Keycode, MockDeviceState, MouseButton, MousePosition};
+
+/// What the callbacks of a test saw, in order.
+#[derive(Clone)]
+struct Log<T>(Arc<Mutex<Vec<T>>>);
+
+impl<T> Default for Log<T> {
+    fn default() -> Log<T> {
+        Log(Arc::new(Mutex::new(vec![])))
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Log<T> {
+    fn push(&self, entry: T) {
+        self.0.lock().unwrap().push(entry);
+    }
+
+    /// A callback logging `entry` each time it's called.
+    fn entry(&self, entry: T) -> impl Fn() + Send + Sync + 'static {
+        let log = self.clone();
+        move || log.push(entry.clone())
+    }
+
+    /// The entries logged so far, emptying the log.
+    fn take(&self) -> Vec<T> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+fn handler() -> (MockDeviceState, MockEventsHandler) {
+    let mock = MockDeviceState::new();
+    let handler = MockEventsHandler::new(mock.clone());
+    (mock, handler)
+}
+
+#[test]
+fn key_callbacks_run_in_event_order() {
+    let (mock, handler) = handler();
+    let log = Log::default();
+    let _down = handler.on_key_down({
+        let log = log.clone();
+        move |key| log.push(DeviceEvent::KeyDown(key))
+    });
+    let _up = handler.on_key_up({
+        let log = log.clone();
+        move |key| log.push(DeviceEvent::KeyUp(key))
+    });
+
+    mock.press(Keycode::B);
+    mock.press(Keycode::A);
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            DeviceEvent::KeyDown(Keycode::A),
+            DeviceEvent::KeyDown(Keycode::B)
+        ]
+    );
+
+    handler.step();
+    assert_eq!(log.take(), []);
+
+    mock.release(Keycode::A);
+    mock.press(Keycode::C);
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            DeviceEvent::KeyDown(Keycode::C),
+            DeviceEvent::KeyUp(Keycode::A)
+        ]
+    );
+}
+
+#[test]
+fn mouse_callbacks_run_in_event_order() {
+    let (mock, handler) = handler();
+    let log = Log::default();
+    let _move = handler.on_mouse_move({
+        let log = log.clone();
+        move |position| log.push(DeviceEvent::MouseMove(position))
+    });
+    let _down = handler.on_mouse_down({
+        let log = log.clone();
+        move |button| log.push(DeviceEvent::MouseDown(button))
+    });
+    let _up = handler.on_mouse_up({
+        let log = log.clone();
+        move |button| log.push(DeviceEvent::MouseUp(button))
+    });
+    let _motion = handler.on_mouse_motion({
+        let log = log.clone();
+        move |dx, dy| log.push(DeviceEvent::MouseMotion((dx, dy)))
+    });
+    let _scroll = handler.on_scroll({
+        let log = log.clone();
+        move |delta| log.push(DeviceEvent::Scroll(delta))
+    });
+
+    mock.move_to((10, 20));
+    mock.press_button(MouseButton::Left);
+    mock.move_device_by(3, 4);
+    mock.scroll_by(ScrollDelta::from_notches(0, 1));
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            DeviceEvent::MouseMove((10, 20)),
+            DeviceEvent::MouseDown(MouseButton::Left),
+            DeviceEvent::MouseMotion((3, 4)),
+            DeviceEvent::Scroll(ScrollDelta::from_notches(0, 1)),
+        ]
+    );
+
+    mock.release_button(MouseButton::Left);
+    mock.press_button(MouseButton::Right);
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            DeviceEvent::MouseDown(MouseButton::Right),
+            DeviceEvent::MouseUp(MouseButton::Left),
+        ]
+    );
+}
+
+#[test]
+fn simulated_moves_saturate() {
+    let (mock, handler) = handler();
+    let events = handler.subscribe();
+
+    mock.mouse_move_by(i32::MAX, i32::MIN);
+    mock.mouse_move_by(1, -1);
+    handler.step();
+    assert_eq!(
+        events.try_iter().collect::<Vec<_>>(),
+        [
+            DeviceEvent::MouseMove((i32::MAX, i32::MIN)),
+            DeviceEvent::MouseMotion((i32::MAX, i32::MIN)),
+        ]
+    );
+}
+
+#[test]
+fn callbacks_stop_when_their_guard_is_dropped() {
+    let (mock, handler) = handler();
+    let log = Log::default();
+    let guard = handler.on_key_down({
+        let log = log.clone();
+        move |key| log.push(key)
+    });
+    let _other = handler.on_key_down({
+        let log = log.clone();
+        move |_| log.push(Keycode::Escape)
+    });
+
+    mock.press(Keycode::A);
+    handler.step();
+    drop(guard);
+    mock.press(Keycode::B);
+    handler.step();
+    assert_eq!(log.take(), [Keycode::A, Keycode::Escape, Keycode::Escape]);
+}
+
+#[test]
+fn subscribers_receive_every_event_in_order() {
+    let (mock, handler) = handler();
+    let events = handler.subscribe();
+    let timed = handler.subscribe_timed();
+    let dropped = handler.subscribe();
+    drop(dropped);
+
+    mock.press(Keycode::A);
+    mock.press_button(MouseButton::Left);
+    mock.move_to((1, 2));
+    handler.step();
+    mock.release(Keycode::A);
+    handler.step();
+
+    let expected = [
+        DeviceEvent::KeyDown(Keycode::A),
+        DeviceEvent::MouseMove((1, 2)),
+        DeviceEvent::MouseDown(MouseButton::Left),
+        DeviceEvent::KeyUp(Keycode::A),
+    ];
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), expected);
+    let timed: Vec<_> = timed.try_iter().collect();
+    assert_eq!(
+        timed.iter().map(|timed| timed.event).collect::<Vec<_>>(),
+        expected
+    );
+    assert!(timed.iter().all(|timed| timed.device_time.is_none()));
+    assert!(timed
+        .windows(2)
+        .all(|pair| pair[0].observed <= pair[1].observed));
+}
+
+#[test]
+fn event_callbacks_see_every_event() {
+    let (mock, handler) = handler();
+    let log = Log::default();
+    let _guard = handler.on_event({
+        let log = log.clone();
+        move |timed| log.push(timed.event)
+    });
+
+    mock.press(Keycode::Space);
+    mock.scroll_by(ScrollDelta::from_notches(1, 0));
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            DeviceEvent::KeyDown(Keycode::Space),
+            DeviceEvent::Scroll(ScrollDelta::from_notches(1, 0)),
+        ]
+    );
+}
+
+#[test]
+fn hotkeys_fire_once_per_activation() {
+    let (mock, handler) = handler();
+    let hotkeys = HotkeyManager::new(&handler);
+    let log = Log::default();
+    let _guard = hotkeys.register(&"Ctrl+Shift+K".parse().unwrap(), log.entry("Ctrl+Shift+K"));
+
+    mock.press(Keycode::LControl);
+    handler.step();
+    mock.press(Keycode::LShift);
+    handler.step();
+    mock.press(Keycode::K);
+    handler.step();
+    handler.step();
+    assert_eq!(log.take(), ["Ctrl+Shift+K"]);
+
+    mock.release(Keycode::K);
+    handler.step();
+    mock.press(Keycode::K);
+    handler.step();
+    assert_eq!(log.take(), ["Ctrl+Shift+K"]);
+}
+
+#[test]
+fn hotkeys_require_exactly_their_modifiers() {
+    let (mock, handler) = handler();
+    let hotkeys = HotkeyManager::new(&handler);
+    let log = Log::default();
+    let _guard = hotkeys.register(&"Ctrl+K".parse().unwrap(), log.entry("Ctrl+K"));
+
+    mock.set_keys(&[Keycode::K]);
+    handler.step();
+    mock.set_keys(&[]);
+    handler.step();
+    mock.set_keys(&[Keycode::LControl, Keycode::LAlt]);
+    handler.step();
+    mock.press(Keycode::K);
+    handler.step();
+    assert_eq!(log.take(), [] as [&str; 0]);
+
+    // Keys that aren't modifiers may be held.
+    mock.set_keys(&[Keycode::LControl, Keycode::A]);
+    handler.step();
+    mock.press(Keycode::K);
+    handler.step();
+    assert_eq!(log.take(), ["Ctrl+K"]);
+}
+
+#[test]
+fn hotkey_sides_are_equivalent_unless_distinguished() {
+    let (mock, handler) = handler();
+    let hotkeys = HotkeyManager::new(&handler);
+    let log = Log::default();
+    let _guard = hotkeys.register(&"Ctrl+K".parse().unwrap(), log.entry("Ctrl+K"));
+
+    mock.press(Keycode::RControl);
+    handler.step();
+    mock.press(Keycode::K);
+    handler.step();
+    assert_eq!(log.take(), ["Ctrl+K"]);
+
+    hotkeys.distinguish_sides(true);
+    mock.release(Keycode::K);
+    handler.step();
+    mock.press(Keycode::K);
+    handler.step();
+    assert_eq!(log.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn hotkey_keys_match_either_side() {
+    let (mock, handler) = handler();
+    let hotkeys = HotkeyManager::new(&handler);
+    let log = Log::default();
+    let _guard = hotkeys.register(&"Ctrl+Shift".parse().unwrap(), log.entry("Ctrl+Shift"));
+
+    mock.press(Keycode::LControl);
+    handler.step();
+    mock.press(Keycode::RShift);
+    handler.step();
+    mock.release(Keycode::RShift);
+    handler.step();
+    mock.press(Keycode::RShift);
+    handler.step();
+    assert_eq!(log.take(), ["Ctrl+Shift", "Ctrl+Shift"]);
+
+    hotkeys.distinguish_sides(true);
+    mock.release(Keycode::RShift);
+    handler.step();
+    mock.press(Keycode::RShift);
+    handler.step();
+    assert_eq!(log.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn hotkeys_stop_when_their_guard_is_dropped() {
+    let (mock, handler) = handler();
+    let hotkeys = HotkeyManager::new(&handler);
+    let log = Log::default();
+    let guard = hotkeys.register(&"F1".parse().unwrap(), log.entry("F1"));
+    drop(guard);
+
+    mock.press(Keycode::F1);
+    handler.step();
+    assert_eq!(log.take(), [] as [&str; 0]);
+}
+
+/// Presses and releases each of `steps`, holding the modifiers of each step.
+fn press_sequence(mock: &MockDeviceState, handler: &MockEventsHandler, steps: &[&[Keycode]]) {
+    for keys in steps {
+        for key in keys.iter() {
+            mock.press(*key);
+            handler.step();
+        }
+        mock.set_keys(&[]);
+        handler.step();
+    }
+}
+
+#[test]
+fn sequences_fire_when_completed() {
+    let (mock, handler) = handler();
+    let sequences = SequenceMatcher::new(&handler, Duration::from_secs(60));
+    let log = Log::default();
+    let _save = sequences.register(&"Ctrl+X Ctrl+S".parse().unwrap(), log.entry("save"));
+    let _quit = sequences.register(&"Ctrl+X Ctrl+C".parse().unwrap(), log.entry("quit"));
+    let prefixes = Log::default();
+    let _prefix = sequences.on_prefix({
+        let prefixes = prefixes.clone();
+        move |prefix| prefixes.push(prefix)
+    });
+
+    let ctrl_x: &[Keycode] = &[Keycode::LControl, Keycode::X];
+    press_sequence(&mock, &handler, &[ctrl_x]);
+    assert_eq!(log.take(), [] as [&str; 0]);
+    assert_eq!(prefixes.take(), ["Ctrl+X".parse().unwrap()]);
+    assert_eq!(sequences.pending(), Some("Ctrl+X".parse().unwrap()));
+
+    press_sequence(&mock, &handler, &[&[Keycode::LControl, Keycode::S]]);
+    assert_eq!(log.take(), ["save"]);
+    assert_eq!(sequences.pending(), None);
+
+    press_sequence(&mock, &handler, &[ctrl_x, &[Keycode::RControl, Keycode::C]]);
+    assert_eq!(log.take(), ["quit"]);
+    assert_eq!(prefixes.take(), ["Ctrl+X".parse().unwrap()]);
+}
+
+#[test]
+fn sequences_are_cancelled_by_other_keys() {
+    let (mock, handler) = handler();
+    let sequences = SequenceMatcher::new(&handler, Duration::from_secs(60));
+    let log = Log::default();
+    let _save = sequences.register(&"Ctrl+X Ctrl+S".parse().unwrap(), log.entry("save"));
+    let _find = sequences.register(&"Ctrl+F".parse().unwrap(), log.entry("find"));
+
+    let ctrl_x: &[Keycode] = &[Keycode::LControl, Keycode::X];
+    press_sequence(&mock, &handler, &[ctrl_x, &[Keycode::A]]);
+    assert_eq!(sequences.pending(), None);
+    press_sequence(&mock, &handler, &[&[Keycode::LControl, Keycode::S]]);
+    assert_eq!(log.take(), [] as [&str; 0]);
+
+    // The key cancelling the prefix may start another sequence.
+    press_sequence(&mock, &handler, &[ctrl_x, &[Keycode::LControl, Keycode::F]]);
+    assert_eq!(log.take(), ["find"]);
+}
+
+#[test]
+fn sequences_time_out_between_steps() {
+    let (mock, handler) = handler();
+    let sequences = SequenceMatcher::new(&handler, Duration::from_millis(1));
+    let log = Log::default();
+    let _save = sequences.register(&"Ctrl+X Ctrl+S".parse().unwrap(), log.entry("save"));
+
+    press_sequence(&mock, &handler, &[&[Keycode::LControl, Keycode::X]]);
+    sleep(Duration::from_millis(10));
+    assert_eq!(sequences.pending(), None);
+    press_sequence(&mock, &handler, &[&[Keycode::LControl, Keycode::S]]);
+    assert_eq!(log.take(), [] as [&str; 0]);
+}
+
+/// Gestures by name, with their button and position.
+type GestureLog = Log<(&'static str, MouseButton, MousePosition)>;
+
+/// Logs the gestures recognized by `handler`, until the returned guards are dropped.
+fn log_gestures(handler: &MockEventsHandler) -> (GestureLog, impl Sized) {
+    let log = Log::default();
+    let record = |name: &'static str| {
+        let log = log.clone();
+        move |button, position| log.push((name, button, position))
+    };
+    let guards = (
+        handler.on_click(record("click")),
+        handler.on_double_click(record("double click")),
+        handler.on_drag_start(record("drag start")),
+        handler.on_drag_move(record("drag move")),
+        handler.on_drag_end(record("drag end")),
+    );
+    (log, guards)
+}
+
+/// Presses and releases `button` at the current position.
+fn click(mock: &MockDeviceState, handler: &MockEventsHandler, button: MouseButton) {
+    mock.press_button(button);
+    handler.step();
+    mock.release_button(button);
+    handler.step();
+}
+
+#[test]
+fn gestures_recognize_clicks_and_double_clicks() {
+    let (mock, handler) = handler();
+    let (log, _guards) = log_gestures(&handler);
+    let left = MouseButton::Left;
+
+    mock.move_to((100, 100));
+    handler.step();
+    click(&mock, &handler, left);
+    mock.move_to((102, 97));
+    handler.step();
+    click(&mock, &handler, left);
+    click(&mock, &handler, left);
+    assert_eq!(
+        log.take(),
+        [
+            ("click", left, (100, 100)),
+            ("click", left, (102, 97)),
+            ("double click", left, (102, 97)),
+            // The click after a double-click starts a new sequence.
+            ("click", left, (102, 97)),
+        ]
+    );
+
+    // Too far apart, or of another button.
+    mock.move_to((110, 97));
+    handler.step();
+    click(&mock, &handler, left);
+    click(&mock, &handler, MouseButton::Right);
+    assert_eq!(
+        log.take(),
+        [
+            ("click", left, (110, 97)),
+            ("click", MouseButton::Right, (110, 97)),
+        ]
+    );
+}
+
+#[test]
+fn gestures_recognize_drags() {
+    let (mock, handler) = handler();
+    let (log, _guards) = log_gestures(&handler);
+    let left = MouseButton::Left;
+
+    mock.press_button(left);
+    handler.step();
+    mock.move_to((4, -4));
+    handler.step();
+    mock.move_to((5, 0));
+    handler.step();
+    mock.move_to((20, 10));
+    handler.step();
+    mock.release_button(left);
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            ("drag start", left, (0, 0)),
+            ("drag move", left, (5, 0)),
+            ("drag move", left, (20, 10)),
+            ("drag end", left, (20, 10)),
+        ]
+    );
+
+    // Moving back within the drag distance still drags, and other buttons are ignored.
+    mock.press_button(left);
+    handler.step();
+    mock.move_to((40, 10));
+    handler.step();
+    click(&mock, &handler, MouseButton::Right);
+    mock.move_to((20, 10));
+    handler.step();
+    mock.release_button(left);
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            ("drag start", left, (20, 10)),
+            ("drag move", left, (40, 10)),
+            ("drag move", left, (20, 10)),
+            ("drag end", left, (20, 10)),
+        ]
+    );
+}
+
+#[test]
+fn gesture_thresholds_are_configurable() {
+    let (mock, handler) = handler();
+    let (log, _guards) = log_gestures(&handler);
+    let left = MouseButton::Left;
+    handler.set_gesture_config(GestureConfig {
+        double_click_time: Duration::from_millis(1),
+        double_click_distance: 0,
+        drag_distance: 20,
+    });
+
+    mock.press_button(left);
+    handler.step();
+    mock.move_to((20, 20));
+    handler.step();
+    mock.release_button(left);
+    handler.step();
+    sleep(Duration::from_millis(10));
+    click(&mock, &handler, left);
+    assert_eq!(
+        log.take(),
+        [("click", left, (20, 20)), ("click", left, (20, 20))]
+    );
+}
+
+#[test]
+fn gestures_start_where_the_pointer_moved_before_a_press() {
+    let (mock, handler) = handler();
+    let (log, _guards) = log_gestures(&handler);
+    let left = MouseButton::Left;
+
+    // Both changes are observed by the same step.
+    mock.move_to((100, 100));
+    mock.press_button(left);
+    handler.step();
+    mock.move_to((120, 100));
+    mock.release_button(left);
+    handler.step();
+    assert_eq!(
+        log.take(),
+        [
+            ("drag start", left, (100, 100)),
+            ("drag move", left, (120, 100)),
+            ("drag end", left, (120, 100)),
+        ]
+    );
+}
+
+/// A left button press and release, at device times `pressed` and `released` in milliseconds.
+fn timed_click(listeners: &EventListeners, pressed: u64, released: u64) {
+    let left = MouseButton::Left;
+    for (event, time) in [
+        (DeviceEvent::MouseDown(left), pressed),
+        (DeviceEvent::MouseUp(left), released),
+    ] {
+        let time = Some(Duration::from_millis(time));
+        listeners.dispatch(TimedEvent::new(event, time));
+    }
+}
+
+#[test]
+fn gestures_start_where_the_pointer_was_before_any_move() {
+    let listeners = EventListeners::default();
+    listeners.set_pointer_position((50, 60));
+    let log = Log::default();
+    let _click = listeners.on_click({
+        let log = log.clone();
+        move |button, position| log.push((button, position))
+    });
+
+    timed_click(&listeners, 0, 50);
+    assert_eq!(log.take(), [(MouseButton::Left, (50, 60))]);
+}
+
+#[test]
+fn double_clicks_are_timed_by_the_device() {
+    let listeners = EventListeners::default();
+    let log = Log::default();
+    let _double_click = listeners.on_double_click({
+        let log = log.clone();
+        move |button, _| log.push(button)
+    });
+
+    // Dispatched right away, but released a second apart on the device.
+    timed_click(&listeners, 0, 50);
+    timed_click(&listeners, 1000, 1050);
+    assert_eq!(log.take(), []);
+    timed_click(&listeners, 1100, 1150);
+    assert_eq!(log.take(), [MouseButton::Left]);
+}
+
+/// The only test starting the global event loop, since it runs until the process exits. The
+/// failed starts come first, as they must leave the event loop stopped.
+#[test]
+fn event_loop_reports_why_it_couldnt_start() {
+    let sleep_dur = Duration::from_millis(1);
+    let unsupported = || DeviceQueryError::BackendUnsupported(String::from("no backend here"));
+    assert_eq!(
+        DeviceEventsHandler::with_backend(sleep_dur, move || {
+            Err::<MockDeviceState, _>(unsupported())
+        })
+        .err(),
+        Some(unsupported())
+    );
+    assert_eq!(
+        DeviceEventsHandler::with_backend(sleep_dur, || -> Result<MockDeviceState, _> {
+            panic!("backend creation failed")
+        })
+        .err(),
+        Some(DeviceQueryError::BackendUnsupported(String::from(
+            "the backend panicked"
+        )))
+    );
+
+    let mock = MockDeviceState::new();
+    let handler = DeviceEventsHandler::with_backend(sleep_dur, {
+        let mock = mock.clone();
+        move || Ok(mock.clone())
+    })
+    .expect("Couldn't start the event loop");
+    let events = handler.subscribe();
+    mock.press(Keycode::A);
+    assert_eq!(
+        events.recv_timeout(Duration::from_secs(5)),
+        Ok(DeviceEvent::KeyDown(Keycode::A))
+    );
+
+    assert_eq!(
+        DeviceEventsHandler::try_new(sleep_dur).err(),
+        Some(DeviceQueryError::EventLoopAlreadyRunning)
+    );
+    assert_eq!(
+        DeviceEventsHandler::with_backend(sleep_dur, move || Ok(MockDeviceState::new())).err(),
+        Some(DeviceQueryError::EventLoopAlreadyRunning)
+    );
+}
+
+/// Async streams, which also need the `stream` feature.
+#[cfg(feature = "stream")]
+mod stream {
+    extern crate futures;
+
+    use self::futures::executor::block_on;
+    use self::futures::{Stream, StreamExt};
+    use super::super::{DeviceEvents, EventListeners};
+    use super::handler;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread;
+    use std::time::Duration;
+    use {DeviceEvent, DeviceEventStream, Keycode, TimedEvent};
+
+    fn poll(stream: &mut DeviceEventStream, waker: &Waker) -> Poll<Option<DeviceEvent>> {
+        Pin::new(stream).poll_next(&mut Context::from_waker(waker))
+    }
+
+    /// Counts how many times it was woken.
+    #[derive(Default)]
+    struct CountingWaker(Mutex<usize>);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            *self.0.lock().unwrap() += 1;
+        }
+    }
+
+    #[test]
+    fn streams_wake_the_task_polling_them() {
+        let (mock, handler) = handler();
+        let mut stream = handler.stream();
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counter.clone());
+
+        assert_eq!(poll(&mut stream, &waker), Poll::Pending);
+        mock.press(Keycode::A);
+        handler.step();
+        mock.release(Keycode::A);
+        handler.step();
+        // Woken once, for the first event after the stream was polled.
+        assert_eq!(*counter.0.lock().unwrap(), 1);
+
+        let key_down = DeviceEvent::KeyDown(Keycode::A);
+        assert_eq!(poll(&mut stream, &waker), Poll::Ready(Some(key_down)));
+        let key_up = DeviceEvent::KeyUp(Keycode::A);
+        assert_eq!(poll(&mut stream, &waker), Poll::Ready(Some(key_up)));
+        assert_eq!(poll(&mut stream, &waker), Poll::Pending);
+    }
+
+    /// Polls its stream as soon as it is woken, like an executor running the task on the
+    /// thread waking it.
+    #[derive(Default)]
+    struct PollingWaker {
+        stream: Mutex<Option<DeviceEventStream>>,
+        polled: Mutex<Vec<Poll<Option<DeviceEvent>>>>,
+    }
+
+    impl Wake for PollingWaker {
+        fn wake(self: Arc<Self>) {
+            let waker = Waker::from(self.clone());
+            if let Some(stream) = self.stream.lock().unwrap().as_mut() {
+                let polled = poll(stream, &waker);
+                self.polled.lock().unwrap().push(polled);
+            }
+        }
+    }
+
+    #[test]
+    fn wakers_can_poll_the_stream_right_away() {
+        let (mock, handler) = handler();
+        let polling = Arc::new(PollingWaker::default());
+        let waker = Waker::from(polling.clone());
+        {
+            let mut stream = polling.stream.lock().unwrap();
+            let stream = stream.get_or_insert_with(|| handler.stream());
+            assert_eq!(poll(stream, &waker), Poll::Pending);
+        }
+
+        mock.press(Keycode::A);
+        handler.step();
+        assert_eq!(
+            *polling.polled.lock().unwrap(),
+            [Poll::Ready(Some(DeviceEvent::KeyDown(Keycode::A)))]
+        );
+    }
+
+    #[test]
+    fn block_on_waits_for_the_next_event() {
+        let (mock, handler) = handler();
+        let mut stream = handler.stream();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(10));
+                mock.press(Keycode::A);
+                handler.step();
+            });
+            let next = block_on(stream.next());
+            assert_eq!(next, Some(DeviceEvent::KeyDown(Keycode::A)));
+        });
+    }
+
+    #[test]
+    fn dropped_streams_are_unregistered() {
+        let listeners = EventListeners::default();
+        let dropped = listeners.stream();
+        let mut kept = listeners.stream();
+        assert_eq!(listeners.stream_count(), 2);
+
+        drop(dropped);
+        listeners.dispatch(TimedEvent::new(DeviceEvent::KeyDown(Keycode::A), None));
+        assert_eq!(listeners.stream_count(), 1);
+        let next = block_on(kept.next());
+        assert_eq!(next, Some(DeviceEvent::KeyDown(Keycode::A)));
+    }
+}
@@ -0,0 +1,53 @@
+//! Text input: the text typed by key events, with the current keyboard layout.
+
+use super::{CallbackGuard, DeviceEvents};
+use std::sync::{Arc, Mutex};
+use {DeviceQueryError, KeyTranslator, Keycode};
+
+/// Runs a callback with the text typed by each key press, translated by a [`KeyTranslator`]
+/// from the key callbacks of a [`DeviceEvents`] implementation.
+///
+/// ```no_run
+/// use device_query::{DeviceEventsHandler, TextInput};
+/// use std::time::Duration;
+///
+/// let event_handler = DeviceEventsHandler::new(Duration::from_millis(10)).unwrap();
+/// let _text_input = TextInput::new(&event_handler, |text| print!("{}", text)).unwrap();
+///
+/// loop {}
+/// ```
+pub struct TextInput {
+    _key_down: CallbackGuard<Keycode>,
+    _key_up: CallbackGuard<Keycode>,
+}
+
+impl TextInput {
+    /// Creates a text input listening to the key events of `events`, passing the typed text to
+    /// `callback`. Fails if the keyboard layout can't be read, see [`KeyTranslator::new`].
+    /// The callback stops being called when the text input is dropped.
+    pub fn new<E: DeviceEvents, Callback: Fn(&str) + Send + Sync + 'static>(
+        events: &E,
+        callback: Callback,
+    ) -> Result<TextInput, DeviceQueryError> {
+        let translator = Arc::new(Mutex::new(KeyTranslator::new()?));
+        let _key_down = events.on_key_down({
+            let translator = translator.clone();
+            move |key| {
+                let text = match translator.lock() {
+                    Ok(mut translator) => translator.key_down(key),
+                    Err(_) => return,
+                };
+                // Called without the lock held, like the other callbacks.
+                if let Some(text) = text {
+                    callback(&text);
+                }
+            }
+        });
+        let _key_up = events.on_key_up(move |key| {
+            if let Ok(mut translator) = translator.lock() {
+                translator.key_up(key);
+            }
+        });
+        Ok(TextInput { _key_down, _key_up })
+    }
+}
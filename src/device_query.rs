@@ -1,7 +1,7 @@
 //! Query functions.
 
-use DeviceState;
-use {Keycode, MouseState};
+use {DeviceBackend, DeviceState};
+use {KeySet, Keycode, Monitor, MouseState, PointerLocation};
 
 /// Trait to get the state of the supported devices.
 pub trait DeviceQuery {
@@ -10,6 +10,27 @@ pub trait DeviceQuery {
 
     /// Get Keyboard state.
     fn get_keys(&self) -> Vec<Keycode>;
+
+    /// Get Keyboard state as a set. Unlike [`get_keys`](Self::get_keys), `DeviceState` doesn't
+    /// allocate to answer it.
+    fn get_key_set(&self) -> KeySet {
+        self.get_keys().into_iter().collect()
+    }
+
+    /// Get the monitors of the desktop. None are known by default.
+    fn get_monitors(&self) -> Vec<Monitor> {
+        Vec::new()
+    }
+
+    /// Get the location of the pointer on the desktop: its coordinates, and the screen,
+    /// monitor and window under it. By default, only the coordinates of
+    /// [`get_mouse`](Self::get_mouse) are known.
+    fn get_pointer_location(&self) -> PointerLocation {
+        PointerLocation {
+            coords: self.get_mouse().coords,
+            ..PointerLocation::default()
+        }
+    }
 }
 
 impl DeviceQuery for DeviceState {
@@ -22,4 +43,19 @@ impl DeviceQuery for DeviceState {
     fn get_keys(&self) -> Vec<Keycode> {
         self.query_keymap()
     }
+
+    /// Query for all keys that are currently pressed down, as a set.
+    fn get_key_set(&self) -> KeySet {
+        self.query_key_set()
+    }
+
+    /// Query for the monitors of the desktop.
+    fn get_monitors(&self) -> Vec<Monitor> {
+        self.query_monitors()
+    }
+
+    /// Query for the location of the pointer on the desktop.
+    fn get_pointer_location(&self) -> PointerLocation {
+        self.query_pointer_location()
+    }
 }
@@ -0,0 +1,132 @@
+//! Input synthesis functions.
+
+use {Keycode, MouseButton, MousePosition};
+
+/// Trait to generate input, the counterpart of [`DeviceQuery`](crate::DeviceQuery).
+///
+/// Pressed buttons and pointer moves can be read back with
+/// [`DeviceQuery::get_mouse`](crate::DeviceQuery::get_mouse). Buttons that don't exist on the
+/// current platform, such as the `Other` buttons on Windows, are ignored.
+pub trait DeviceSimulate {
+    /// Press `key` down. Keys that don't exist on the current platform are ignored.
+    fn key_down(&self, key: Keycode);
+
+    /// Release `key`. Keys that don't exist on the current platform are ignored.
+    fn key_up(&self, key: Keycode);
+
+    /// Press and release `key`.
+    fn key_press(&self, key: Keycode) {
+        self.key_down(key);
+        self.key_up(key);
+    }
+
+    /// Type `text` by pressing the keys producing each character on a US keyboard layout, holding
+    /// shift where needed.
+    ///
+    /// Returns the first character that can't be typed, in which case nothing is typed.
+    fn type_text(&self, text: &str) -> Result<(), char> {
+        let keys = text
+            .chars()
+            .map(|c| char_to_key(c).ok_or(c))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (key, shift) in keys {
+            if shift {
+                self.key_down(Keycode::LShift);
+            }
+            self.key_press(key);
+            if shift {
+                self.key_up(Keycode::LShift);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the mouse pointer to `position`.
+    fn mouse_move_to(&self, position: MousePosition);
+
+    /// Move the mouse pointer by `dx` and `dy` pixels from its current position.
+    fn mouse_move_by(&self, dx: i32, dy: i32);
+
+    /// Press mouse `button` down. Buttons that don't exist on the current platform are ignored.
+    fn mouse_down(&self, button: MouseButton);
+
+    /// Release mouse `button`. Buttons that don't exist on the current platform are ignored.
+    fn mouse_up(&self, button: MouseButton);
+
+    /// Press and release mouse `button`.
+    fn mouse_click(&self, button: MouseButton) {
+        self.mouse_down(button);
+        self.mouse_up(button);
+    }
+
+    /// Press mouse `button` at `from`, move to `to` and release it there.
+    fn mouse_drag(&self, button: MouseButton, from: MousePosition, to: MousePosition) {
+        self.mouse_move_to(from);
+        self.mouse_down(button);
+        self.mouse_move_to(to);
+        self.mouse_up(button);
+    }
+
+    /// Scroll by `dx` horizontal and `dy` vertical wheel ticks. Positive values scroll right and
+    /// down, negative values left and up.
+    fn scroll(&self, dx: i32, dy: i32);
+}
+
+/// The key typing `c` on a US keyboard layout, and whether shift must be held.
+pub(crate) fn char_to_key(c: char) -> Option<(Keycode, bool)> {
+    let shift = c.is_ascii_uppercase() || "~!@#$%^&*()_+{}|:\"<>?".contains(c);
+    let key = match c.to_ascii_lowercase() {
+        'a' => Keycode::A,
+        'b' => Keycode::B,
+        'c' => Keycode::C,
+        'd' => Keycode::D,
+        'e' => Keycode::E,
+        'f' => Keycode::F,
+        'g' => Keycode::G,
+        'h' => Keycode::H,
+        'i' => Keycode::I,
+        'j' => Keycode::J,
+        'k' => Keycode::K,
+        'l' => Keycode::L,
+        'm' => Keycode::M,
+        'n' => Keycode::N,
+        'o' => Keycode::O,
+        'p' => Keycode::P,
+        'q' => Keycode::Q,
+        'r' => Keycode::R,
+        's' => Keycode::S,
+        't' => Keycode::T,
+        'u' => Keycode::U,
+        'v' => Keycode::V,
+        'w' => Keycode::W,
+        'x' => Keycode::X,
+        'y' => Keycode::Y,
+        'z' => Keycode::Z,
+        '0' | ')' => Keycode::Key0,
+        '1' | '!' => Keycode::Key1,
+        '2' | '@' => Keycode::Key2,
+        '3' | '#' => Keycode::Key3,
+        '4' | '$' => Keycode::Key4,
+        '5' | '%' => Keycode::Key5,
+        '6' | '^' => Keycode::Key6,
+        '7' | '&' => Keycode::Key7,
+        '8' | '*' => Keycode::Key8,
+        '9' | '(' => Keycode::Key9,
+        ' ' => Keycode::Space,
+        '\n' => Keycode::Enter,
+        '\t' => Keycode::Tab,
+        '`' | '~' => Keycode::Grave,
+        '-' | '_' => Keycode::Minus,
+        '=' | '+' => Keycode::Equal,
+        '[' | '{' => Keycode::LeftBracket,
+        ']' | '}' => Keycode::RightBracket,
+        '\\' | '|' => Keycode::BackSlash,
+        ';' | ':' => Keycode::Semicolon,
+        '\'' | '"' => Keycode::Apostrophe,
+        ',' | '<' => Keycode::Comma,
+        '.' | '>' => Keycode::Dot,
+        '/' | '?' => Keycode::Slash,
+        _ => return None,
+    };
+    Some((key, shift))
+}
@@ -0,0 +1,92 @@
+//! Sources of input behind a `DeviceState`.
+
+use std::time::Duration;
+use {KeySet, Keycode, Monitor, MouseDelta, MouseState, PointerLocation, ScrollDelta, TimedEvent};
+
+/// A source of keyboard and mouse input.
+///
+/// The platform backends implement this trait, and any other implementation can be wrapped with
+/// [`DeviceState::from_backend`](crate::DeviceState::from_backend) to be used wherever a
+/// `DeviceState` is expected. Backends are `Send + Sync`, so that a `DeviceState` can be queried
+/// from any thread.
+///
+/// ```
+/// use device_query::{DeviceBackend, DeviceQuery, DeviceState, Keycode, MouseState};
+///
+/// struct AlwaysA;
+///
+/// impl DeviceBackend for AlwaysA {
+///     fn query_keymap(&self) -> Vec<Keycode> {
+///         vec![Keycode::A]
+///     }
+///
+///     fn query_pointer(&self) -> MouseState {
+///         MouseState::default()
+///     }
+/// }
+///
+/// let device_state = DeviceState::from_backend(AlwaysA);
+/// assert_eq!(device_state.get_keys(), vec![Keycode::A]);
+/// ```
+pub trait DeviceBackend: Send + Sync {
+    /// Query for all keys that are currently pressed down.
+    fn query_keymap(&self) -> Vec<Keycode>;
+
+    /// Query for all keys that are currently pressed down, as a set.
+    ///
+    /// The default implementation collects [`query_keymap`](Self::query_keymap); backends
+    /// override it to avoid allocating.
+    fn query_key_set(&self) -> KeySet {
+        self.query_keymap().into_iter().collect()
+    }
+
+    /// Query for the current mouse position and mouse button state.
+    fn query_pointer(&self) -> MouseState;
+
+    /// Query for the monitors of the desktop.
+    ///
+    /// The default implementation knows of no monitor, like backends reading input devices
+    /// without a display server.
+    fn query_monitors(&self) -> Vec<Monitor> {
+        Vec::new()
+    }
+
+    /// Query for the location of the pointer on the desktop.
+    ///
+    /// The default implementation locates the coordinates of
+    /// [`query_pointer`](Self::query_pointer) on screen 0 among
+    /// [`query_monitors`](Self::query_monitors), over no known window.
+    fn query_pointer_location(&self) -> PointerLocation {
+        PointerLocation::locate(self.query_pointer().coords, 0, &self.query_monitors())
+    }
+
+    /// Query for the scrolling done since the previous call.
+    ///
+    /// Polled by the event loop when [`read_events`](Self::read_events) returns `false`. The
+    /// default implementation never reports scrolling, since the wheel has no state the platform
+    /// backends can query; event-driven backends report [`Scroll`](crate::DeviceEvent::Scroll)
+    /// events from `read_events` instead.
+    fn query_scroll(&self) -> ScrollDelta {
+        ScrollDelta::default()
+    }
+
+    /// Query for the relative motion of the mouse devices since the previous call, before
+    /// pointer acceleration.
+    ///
+    /// Polled by the event loop when [`read_events`](Self::read_events) returns `false`, like
+    /// [`query_scroll`](Self::query_scroll). The default implementation never reports motion;
+    /// event-driven backends report [`MouseMotion`](crate::DeviceEvent::MouseMotion) events from
+    /// `read_events` instead.
+    fn query_motion(&self) -> MouseDelta {
+        (0, 0)
+    }
+
+    /// Waits up to `timeout` for input events and appends them to `events`.
+    ///
+    /// Backends that can only be polled keep the default implementation, which returns `false`;
+    /// the event loop then sleeps for `timeout` and diffs successive queries instead.
+    fn read_events(&self, timeout: Duration, events: &mut Vec<TimedEvent>) -> bool {
+        let _ = (timeout, events);
+        false
+    }
+}
@@ -0,0 +1,448 @@
+//! Backend reading `/dev/input/event*` devices directly, for systems without an X server.
+
+use super::kernel_key;
+use super::libc;
+use device_events::{DeviceEvent, TimedEvent};
+use device_state::DeviceBackend;
+use error::DeviceQueryError;
+use key_set::KeySet;
+use keymap::Keycode;
+use mouse_state::{MouseButton, MouseDelta, MousePosition, MouseState, ScrollDelta};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::os::raw::c_int;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_MAX: usize = 0x1f;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const REL_HWHEEL: u16 = 0x06;
+const REL_WHEEL: u16 = 0x08;
+const REL_WHEEL_HI_RES: u16 = 0x0b;
+const REL_HWHEEL_HI_RES: u16 = 0x0c;
+const KEY_MAX: usize = 0x2ff;
+
+/// The last mouse button code, `BTN_JOYSTICK - 1`. Mouse buttons start at `BTN_LEFT`.
+const BTN_MOUSE_LAST: u16 = 0x11f;
+
+/// Size of a bitmask holding one bit per kernel key code.
+const KEY_BYTES: usize = KEY_MAX / 8 + 1;
+
+type KeyBits = [u8; KEY_BYTES];
+
+/// EVIOCGBIT(0, len): the event types supported by the device.
+const EVIOCGBIT_EV: libc::Ioctl = libc::_IOR::<[u8; EV_MAX / 8 + 1]>(b'E' as u32, 0x20);
+/// EVIOCGBIT(EV_REL, len): the relative axes supported by the device.
+const EVIOCGBIT_REL: libc::Ioctl = libc::_IOR::<[u8; 2]>(b'E' as u32, 0x20 + EV_REL as u32);
+/// EVIOCGKEY(len): the keys and buttons currently held down on the device.
+const EVIOCGKEY: libc::Ioctl = libc::_IOR::<KeyBits>(b'E' as u32, 0x18);
+
+fn test_bit(bits: &[u8], bit: usize) -> bool {
+    bits.get(bit / 8)
+        .is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+}
+
+/// The kernel timestamp of `event`.
+fn event_time(event: &libc::input_event) -> Duration {
+    Duration::new(event.time.tv_sec as u64, event.time.tv_usec as u32 * 1000)
+}
+
+fn no_device() -> DeviceQueryError {
+    DeviceQueryError::BackendUnsupported(String::from("no keyboard or pointer input device"))
+}
+
+fn open_error(error: io::Error) -> DeviceQueryError {
+    match error.kind() {
+        io::ErrorKind::PermissionDenied => DeviceQueryError::PermissionDenied,
+        _ => no_device(),
+    }
+}
+
+/// The relative motion reported by a device, usually since its last `SYN_REPORT`.
+#[derive(Debug, Default)]
+struct Frame {
+    motion: MouseDelta,
+    /// Horizontal and vertical wheel notches, positive right and down.
+    notches: [i32; 2],
+    /// The same wheel motion in 1/120 of a notch, from high resolution wheels.
+    hi_res: [Option<i32>; 2],
+}
+
+impl Frame {
+    /// Adds a relative motion event. Returns `false` for any other event.
+    fn add(&mut self, event: &libc::input_event) -> bool {
+        if event.type_ != EV_REL {
+            return false;
+        }
+        let value = event.value;
+        match event.code {
+            REL_X => self.motion.0 = self.motion.0.saturating_add(value),
+            REL_Y => self.motion.1 = self.motion.1.saturating_add(value),
+            REL_HWHEEL => self.notches[0] = self.notches[0].saturating_add(value),
+            // The kernel reports the vertical wheel positive up.
+            REL_WHEEL => self.notches[1] = self.notches[1].saturating_sub(value),
+            REL_HWHEEL_HI_RES => {
+                let hi_res = self.hi_res[0].get_or_insert(0);
+                *hi_res = hi_res.saturating_add(value);
+            }
+            REL_WHEEL_HI_RES => {
+                let hi_res = self.hi_res[1].get_or_insert(0);
+                *hi_res = hi_res.saturating_sub(value);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// The wheel motion. High resolution wheels report each notch twice, the high resolution
+    /// value is preferred.
+    fn scroll(&self) -> ScrollDelta {
+        let axis = |axis: usize| {
+            self.hi_res[axis]
+                .unwrap_or_else(|| self.notches[axis].saturating_mul(ScrollDelta::NOTCH))
+        };
+        ScrollDelta {
+            horizontal: axis(0),
+            vertical: axis(1),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Device {
+    file: File,
+    has_keys: bool,
+    has_motion: bool,
+}
+
+impl Device {
+    fn open(path: &Path) -> io::Result<Device> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK | libc::O_CLOEXEC)
+            .open(path)?;
+        let mut ev_bits = [0u8; EV_MAX / 8 + 1];
+        let mut rel_bits = [0u8; 2];
+        unsafe {
+            if libc::ioctl(file.as_raw_fd(), EVIOCGBIT_EV, ev_bits.as_mut_ptr()) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if test_bit(&ev_bits, EV_REL as usize)
+                && libc::ioctl(file.as_raw_fd(), EVIOCGBIT_REL, rel_bits.as_mut_ptr()) < 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(Device {
+            file,
+            has_keys: test_bit(&ev_bits, EV_KEY as usize),
+            has_motion: (test_bit(&rel_bits, REL_X as usize)
+                && test_bit(&rel_bits, REL_Y as usize))
+                || test_bit(&rel_bits, REL_WHEEL as usize)
+                || test_bit(&rel_bits, REL_HWHEEL as usize),
+        })
+    }
+
+    /// ORs the keys currently held on this device into `bits`.
+    fn read_keys(&self, bits: &mut KeyBits) {
+        let mut device_bits: KeyBits = [0; KEY_BYTES];
+        unsafe {
+            if libc::ioctl(self.file.as_raw_fd(), EVIOCGKEY, device_bits.as_mut_ptr()) < 0 {
+                return;
+            }
+        }
+        for (byte, device_byte) in bits.iter_mut().zip(device_bits.iter()) {
+            *byte |= device_byte;
+        }
+    }
+
+    /// Drains all pending events, passing each of them to `f`.
+    fn read_input(&self, mut f: impl FnMut(&libc::input_event)) {
+        let mut events: [libc::input_event; 64] = unsafe { mem::zeroed() };
+        loop {
+            let read = unsafe {
+                libc::read(
+                    self.file.as_raw_fd(),
+                    events.as_mut_ptr() as *mut libc::c_void,
+                    mem::size_of_val(&events),
+                )
+            };
+            if read <= 0 {
+                // EAGAIN once the queue is empty, or the device went away.
+                return;
+            }
+            let count = read as usize / mem::size_of::<libc::input_event>();
+            events[..count].iter().for_each(&mut f);
+        }
+    }
+}
+
+/// Converts a key or button event.
+fn convert(event: &libc::input_event) -> Option<DeviceEvent> {
+    match event.type_ {
+        // Value 2 is an autorepeat, which isn't a change of state.
+        EV_KEY if event.value != 2 => {
+            let down = event.value == 1;
+            if let Some(button) = MouseButton::from_evdev_code(event.code) {
+                return Some(if down {
+                    DeviceEvent::MouseDown(button)
+                } else {
+                    DeviceEvent::MouseUp(button)
+                });
+            }
+            let key = kernel_key::kernel_key_to_keycode(event.code)?;
+            Some(if down {
+                DeviceEvent::KeyDown(key)
+            } else {
+                DeviceEvent::KeyUp(key)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The state built from the events read from the devices, by queries and the event loop alike.
+#[derive(Debug, Default)]
+struct Input {
+    coords: MousePosition,
+    /// Scrolling not reported by `query_scroll` yet.
+    scroll: ScrollDelta,
+    /// Motion not reported by `query_motion` yet.
+    motion: MouseDelta,
+    /// The motion of each device since its last `SYN_REPORT`.
+    frames: Vec<Frame>,
+    /// Set once the event loop reads events, which are then kept until it does.
+    event_driven: bool,
+    /// Events read but not returned by `read_events` yet.
+    events: Vec<TimedEvent>,
+}
+
+impl Input {
+    /// Handles an event of device `device`. Motion is reported once per `SYN_REPORT` of its
+    /// device, after every axis was updated.
+    fn add(&mut self, device: usize, event: &libc::input_event) {
+        if self.frames[device].add(event) {
+            return;
+        }
+        let converted = if event.type_ == EV_SYN {
+            let frame = mem::take(&mut self.frames[device]);
+            self.apply(&frame)
+        } else {
+            [convert(event), None, None]
+        };
+        if self.event_driven {
+            let time = Some(event_time(event));
+            self.events.extend(
+                converted
+                    .iter()
+                    .flatten()
+                    .map(|converted| TimedEvent::new(*converted, time)),
+            );
+        }
+    }
+
+    /// Adds the motion of `frame` to the pointer position, motion and scrolling, and returns
+    /// the move, motion and scroll events it produces.
+    fn apply(&mut self, frame: &Frame) -> [Option<DeviceEvent>; 3] {
+        self.coords = (
+            self.coords.0.saturating_add(frame.motion.0),
+            self.coords.1.saturating_add(frame.motion.1),
+        );
+        self.motion = (
+            self.motion.0.saturating_add(frame.motion.0),
+            self.motion.1.saturating_add(frame.motion.1),
+        );
+        let scroll = frame.scroll();
+        self.scroll.horizontal = self.scroll.horizontal.saturating_add(scroll.horizontal);
+        self.scroll.vertical = self.scroll.vertical.saturating_add(scroll.vertical);
+        [
+            Some(DeviceEvent::MouseMove(self.coords)).filter(|_| frame.motion != (0, 0)),
+            Some(DeviceEvent::MouseMotion(frame.motion)).filter(|_| frame.motion != (0, 0)),
+            Some(DeviceEvent::Scroll(scroll)).filter(|_| !scroll.is_zero()),
+        ]
+    }
+}
+
+/// Backend reading keyboards and pointers from `/dev/input`.
+///
+/// Evdev reports relative motion only, so the pointer position is accumulated from the motion
+/// observed since the devices were opened, starting at `(0, 0)` and unbounded by any screen.
+///
+/// Mouse motion is reported in device units, which the kernel never accelerates.
+///
+/// Scrolling is reported in fractions of a notch by high resolution wheels, and in whole notches
+/// otherwise.
+///
+/// The event loop reads the device events as they arrive, with their kernel timestamps, and
+/// reports the events of different devices in timestamp order. The backend can still be
+/// queried meanwhile: the events a query reads are kept for the event loop.
+#[derive(Debug)]
+pub struct EvdevBackend {
+    devices: Vec<Device>,
+    input: Mutex<Input>,
+    /// An eventfd signaled when a query keeps events for the event loop, waking its `poll`.
+    kept_events: File,
+}
+
+impl EvdevBackend {
+    /// Opens every readable `/dev/input/event*` device. Fails with
+    /// [`DeviceQueryError::PermissionDenied`] if none could be opened for lack of permission,
+    /// which usually means the process is not in the `input` group.
+    pub fn open_all() -> Result<EvdevBackend, DeviceQueryError> {
+        let mut paths: Vec<_> = fs::read_dir("/dev/input")
+            .map_err(open_error)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("event"))
+            })
+            .collect();
+        paths.sort();
+        Self::open_paths(&paths)
+    }
+
+    /// Opens the given event devices, skipping the ones that can't be opened.
+    pub fn open_paths<P: AsRef<Path>>(paths: &[P]) -> Result<EvdevBackend, DeviceQueryError> {
+        let mut denied = false;
+        let mut devices = vec![];
+        for path in paths {
+            match Device::open(path.as_ref()) {
+                Ok(device) if device.has_keys || device.has_motion => devices.push(device),
+                Ok(_) => {}
+                Err(error) => denied |= error.kind() == io::ErrorKind::PermissionDenied,
+            }
+        }
+        if devices.is_empty() {
+            return Err(if denied {
+                DeviceQueryError::PermissionDenied
+            } else {
+                no_device()
+            });
+        }
+        let kept_events = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if kept_events < 0 {
+            return Err(open_error(io::Error::last_os_error()));
+        }
+        Ok(EvdevBackend {
+            input: Mutex::new(Input {
+                frames: devices.iter().map(|_| Frame::default()).collect(),
+                ..Input::default()
+            }),
+            devices,
+            kept_events: unsafe { File::from_raw_fd(kept_events) },
+        })
+    }
+
+    /// Blocks until a device has events to read, a query kept events for the event loop, or
+    /// `timeout` elapses.
+    fn wait(&self, timeout: Duration) {
+        let mut fds: Vec<libc::pollfd> = self
+            .devices
+            .iter()
+            .map(|device| &device.file)
+            .chain(Some(&self.kept_events))
+            .map(|file| libc::pollfd {
+                fd: file.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            })
+            .collect();
+        let timeout = timeout.as_millis().clamp(1, c_int::MAX as u128) as c_int;
+        unsafe {
+            libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout);
+        }
+    }
+
+    /// Drains the pending events of every device, merged by timestamp, into the input state.
+    fn read_input(&self) -> MutexGuard<'_, Input> {
+        // Read with the lock held, so that concurrent reads are applied in order.
+        let mut input = self.input.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut read = vec![];
+        for (index, device) in self.devices.iter().enumerate() {
+            device.read_input(|event| read.push((index, *event)));
+        }
+        // The sort is stable, so the events of a device stay in the order they were read.
+        read.sort_by_key(|(_, event)| event_time(event));
+        let kept = input.events.len();
+        for (index, event) in &read {
+            input.add(*index, event);
+        }
+        if input.events.len() > kept {
+            let _ = (&self.kept_events).write(&1u64.to_ne_bytes());
+        }
+        input
+    }
+
+    fn key_bits(&self) -> KeyBits {
+        let mut bits = [0; KEY_BYTES];
+        for device in self.devices.iter().filter(|device| device.has_keys) {
+            device.read_keys(&mut bits);
+        }
+        bits
+    }
+
+    /// The keys currently held on any device, in kernel key code order.
+    fn pressed_keys(&self) -> impl Iterator<Item = Keycode> {
+        let bits = self.key_bits();
+        (0..KEY_BYTES * 8)
+            .filter(move |code| test_bit(&bits, *code))
+            .filter_map(|code| kernel_key::kernel_key_to_keycode(code as u16))
+    }
+}
+
+impl DeviceBackend for EvdevBackend {
+    fn query_pointer(&self) -> MouseState {
+        let coords = self.read_input().coords;
+
+        let bits = self.key_bits();
+        let button_pressed = (kernel_key::BTN_LEFT..=BTN_MOUSE_LAST)
+            .filter(|code| test_bit(&bits, *code as usize))
+            .filter_map(MouseButton::from_evdev_code)
+            .collect();
+        MouseState {
+            coords,
+            button_pressed,
+        }
+    }
+
+    fn query_scroll(&self) -> ScrollDelta {
+        mem::take(&mut self.read_input().scroll)
+    }
+
+    fn query_motion(&self) -> MouseDelta {
+        mem::take(&mut self.read_input().motion)
+    }
+
+    fn query_keymap(&self) -> Vec<Keycode> {
+        self.pressed_keys().collect()
+    }
+
+    fn query_key_set(&self) -> KeySet {
+        self.pressed_keys().collect()
+    }
+
+    fn read_events(&self, timeout: Duration, events: &mut Vec<TimedEvent>) -> bool {
+        let waiting = {
+            let mut input = self.input.lock().unwrap_or_else(PoisonError::into_inner);
+            input.event_driven = true;
+            input.events.is_empty()
+        };
+        if waiting {
+            self.wait(timeout);
+        }
+        let mut kept = [0; 8];
+        let _ = (&self.kept_events).read(&mut kept);
+        events.append(&mut self.read_input().events);
+        true
+    }
+}
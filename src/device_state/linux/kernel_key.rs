@@ -1,7 +1,10 @@
-/// A non-exhaustive list of keycodes from Linux. Only the ones that this library currently supports
-/// is currently listed in this file; other keycodes will need to be added later as needed.
-/// Reference: https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
+//! Key codes from Linux, shared by the X11 and evdev backends.
+//! Reference: https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
 
+use keymap::Keycode;
+use std::convert::TryFrom;
+
+pub const KEY_RESERVED: u16 = 0;
 pub const KEY_ESC: u16 = 1;
 pub const KEY_1: u16 = 2;
 pub const KEY_2: u16 = 3;
@@ -70,6 +73,8 @@ pub const KEY_F7: u16 = 65;
 pub const KEY_F8: u16 = 66;
 pub const KEY_F9: u16 = 67;
 pub const KEY_F10: u16 = 68;
+pub const KEY_NUMLOCK: u16 = 69;
+pub const KEY_SCROLLLOCK: u16 = 70;
 pub const KEY_KP7: u16 = 71;
 pub const KEY_KP8: u16 = 72;
 pub const KEY_KP9: u16 = 73;
@@ -83,20 +88,23 @@ pub const KEY_KP2: u16 = 80;
 pub const KEY_KP3: u16 = 81;
 pub const KEY_KP0: u16 = 82;
 pub const KEY_KPDOT: u16 = 83;
+pub const KEY_ZENKAKUHANKAKU: u16 = 85;
+pub const KEY_102ND: u16 = 86;
 pub const KEY_F11: u16 = 87;
 pub const KEY_F12: u16 = 88;
-pub const KEY_F13: u16 = 183;
-pub const KEY_F14: u16 = 184;
-pub const KEY_F15: u16 = 185;
-pub const KEY_F16: u16 = 186;
-pub const KEY_F17: u16 = 187;
-pub const KEY_F18: u16 = 188;
-pub const KEY_F19: u16 = 189;
-pub const KEY_F20: u16 = 190;
+pub const KEY_RO: u16 = 89;
+pub const KEY_KATAKANA: u16 = 90;
+pub const KEY_HIRAGANA: u16 = 91;
+pub const KEY_HENKAN: u16 = 92;
+pub const KEY_KATAKANAHIRAGANA: u16 = 93;
+pub const KEY_MUHENKAN: u16 = 94;
+pub const KEY_KPJPCOMMA: u16 = 95;
 pub const KEY_KPENTER: u16 = 96;
 pub const KEY_RIGHTCTRL: u16 = 97;
 pub const KEY_KPSLASH: u16 = 98;
+pub const KEY_SYSRQ: u16 = 99;
 pub const KEY_RIGHTALT: u16 = 100;
+pub const KEY_LINEFEED: u16 = 101;
 pub const KEY_HOME: u16 = 102;
 pub const KEY_UP: u16 = 103;
 pub const KEY_PAGEUP: u16 = 104;
@@ -107,6 +115,1259 @@ pub const KEY_DOWN: u16 = 108;
 pub const KEY_PAGEDOWN: u16 = 109;
 pub const KEY_INSERT: u16 = 110;
 pub const KEY_DELETE: u16 = 111;
+pub const KEY_MACRO: u16 = 112;
+pub const KEY_MUTE: u16 = 113;
+pub const KEY_VOLUMEDOWN: u16 = 114;
+pub const KEY_VOLUMEUP: u16 = 115;
+pub const KEY_POWER: u16 = 116;
 pub const KEY_KPEQUAL: u16 = 117;
+pub const KEY_KPPLUSMINUS: u16 = 118;
+pub const KEY_PAUSE: u16 = 119;
+pub const KEY_SCALE: u16 = 120;
+pub const KEY_KPCOMMA: u16 = 121;
+pub const KEY_HANGEUL: u16 = 122;
+pub const KEY_HANJA: u16 = 123;
+pub const KEY_YEN: u16 = 124;
 pub const KEY_LEFTMETA: u16 = 125;
 pub const KEY_RIGHTMETA: u16 = 126;
+pub const KEY_COMPOSE: u16 = 127;
+pub const KEY_STOP: u16 = 128;
+pub const KEY_AGAIN: u16 = 129;
+pub const KEY_PROPS: u16 = 130;
+pub const KEY_UNDO: u16 = 131;
+pub const KEY_FRONT: u16 = 132;
+pub const KEY_COPY: u16 = 133;
+pub const KEY_OPEN: u16 = 134;
+pub const KEY_PASTE: u16 = 135;
+pub const KEY_FIND: u16 = 136;
+pub const KEY_CUT: u16 = 137;
+pub const KEY_HELP: u16 = 138;
+pub const KEY_MENU: u16 = 139;
+pub const KEY_CALC: u16 = 140;
+pub const KEY_SETUP: u16 = 141;
+pub const KEY_SLEEP: u16 = 142;
+pub const KEY_WAKEUP: u16 = 143;
+pub const KEY_FILE: u16 = 144;
+pub const KEY_SENDFILE: u16 = 145;
+pub const KEY_DELETEFILE: u16 = 146;
+pub const KEY_XFER: u16 = 147;
+pub const KEY_PROG1: u16 = 148;
+pub const KEY_PROG2: u16 = 149;
+pub const KEY_WWW: u16 = 150;
+pub const KEY_MSDOS: u16 = 151;
+pub const KEY_COFFEE: u16 = 152;
+pub const KEY_ROTATE_DISPLAY: u16 = 153;
+pub const KEY_CYCLEWINDOWS: u16 = 154;
+pub const KEY_MAIL: u16 = 155;
+pub const KEY_BOOKMARKS: u16 = 156;
+pub const KEY_COMPUTER: u16 = 157;
+pub const KEY_BACK: u16 = 158;
+pub const KEY_FORWARD: u16 = 159;
+pub const KEY_CLOSECD: u16 = 160;
+pub const KEY_EJECTCD: u16 = 161;
+pub const KEY_EJECTCLOSECD: u16 = 162;
+pub const KEY_NEXTSONG: u16 = 163;
+pub const KEY_PLAYPAUSE: u16 = 164;
+pub const KEY_PREVIOUSSONG: u16 = 165;
+pub const KEY_STOPCD: u16 = 166;
+pub const KEY_RECORD: u16 = 167;
+pub const KEY_REWIND: u16 = 168;
+pub const KEY_PHONE: u16 = 169;
+pub const KEY_ISO: u16 = 170;
+pub const KEY_CONFIG: u16 = 171;
+pub const KEY_HOMEPAGE: u16 = 172;
+pub const KEY_REFRESH: u16 = 173;
+pub const KEY_EXIT: u16 = 174;
+pub const KEY_MOVE: u16 = 175;
+pub const KEY_EDIT: u16 = 176;
+pub const KEY_SCROLLUP: u16 = 177;
+pub const KEY_SCROLLDOWN: u16 = 178;
+pub const KEY_KPLEFTPAREN: u16 = 179;
+pub const KEY_KPRIGHTPAREN: u16 = 180;
+pub const KEY_NEW: u16 = 181;
+pub const KEY_REDO: u16 = 182;
+pub const KEY_F13: u16 = 183;
+pub const KEY_F14: u16 = 184;
+pub const KEY_F15: u16 = 185;
+pub const KEY_F16: u16 = 186;
+pub const KEY_F17: u16 = 187;
+pub const KEY_F18: u16 = 188;
+pub const KEY_F19: u16 = 189;
+pub const KEY_F20: u16 = 190;
+pub const KEY_F21: u16 = 191;
+pub const KEY_F22: u16 = 192;
+pub const KEY_F23: u16 = 193;
+pub const KEY_F24: u16 = 194;
+pub const KEY_PLAYCD: u16 = 200;
+pub const KEY_PAUSECD: u16 = 201;
+pub const KEY_PROG3: u16 = 202;
+pub const KEY_PROG4: u16 = 203;
+pub const KEY_ALL_APPLICATIONS: u16 = 204;
+pub const KEY_SUSPEND: u16 = 205;
+pub const KEY_CLOSE: u16 = 206;
+pub const KEY_PLAY: u16 = 207;
+pub const KEY_FASTFORWARD: u16 = 208;
+pub const KEY_BASSBOOST: u16 = 209;
+pub const KEY_PRINT: u16 = 210;
+pub const KEY_HP: u16 = 211;
+pub const KEY_CAMERA: u16 = 212;
+pub const KEY_SOUND: u16 = 213;
+pub const KEY_QUESTION: u16 = 214;
+pub const KEY_EMAIL: u16 = 215;
+pub const KEY_CHAT: u16 = 216;
+pub const KEY_SEARCH: u16 = 217;
+pub const KEY_CONNECT: u16 = 218;
+pub const KEY_FINANCE: u16 = 219;
+pub const KEY_SPORT: u16 = 220;
+pub const KEY_SHOP: u16 = 221;
+pub const KEY_ALTERASE: u16 = 222;
+pub const KEY_CANCEL: u16 = 223;
+pub const KEY_BRIGHTNESSDOWN: u16 = 224;
+pub const KEY_BRIGHTNESSUP: u16 = 225;
+pub const KEY_MEDIA: u16 = 226;
+pub const KEY_SWITCHVIDEOMODE: u16 = 227;
+pub const KEY_KBDILLUMTOGGLE: u16 = 228;
+pub const KEY_KBDILLUMDOWN: u16 = 229;
+pub const KEY_KBDILLUMUP: u16 = 230;
+pub const KEY_SEND: u16 = 231;
+pub const KEY_REPLY: u16 = 232;
+pub const KEY_FORWARDMAIL: u16 = 233;
+pub const KEY_SAVE: u16 = 234;
+pub const KEY_DOCUMENTS: u16 = 235;
+pub const KEY_BATTERY: u16 = 236;
+pub const KEY_BLUETOOTH: u16 = 237;
+pub const KEY_WLAN: u16 = 238;
+pub const KEY_UWB: u16 = 239;
+pub const KEY_UNKNOWN: u16 = 240;
+pub const KEY_VIDEO_NEXT: u16 = 241;
+pub const KEY_VIDEO_PREV: u16 = 242;
+pub const KEY_BRIGHTNESS_CYCLE: u16 = 243;
+pub const KEY_BRIGHTNESS_AUTO: u16 = 244;
+pub const KEY_DISPLAY_OFF: u16 = 245;
+pub const KEY_WWAN: u16 = 246;
+pub const KEY_RFKILL: u16 = 247;
+pub const KEY_MICMUTE: u16 = 248;
+pub const BTN_0: u16 = 0x100;
+pub const BTN_1: u16 = 0x101;
+pub const BTN_2: u16 = 0x102;
+pub const BTN_3: u16 = 0x103;
+pub const BTN_4: u16 = 0x104;
+pub const BTN_5: u16 = 0x105;
+pub const BTN_6: u16 = 0x106;
+pub const BTN_7: u16 = 0x107;
+pub const BTN_8: u16 = 0x108;
+pub const BTN_9: u16 = 0x109;
+pub const BTN_LEFT: u16 = 0x110;
+pub const BTN_RIGHT: u16 = 0x111;
+pub const BTN_MIDDLE: u16 = 0x112;
+pub const BTN_SIDE: u16 = 0x113;
+pub const BTN_EXTRA: u16 = 0x114;
+pub const BTN_FORWARD: u16 = 0x115;
+pub const BTN_BACK: u16 = 0x116;
+pub const BTN_TASK: u16 = 0x117;
+pub const BTN_TRIGGER: u16 = 0x120;
+pub const BTN_THUMB: u16 = 0x121;
+pub const BTN_THUMB2: u16 = 0x122;
+pub const BTN_TOP: u16 = 0x123;
+pub const BTN_TOP2: u16 = 0x124;
+pub const BTN_PINKIE: u16 = 0x125;
+pub const BTN_BASE: u16 = 0x126;
+pub const BTN_BASE2: u16 = 0x127;
+pub const BTN_BASE3: u16 = 0x128;
+pub const BTN_BASE4: u16 = 0x129;
+pub const BTN_BASE5: u16 = 0x12a;
+pub const BTN_BASE6: u16 = 0x12b;
+pub const BTN_DEAD: u16 = 0x12f;
+pub const BTN_SOUTH: u16 = 0x130;
+pub const BTN_EAST: u16 = 0x131;
+pub const BTN_C: u16 = 0x132;
+pub const BTN_NORTH: u16 = 0x133;
+pub const BTN_WEST: u16 = 0x134;
+pub const BTN_Z: u16 = 0x135;
+pub const BTN_TL: u16 = 0x136;
+pub const BTN_TR: u16 = 0x137;
+pub const BTN_TL2: u16 = 0x138;
+pub const BTN_TR2: u16 = 0x139;
+pub const BTN_SELECT: u16 = 0x13a;
+pub const BTN_START: u16 = 0x13b;
+pub const BTN_MODE: u16 = 0x13c;
+pub const BTN_THUMBL: u16 = 0x13d;
+pub const BTN_THUMBR: u16 = 0x13e;
+pub const BTN_TOOL_PEN: u16 = 0x140;
+pub const BTN_TOOL_RUBBER: u16 = 0x141;
+pub const BTN_TOOL_BRUSH: u16 = 0x142;
+pub const BTN_TOOL_PENCIL: u16 = 0x143;
+pub const BTN_TOOL_AIRBRUSH: u16 = 0x144;
+pub const BTN_TOOL_FINGER: u16 = 0x145;
+pub const BTN_TOOL_MOUSE: u16 = 0x146;
+pub const BTN_TOOL_LENS: u16 = 0x147;
+pub const BTN_TOOL_QUINTTAP: u16 = 0x148;
+pub const BTN_STYLUS3: u16 = 0x149;
+pub const BTN_TOUCH: u16 = 0x14a;
+pub const BTN_STYLUS: u16 = 0x14b;
+pub const BTN_STYLUS2: u16 = 0x14c;
+pub const BTN_TOOL_DOUBLETAP: u16 = 0x14d;
+pub const BTN_TOOL_TRIPLETAP: u16 = 0x14e;
+pub const BTN_TOOL_QUADTAP: u16 = 0x14f;
+pub const BTN_GEAR_DOWN: u16 = 0x150;
+pub const BTN_GEAR_UP: u16 = 0x151;
+pub const KEY_OK: u16 = 0x160;
+pub const KEY_SELECT: u16 = 0x161;
+pub const KEY_GOTO: u16 = 0x162;
+pub const KEY_CLEAR: u16 = 0x163;
+pub const KEY_POWER2: u16 = 0x164;
+pub const KEY_OPTION: u16 = 0x165;
+pub const KEY_INFO: u16 = 0x166;
+pub const KEY_TIME: u16 = 0x167;
+pub const KEY_VENDOR: u16 = 0x168;
+pub const KEY_ARCHIVE: u16 = 0x169;
+pub const KEY_PROGRAM: u16 = 0x16a;
+pub const KEY_CHANNEL: u16 = 0x16b;
+pub const KEY_FAVORITES: u16 = 0x16c;
+pub const KEY_EPG: u16 = 0x16d;
+pub const KEY_PVR: u16 = 0x16e;
+pub const KEY_MHP: u16 = 0x16f;
+pub const KEY_LANGUAGE: u16 = 0x170;
+pub const KEY_TITLE: u16 = 0x171;
+pub const KEY_SUBTITLE: u16 = 0x172;
+pub const KEY_ANGLE: u16 = 0x173;
+pub const KEY_FULL_SCREEN: u16 = 0x174;
+pub const KEY_MODE: u16 = 0x175;
+pub const KEY_KEYBOARD: u16 = 0x176;
+pub const KEY_ASPECT_RATIO: u16 = 0x177;
+pub const KEY_PC: u16 = 0x178;
+pub const KEY_TV: u16 = 0x179;
+pub const KEY_TV2: u16 = 0x17a;
+pub const KEY_VCR: u16 = 0x17b;
+pub const KEY_VCR2: u16 = 0x17c;
+pub const KEY_SAT: u16 = 0x17d;
+pub const KEY_SAT2: u16 = 0x17e;
+pub const KEY_CD: u16 = 0x17f;
+pub const KEY_TAPE: u16 = 0x180;
+pub const KEY_RADIO: u16 = 0x181;
+pub const KEY_TUNER: u16 = 0x182;
+pub const KEY_PLAYER: u16 = 0x183;
+pub const KEY_TEXT: u16 = 0x184;
+pub const KEY_DVD: u16 = 0x185;
+pub const KEY_AUX: u16 = 0x186;
+pub const KEY_MP3: u16 = 0x187;
+pub const KEY_AUDIO: u16 = 0x188;
+pub const KEY_VIDEO: u16 = 0x189;
+pub const KEY_DIRECTORY: u16 = 0x18a;
+pub const KEY_LIST: u16 = 0x18b;
+pub const KEY_MEMO: u16 = 0x18c;
+pub const KEY_CALENDAR: u16 = 0x18d;
+pub const KEY_RED: u16 = 0x18e;
+pub const KEY_GREEN: u16 = 0x18f;
+pub const KEY_YELLOW: u16 = 0x190;
+pub const KEY_BLUE: u16 = 0x191;
+pub const KEY_CHANNELUP: u16 = 0x192;
+pub const KEY_CHANNELDOWN: u16 = 0x193;
+pub const KEY_FIRST: u16 = 0x194;
+pub const KEY_LAST: u16 = 0x195;
+pub const KEY_AB: u16 = 0x196;
+pub const KEY_NEXT: u16 = 0x197;
+pub const KEY_RESTART: u16 = 0x198;
+pub const KEY_SLOW: u16 = 0x199;
+pub const KEY_SHUFFLE: u16 = 0x19a;
+pub const KEY_BREAK: u16 = 0x19b;
+pub const KEY_PREVIOUS: u16 = 0x19c;
+pub const KEY_DIGITS: u16 = 0x19d;
+pub const KEY_TEEN: u16 = 0x19e;
+pub const KEY_TWEN: u16 = 0x19f;
+pub const KEY_VIDEOPHONE: u16 = 0x1a0;
+pub const KEY_GAMES: u16 = 0x1a1;
+pub const KEY_ZOOMIN: u16 = 0x1a2;
+pub const KEY_ZOOMOUT: u16 = 0x1a3;
+pub const KEY_ZOOMRESET: u16 = 0x1a4;
+pub const KEY_WORDPROCESSOR: u16 = 0x1a5;
+pub const KEY_EDITOR: u16 = 0x1a6;
+pub const KEY_SPREADSHEET: u16 = 0x1a7;
+pub const KEY_GRAPHICSEDITOR: u16 = 0x1a8;
+pub const KEY_PRESENTATION: u16 = 0x1a9;
+pub const KEY_DATABASE: u16 = 0x1aa;
+pub const KEY_NEWS: u16 = 0x1ab;
+pub const KEY_VOICEMAIL: u16 = 0x1ac;
+pub const KEY_ADDRESSBOOK: u16 = 0x1ad;
+pub const KEY_MESSENGER: u16 = 0x1ae;
+pub const KEY_DISPLAYTOGGLE: u16 = 0x1af;
+pub const KEY_SPELLCHECK: u16 = 0x1b0;
+pub const KEY_LOGOFF: u16 = 0x1b1;
+pub const KEY_DOLLAR: u16 = 0x1b2;
+pub const KEY_EURO: u16 = 0x1b3;
+pub const KEY_FRAMEBACK: u16 = 0x1b4;
+pub const KEY_FRAMEFORWARD: u16 = 0x1b5;
+pub const KEY_CONTEXT_MENU: u16 = 0x1b6;
+pub const KEY_MEDIA_REPEAT: u16 = 0x1b7;
+pub const KEY_10CHANNELSUP: u16 = 0x1b8;
+pub const KEY_10CHANNELSDOWN: u16 = 0x1b9;
+pub const KEY_IMAGES: u16 = 0x1ba;
+pub const KEY_NOTIFICATION_CENTER: u16 = 0x1bc;
+pub const KEY_PICKUP_PHONE: u16 = 0x1bd;
+pub const KEY_HANGUP_PHONE: u16 = 0x1be;
+pub const KEY_LINK_PHONE: u16 = 0x1bf;
+pub const KEY_DEL_EOL: u16 = 0x1c0;
+pub const KEY_DEL_EOS: u16 = 0x1c1;
+pub const KEY_INS_LINE: u16 = 0x1c2;
+pub const KEY_DEL_LINE: u16 = 0x1c3;
+pub const KEY_FN: u16 = 0x1d0;
+pub const KEY_FN_ESC: u16 = 0x1d1;
+pub const KEY_FN_F1: u16 = 0x1d2;
+pub const KEY_FN_F2: u16 = 0x1d3;
+pub const KEY_FN_F3: u16 = 0x1d4;
+pub const KEY_FN_F4: u16 = 0x1d5;
+pub const KEY_FN_F5: u16 = 0x1d6;
+pub const KEY_FN_F6: u16 = 0x1d7;
+pub const KEY_FN_F7: u16 = 0x1d8;
+pub const KEY_FN_F8: u16 = 0x1d9;
+pub const KEY_FN_F9: u16 = 0x1da;
+pub const KEY_FN_F10: u16 = 0x1db;
+pub const KEY_FN_F11: u16 = 0x1dc;
+pub const KEY_FN_F12: u16 = 0x1dd;
+pub const KEY_FN_1: u16 = 0x1de;
+pub const KEY_FN_2: u16 = 0x1df;
+pub const KEY_FN_D: u16 = 0x1e0;
+pub const KEY_FN_E: u16 = 0x1e1;
+pub const KEY_FN_F: u16 = 0x1e2;
+pub const KEY_FN_S: u16 = 0x1e3;
+pub const KEY_FN_B: u16 = 0x1e4;
+pub const KEY_FN_RIGHT_SHIFT: u16 = 0x1e5;
+pub const KEY_BRL_DOT1: u16 = 0x1f1;
+pub const KEY_BRL_DOT2: u16 = 0x1f2;
+pub const KEY_BRL_DOT3: u16 = 0x1f3;
+pub const KEY_BRL_DOT4: u16 = 0x1f4;
+pub const KEY_BRL_DOT5: u16 = 0x1f5;
+pub const KEY_BRL_DOT6: u16 = 0x1f6;
+pub const KEY_BRL_DOT7: u16 = 0x1f7;
+pub const KEY_BRL_DOT8: u16 = 0x1f8;
+pub const KEY_BRL_DOT9: u16 = 0x1f9;
+pub const KEY_BRL_DOT10: u16 = 0x1fa;
+pub const KEY_NUMERIC_0: u16 = 0x200;
+pub const KEY_NUMERIC_1: u16 = 0x201;
+pub const KEY_NUMERIC_2: u16 = 0x202;
+pub const KEY_NUMERIC_3: u16 = 0x203;
+pub const KEY_NUMERIC_4: u16 = 0x204;
+pub const KEY_NUMERIC_5: u16 = 0x205;
+pub const KEY_NUMERIC_6: u16 = 0x206;
+pub const KEY_NUMERIC_7: u16 = 0x207;
+pub const KEY_NUMERIC_8: u16 = 0x208;
+pub const KEY_NUMERIC_9: u16 = 0x209;
+pub const KEY_NUMERIC_STAR: u16 = 0x20a;
+pub const KEY_NUMERIC_POUND: u16 = 0x20b;
+pub const KEY_NUMERIC_A: u16 = 0x20c;
+pub const KEY_NUMERIC_B: u16 = 0x20d;
+pub const KEY_NUMERIC_C: u16 = 0x20e;
+pub const KEY_NUMERIC_D: u16 = 0x20f;
+pub const KEY_CAMERA_FOCUS: u16 = 0x210;
+pub const KEY_WPS_BUTTON: u16 = 0x211;
+pub const KEY_TOUCHPAD_TOGGLE: u16 = 0x212;
+pub const KEY_TOUCHPAD_ON: u16 = 0x213;
+pub const KEY_TOUCHPAD_OFF: u16 = 0x214;
+pub const KEY_CAMERA_ZOOMIN: u16 = 0x215;
+pub const KEY_CAMERA_ZOOMOUT: u16 = 0x216;
+pub const KEY_CAMERA_UP: u16 = 0x217;
+pub const KEY_CAMERA_DOWN: u16 = 0x218;
+pub const KEY_CAMERA_LEFT: u16 = 0x219;
+pub const KEY_CAMERA_RIGHT: u16 = 0x21a;
+pub const KEY_ATTENDANT_ON: u16 = 0x21b;
+pub const KEY_ATTENDANT_OFF: u16 = 0x21c;
+pub const KEY_ATTENDANT_TOGGLE: u16 = 0x21d;
+pub const KEY_LIGHTS_TOGGLE: u16 = 0x21e;
+pub const BTN_DPAD_UP: u16 = 0x220;
+pub const BTN_DPAD_DOWN: u16 = 0x221;
+pub const BTN_DPAD_LEFT: u16 = 0x222;
+pub const BTN_DPAD_RIGHT: u16 = 0x223;
+pub const KEY_ALS_TOGGLE: u16 = 0x230;
+pub const KEY_ROTATE_LOCK_TOGGLE: u16 = 0x231;
+pub const KEY_REFRESH_RATE_TOGGLE: u16 = 0x232;
+pub const KEY_BUTTONCONFIG: u16 = 0x240;
+pub const KEY_TASKMANAGER: u16 = 0x241;
+pub const KEY_JOURNAL: u16 = 0x242;
+pub const KEY_CONTROLPANEL: u16 = 0x243;
+pub const KEY_APPSELECT: u16 = 0x244;
+pub const KEY_SCREENSAVER: u16 = 0x245;
+pub const KEY_VOICECOMMAND: u16 = 0x246;
+pub const KEY_ASSISTANT: u16 = 0x247;
+pub const KEY_KBD_LAYOUT_NEXT: u16 = 0x248;
+pub const KEY_EMOJI_PICKER: u16 = 0x249;
+pub const KEY_DICTATE: u16 = 0x24a;
+pub const KEY_BRIGHTNESS_MIN: u16 = 0x250;
+pub const KEY_BRIGHTNESS_MAX: u16 = 0x251;
+pub const KEY_KBDINPUTASSIST_PREV: u16 = 0x260;
+pub const KEY_KBDINPUTASSIST_NEXT: u16 = 0x261;
+pub const KEY_KBDINPUTASSIST_PREVGROUP: u16 = 0x262;
+pub const KEY_KBDINPUTASSIST_NEXTGROUP: u16 = 0x263;
+pub const KEY_KBDINPUTASSIST_ACCEPT: u16 = 0x264;
+pub const KEY_KBDINPUTASSIST_CANCEL: u16 = 0x265;
+pub const KEY_RIGHT_UP: u16 = 0x266;
+pub const KEY_RIGHT_DOWN: u16 = 0x267;
+pub const KEY_LEFT_UP: u16 = 0x268;
+pub const KEY_LEFT_DOWN: u16 = 0x269;
+pub const KEY_ROOT_MENU: u16 = 0x26a;
+pub const KEY_MEDIA_TOP_MENU: u16 = 0x26b;
+pub const KEY_NUMERIC_11: u16 = 0x26c;
+pub const KEY_NUMERIC_12: u16 = 0x26d;
+pub const KEY_AUDIO_DESC: u16 = 0x26e;
+pub const KEY_3D_MODE: u16 = 0x26f;
+pub const KEY_NEXT_FAVORITE: u16 = 0x270;
+pub const KEY_STOP_RECORD: u16 = 0x271;
+pub const KEY_PAUSE_RECORD: u16 = 0x272;
+pub const KEY_VOD: u16 = 0x273;
+pub const KEY_UNMUTE: u16 = 0x274;
+pub const KEY_FASTREVERSE: u16 = 0x275;
+pub const KEY_SLOWREVERSE: u16 = 0x276;
+pub const KEY_DATA: u16 = 0x277;
+pub const KEY_ONSCREEN_KEYBOARD: u16 = 0x278;
+pub const KEY_PRIVACY_SCREEN_TOGGLE: u16 = 0x279;
+pub const KEY_SELECTIVE_SCREENSHOT: u16 = 0x27a;
+pub const KEY_NEXT_ELEMENT: u16 = 0x27b;
+pub const KEY_PREVIOUS_ELEMENT: u16 = 0x27c;
+pub const KEY_AUTOPILOT_ENGAGE_TOGGLE: u16 = 0x27d;
+pub const KEY_MARK_WAYPOINT: u16 = 0x27e;
+pub const KEY_SOS: u16 = 0x27f;
+pub const KEY_NAV_CHART: u16 = 0x280;
+pub const KEY_FISHING_CHART: u16 = 0x281;
+pub const KEY_SINGLE_RANGE_RADAR: u16 = 0x282;
+pub const KEY_DUAL_RANGE_RADAR: u16 = 0x283;
+pub const KEY_RADAR_OVERLAY: u16 = 0x284;
+pub const KEY_TRADITIONAL_SONAR: u16 = 0x285;
+pub const KEY_CLEARVU_SONAR: u16 = 0x286;
+pub const KEY_SIDEVU_SONAR: u16 = 0x287;
+pub const KEY_NAV_INFO: u16 = 0x288;
+pub const KEY_BRIGHTNESS_MENU: u16 = 0x289;
+pub const KEY_MACRO1: u16 = 0x290;
+pub const KEY_MACRO2: u16 = 0x291;
+pub const KEY_MACRO3: u16 = 0x292;
+pub const KEY_MACRO4: u16 = 0x293;
+pub const KEY_MACRO5: u16 = 0x294;
+pub const KEY_MACRO6: u16 = 0x295;
+pub const KEY_MACRO7: u16 = 0x296;
+pub const KEY_MACRO8: u16 = 0x297;
+pub const KEY_MACRO9: u16 = 0x298;
+pub const KEY_MACRO10: u16 = 0x299;
+pub const KEY_MACRO11: u16 = 0x29a;
+pub const KEY_MACRO12: u16 = 0x29b;
+pub const KEY_MACRO13: u16 = 0x29c;
+pub const KEY_MACRO14: u16 = 0x29d;
+pub const KEY_MACRO15: u16 = 0x29e;
+pub const KEY_MACRO16: u16 = 0x29f;
+pub const KEY_MACRO17: u16 = 0x2a0;
+pub const KEY_MACRO18: u16 = 0x2a1;
+pub const KEY_MACRO19: u16 = 0x2a2;
+pub const KEY_MACRO20: u16 = 0x2a3;
+pub const KEY_MACRO21: u16 = 0x2a4;
+pub const KEY_MACRO22: u16 = 0x2a5;
+pub const KEY_MACRO23: u16 = 0x2a6;
+pub const KEY_MACRO24: u16 = 0x2a7;
+pub const KEY_MACRO25: u16 = 0x2a8;
+pub const KEY_MACRO26: u16 = 0x2a9;
+pub const KEY_MACRO27: u16 = 0x2aa;
+pub const KEY_MACRO28: u16 = 0x2ab;
+pub const KEY_MACRO29: u16 = 0x2ac;
+pub const KEY_MACRO30: u16 = 0x2ad;
+pub const KEY_MACRO_RECORD_START: u16 = 0x2b0;
+pub const KEY_MACRO_RECORD_STOP: u16 = 0x2b1;
+pub const KEY_MACRO_PRESET_CYCLE: u16 = 0x2b2;
+pub const KEY_MACRO_PRESET1: u16 = 0x2b3;
+pub const KEY_MACRO_PRESET2: u16 = 0x2b4;
+pub const KEY_MACRO_PRESET3: u16 = 0x2b5;
+pub const KEY_KBD_LCD_MENU1: u16 = 0x2b8;
+pub const KEY_KBD_LCD_MENU2: u16 = 0x2b9;
+pub const KEY_KBD_LCD_MENU3: u16 = 0x2ba;
+pub const KEY_KBD_LCD_MENU4: u16 = 0x2bb;
+pub const KEY_KBD_LCD_MENU5: u16 = 0x2bc;
+pub const BTN_TRIGGER_HAPPY1: u16 = 0x2c0;
+pub const BTN_TRIGGER_HAPPY2: u16 = 0x2c1;
+pub const BTN_TRIGGER_HAPPY3: u16 = 0x2c2;
+pub const BTN_TRIGGER_HAPPY4: u16 = 0x2c3;
+pub const BTN_TRIGGER_HAPPY5: u16 = 0x2c4;
+pub const BTN_TRIGGER_HAPPY6: u16 = 0x2c5;
+pub const BTN_TRIGGER_HAPPY7: u16 = 0x2c6;
+pub const BTN_TRIGGER_HAPPY8: u16 = 0x2c7;
+pub const BTN_TRIGGER_HAPPY9: u16 = 0x2c8;
+pub const BTN_TRIGGER_HAPPY10: u16 = 0x2c9;
+pub const BTN_TRIGGER_HAPPY11: u16 = 0x2ca;
+pub const BTN_TRIGGER_HAPPY12: u16 = 0x2cb;
+pub const BTN_TRIGGER_HAPPY13: u16 = 0x2cc;
+pub const BTN_TRIGGER_HAPPY14: u16 = 0x2cd;
+pub const BTN_TRIGGER_HAPPY15: u16 = 0x2ce;
+pub const BTN_TRIGGER_HAPPY16: u16 = 0x2cf;
+pub const BTN_TRIGGER_HAPPY17: u16 = 0x2d0;
+pub const BTN_TRIGGER_HAPPY18: u16 = 0x2d1;
+pub const BTN_TRIGGER_HAPPY19: u16 = 0x2d2;
+pub const BTN_TRIGGER_HAPPY20: u16 = 0x2d3;
+pub const BTN_TRIGGER_HAPPY21: u16 = 0x2d4;
+pub const BTN_TRIGGER_HAPPY22: u16 = 0x2d5;
+pub const BTN_TRIGGER_HAPPY23: u16 = 0x2d6;
+pub const BTN_TRIGGER_HAPPY24: u16 = 0x2d7;
+pub const BTN_TRIGGER_HAPPY25: u16 = 0x2d8;
+pub const BTN_TRIGGER_HAPPY26: u16 = 0x2d9;
+pub const BTN_TRIGGER_HAPPY27: u16 = 0x2da;
+pub const BTN_TRIGGER_HAPPY28: u16 = 0x2db;
+pub const BTN_TRIGGER_HAPPY29: u16 = 0x2dc;
+pub const BTN_TRIGGER_HAPPY30: u16 = 0x2dd;
+pub const BTN_TRIGGER_HAPPY31: u16 = 0x2de;
+pub const BTN_TRIGGER_HAPPY32: u16 = 0x2df;
+pub const BTN_TRIGGER_HAPPY33: u16 = 0x2e0;
+pub const BTN_TRIGGER_HAPPY34: u16 = 0x2e1;
+pub const BTN_TRIGGER_HAPPY35: u16 = 0x2e2;
+pub const BTN_TRIGGER_HAPPY36: u16 = 0x2e3;
+pub const BTN_TRIGGER_HAPPY37: u16 = 0x2e4;
+pub const BTN_TRIGGER_HAPPY38: u16 = 0x2e5;
+pub const BTN_TRIGGER_HAPPY39: u16 = 0x2e6;
+pub const BTN_TRIGGER_HAPPY40: u16 = 0x2e7;
+pub const KEY_MAX: u16 = 0x2ff;
+
+/// Every key and button code defined by `input-event-codes.h`, in increasing order, with the
+/// `Keycode` it converts to. Codes without a `Keycode` are deliberately left unmapped: buttons are
+/// reported through `MouseState` or not at all, and the other keys are reported as
+/// `Keycode::Unknown`.
+const KERNEL_KEYS: [(u16, Option<Keycode>); 612] = [
+    (KEY_RESERVED, None),
+    (KEY_ESC, Some(Keycode::Escape)),
+    (KEY_1, Some(Keycode::Key1)),
+    (KEY_2, Some(Keycode::Key2)),
+    (KEY_3, Some(Keycode::Key3)),
+    (KEY_4, Some(Keycode::Key4)),
+    (KEY_5, Some(Keycode::Key5)),
+    (KEY_6, Some(Keycode::Key6)),
+    (KEY_7, Some(Keycode::Key7)),
+    (KEY_8, Some(Keycode::Key8)),
+    (KEY_9, Some(Keycode::Key9)),
+    (KEY_0, Some(Keycode::Key0)),
+    (KEY_MINUS, Some(Keycode::Minus)),
+    (KEY_EQUAL, Some(Keycode::Equal)),
+    (KEY_BACKSPACE, Some(Keycode::Backspace)),
+    (KEY_TAB, Some(Keycode::Tab)),
+    (KEY_Q, Some(Keycode::Q)),
+    (KEY_W, Some(Keycode::W)),
+    (KEY_E, Some(Keycode::E)),
+    (KEY_R, Some(Keycode::R)),
+    (KEY_T, Some(Keycode::T)),
+    (KEY_Y, Some(Keycode::Y)),
+    (KEY_U, Some(Keycode::U)),
+    (KEY_I, Some(Keycode::I)),
+    (KEY_O, Some(Keycode::O)),
+    (KEY_P, Some(Keycode::P)),
+    (KEY_LEFTBRACE, Some(Keycode::LeftBracket)),
+    (KEY_RIGHTBRACE, Some(Keycode::RightBracket)),
+    (KEY_ENTER, Some(Keycode::Enter)),
+    (KEY_LEFTCTRL, Some(Keycode::LControl)),
+    (KEY_A, Some(Keycode::A)),
+    (KEY_S, Some(Keycode::S)),
+    (KEY_D, Some(Keycode::D)),
+    (KEY_F, Some(Keycode::F)),
+    (KEY_G, Some(Keycode::G)),
+    (KEY_H, Some(Keycode::H)),
+    (KEY_J, Some(Keycode::J)),
+    (KEY_K, Some(Keycode::K)),
+    (KEY_L, Some(Keycode::L)),
+    (KEY_SEMICOLON, Some(Keycode::Semicolon)),
+    (KEY_APOSTROPHE, Some(Keycode::Apostrophe)),
+    (KEY_GRAVE, Some(Keycode::Grave)),
+    (KEY_LEFTSHIFT, Some(Keycode::LShift)),
+    (KEY_BACKSLASH, Some(Keycode::BackSlash)),
+    (KEY_Z, Some(Keycode::Z)),
+    (KEY_X, Some(Keycode::X)),
+    (KEY_C, Some(Keycode::C)),
+    (KEY_V, Some(Keycode::V)),
+    (KEY_B, Some(Keycode::B)),
+    (KEY_N, Some(Keycode::N)),
+    (KEY_M, Some(Keycode::M)),
+    (KEY_COMMA, Some(Keycode::Comma)),
+    (KEY_DOT, Some(Keycode::Dot)),
+    (KEY_SLASH, Some(Keycode::Slash)),
+    (KEY_RIGHTSHIFT, Some(Keycode::RShift)),
+    (KEY_KPASTERISK, Some(Keycode::NumpadMultiply)),
+    (KEY_LEFTALT, Some(Keycode::LAlt)),
+    (KEY_SPACE, Some(Keycode::Space)),
+    (KEY_CAPSLOCK, Some(Keycode::CapsLock)),
+    (KEY_F1, Some(Keycode::F1)),
+    (KEY_F2, Some(Keycode::F2)),
+    (KEY_F3, Some(Keycode::F3)),
+    (KEY_F4, Some(Keycode::F4)),
+    (KEY_F5, Some(Keycode::F5)),
+    (KEY_F6, Some(Keycode::F6)),
+    (KEY_F7, Some(Keycode::F7)),
+    (KEY_F8, Some(Keycode::F8)),
+    (KEY_F9, Some(Keycode::F9)),
+    (KEY_F10, Some(Keycode::F10)),
+    (KEY_NUMLOCK, Some(Keycode::NumLock)),
+    (KEY_SCROLLLOCK, Some(Keycode::ScrollLock)),
+    (KEY_KP7, Some(Keycode::Numpad7)),
+    (KEY_KP8, Some(Keycode::Numpad8)),
+    (KEY_KP9, Some(Keycode::Numpad9)),
+    (KEY_KPMINUS, Some(Keycode::NumpadSubtract)),
+    (KEY_KP4, Some(Keycode::Numpad4)),
+    (KEY_KP5, Some(Keycode::Numpad5)),
+    (KEY_KP6, Some(Keycode::Numpad6)),
+    (KEY_KPPLUS, Some(Keycode::NumpadAdd)),
+    (KEY_KP1, Some(Keycode::Numpad1)),
+    (KEY_KP2, Some(Keycode::Numpad2)),
+    (KEY_KP3, Some(Keycode::Numpad3)),
+    (KEY_KP0, Some(Keycode::Numpad0)),
+    (KEY_KPDOT, Some(Keycode::NumpadDecimal)),
+    (KEY_ZENKAKUHANKAKU, Some(Keycode::ZenkakuHankaku)),
+    (KEY_102ND, Some(Keycode::IntlBackslash)),
+    (KEY_F11, Some(Keycode::F11)),
+    (KEY_F12, Some(Keycode::F12)),
+    (KEY_RO, Some(Keycode::IntlRo)),
+    (KEY_KATAKANA, Some(Keycode::Katakana)),
+    (KEY_HIRAGANA, Some(Keycode::Hiragana)),
+    (KEY_HENKAN, Some(Keycode::Convert)),
+    (KEY_KATAKANAHIRAGANA, Some(Keycode::KanaMode)),
+    (KEY_MUHENKAN, Some(Keycode::NonConvert)),
+    (KEY_KPJPCOMMA, None),
+    (KEY_KPENTER, Some(Keycode::NumpadEnter)),
+    (KEY_RIGHTCTRL, Some(Keycode::RControl)),
+    (KEY_KPSLASH, Some(Keycode::NumpadDivide)),
+    (KEY_SYSRQ, Some(Keycode::PrintScreen)),
+    (KEY_RIGHTALT, Some(Keycode::RAlt)),
+    (KEY_LINEFEED, None),
+    (KEY_HOME, Some(Keycode::Home)),
+    (KEY_UP, Some(Keycode::Up)),
+    (KEY_PAGEUP, Some(Keycode::PageUp)),
+    (KEY_LEFT, Some(Keycode::Left)),
+    (KEY_RIGHT, Some(Keycode::Right)),
+    (KEY_END, Some(Keycode::End)),
+    (KEY_DOWN, Some(Keycode::Down)),
+    (KEY_PAGEDOWN, Some(Keycode::PageDown)),
+    (KEY_INSERT, Some(Keycode::Insert)),
+    (KEY_DELETE, Some(Keycode::Delete)),
+    (KEY_MACRO, None),
+    (KEY_MUTE, Some(Keycode::VolumeMute)),
+    (KEY_VOLUMEDOWN, Some(Keycode::VolumeDown)),
+    (KEY_VOLUMEUP, Some(Keycode::VolumeUp)),
+    (KEY_POWER, None),
+    (KEY_KPEQUAL, Some(Keycode::NumpadEquals)),
+    (KEY_KPPLUSMINUS, None),
+    (KEY_PAUSE, Some(Keycode::Pause)),
+    (KEY_SCALE, None),
+    (KEY_KPCOMMA, Some(Keycode::NumpadComma)),
+    (KEY_HANGEUL, Some(Keycode::Lang1)),
+    (KEY_HANJA, Some(Keycode::Lang2)),
+    (KEY_YEN, Some(Keycode::IntlYen)),
+    (KEY_LEFTMETA, Some(Keycode::LMeta)),
+    (KEY_RIGHTMETA, Some(Keycode::RMeta)),
+    (KEY_COMPOSE, Some(Keycode::Menu)),
+    (KEY_STOP, Some(Keycode::BrowserStop)),
+    (KEY_AGAIN, None),
+    (KEY_PROPS, None),
+    (KEY_UNDO, None),
+    (KEY_FRONT, None),
+    (KEY_COPY, None),
+    (KEY_OPEN, None),
+    (KEY_PASTE, None),
+    (KEY_FIND, None),
+    (KEY_CUT, None),
+    (KEY_HELP, None),
+    (KEY_MENU, None),
+    (KEY_CALC, None),
+    (KEY_SETUP, None),
+    (KEY_SLEEP, None),
+    (KEY_WAKEUP, None),
+    (KEY_FILE, None),
+    (KEY_SENDFILE, None),
+    (KEY_DELETEFILE, None),
+    (KEY_XFER, None),
+    (KEY_PROG1, None),
+    (KEY_PROG2, None),
+    (KEY_WWW, None),
+    (KEY_MSDOS, None),
+    (KEY_COFFEE, None),
+    (KEY_ROTATE_DISPLAY, None),
+    (KEY_CYCLEWINDOWS, None),
+    (KEY_MAIL, Some(Keycode::LaunchMail)),
+    (KEY_BOOKMARKS, Some(Keycode::BrowserFavorites)),
+    (KEY_COMPUTER, None),
+    (KEY_BACK, Some(Keycode::BrowserBack)),
+    (KEY_FORWARD, Some(Keycode::BrowserForward)),
+    (KEY_CLOSECD, None),
+    (KEY_EJECTCD, None),
+    (KEY_EJECTCLOSECD, None),
+    (KEY_NEXTSONG, Some(Keycode::MediaNextTrack)),
+    (KEY_PLAYPAUSE, Some(Keycode::MediaPlayPause)),
+    (KEY_PREVIOUSSONG, Some(Keycode::MediaPrevTrack)),
+    (KEY_STOPCD, Some(Keycode::MediaStop)),
+    (KEY_RECORD, None),
+    (KEY_REWIND, None),
+    (KEY_PHONE, None),
+    (KEY_ISO, None),
+    (KEY_CONFIG, None),
+    (KEY_HOMEPAGE, Some(Keycode::BrowserHome)),
+    (KEY_REFRESH, Some(Keycode::BrowserRefresh)),
+    (KEY_EXIT, None),
+    (KEY_MOVE, None),
+    (KEY_EDIT, None),
+    (KEY_SCROLLUP, None),
+    (KEY_SCROLLDOWN, None),
+    (KEY_KPLEFTPAREN, None),
+    (KEY_KPRIGHTPAREN, None),
+    (KEY_NEW, None),
+    (KEY_REDO, None),
+    (KEY_F13, Some(Keycode::F13)),
+    (KEY_F14, Some(Keycode::F14)),
+    (KEY_F15, Some(Keycode::F15)),
+    (KEY_F16, Some(Keycode::F16)),
+    (KEY_F17, Some(Keycode::F17)),
+    (KEY_F18, Some(Keycode::F18)),
+    (KEY_F19, Some(Keycode::F19)),
+    (KEY_F20, Some(Keycode::F20)),
+    (KEY_F21, Some(Keycode::F21)),
+    (KEY_F22, Some(Keycode::F22)),
+    (KEY_F23, Some(Keycode::F23)),
+    (KEY_F24, Some(Keycode::F24)),
+    (KEY_PLAYCD, None),
+    (KEY_PAUSECD, None),
+    (KEY_PROG3, None),
+    (KEY_PROG4, None),
+    (KEY_ALL_APPLICATIONS, None),
+    (KEY_SUSPEND, None),
+    (KEY_CLOSE, None),
+    (KEY_PLAY, None),
+    (KEY_FASTFORWARD, None),
+    (KEY_BASSBOOST, None),
+    (KEY_PRINT, None),
+    (KEY_HP, None),
+    (KEY_CAMERA, None),
+    (KEY_SOUND, None),
+    (KEY_QUESTION, None),
+    (KEY_EMAIL, None),
+    (KEY_CHAT, None),
+    (KEY_SEARCH, Some(Keycode::BrowserSearch)),
+    (KEY_CONNECT, None),
+    (KEY_FINANCE, None),
+    (KEY_SPORT, None),
+    (KEY_SHOP, None),
+    (KEY_ALTERASE, None),
+    (KEY_CANCEL, None),
+    (KEY_BRIGHTNESSDOWN, None),
+    (KEY_BRIGHTNESSUP, None),
+    (KEY_MEDIA, None),
+    (KEY_SWITCHVIDEOMODE, None),
+    (KEY_KBDILLUMTOGGLE, None),
+    (KEY_KBDILLUMDOWN, None),
+    (KEY_KBDILLUMUP, None),
+    (KEY_SEND, None),
+    (KEY_REPLY, None),
+    (KEY_FORWARDMAIL, None),
+    (KEY_SAVE, None),
+    (KEY_DOCUMENTS, None),
+    (KEY_BATTERY, None),
+    (KEY_BLUETOOTH, None),
+    (KEY_WLAN, None),
+    (KEY_UWB, None),
+    (KEY_UNKNOWN, None),
+    (KEY_VIDEO_NEXT, None),
+    (KEY_VIDEO_PREV, None),
+    (KEY_BRIGHTNESS_CYCLE, None),
+    (KEY_BRIGHTNESS_AUTO, None),
+    (KEY_DISPLAY_OFF, None),
+    (KEY_WWAN, None),
+    (KEY_RFKILL, None),
+    (KEY_MICMUTE, None),
+    (BTN_0, None),
+    (BTN_1, None),
+    (BTN_2, None),
+    (BTN_3, None),
+    (BTN_4, None),
+    (BTN_5, None),
+    (BTN_6, None),
+    (BTN_7, None),
+    (BTN_8, None),
+    (BTN_9, None),
+    (BTN_LEFT, None),
+    (BTN_RIGHT, None),
+    (BTN_MIDDLE, None),
+    (BTN_SIDE, None),
+    (BTN_EXTRA, None),
+    (BTN_FORWARD, None),
+    (BTN_BACK, None),
+    (BTN_TASK, None),
+    (BTN_TRIGGER, None),
+    (BTN_THUMB, None),
+    (BTN_THUMB2, None),
+    (BTN_TOP, None),
+    (BTN_TOP2, None),
+    (BTN_PINKIE, None),
+    (BTN_BASE, None),
+    (BTN_BASE2, None),
+    (BTN_BASE3, None),
+    (BTN_BASE4, None),
+    (BTN_BASE5, None),
+    (BTN_BASE6, None),
+    (BTN_DEAD, None),
+    (BTN_SOUTH, None),
+    (BTN_EAST, None),
+    (BTN_C, None),
+    (BTN_NORTH, None),
+    (BTN_WEST, None),
+    (BTN_Z, None),
+    (BTN_TL, None),
+    (BTN_TR, None),
+    (BTN_TL2, None),
+    (BTN_TR2, None),
+    (BTN_SELECT, None),
+    (BTN_START, None),
+    (BTN_MODE, None),
+    (BTN_THUMBL, None),
+    (BTN_THUMBR, None),
+    (BTN_TOOL_PEN, None),
+    (BTN_TOOL_RUBBER, None),
+    (BTN_TOOL_BRUSH, None),
+    (BTN_TOOL_PENCIL, None),
+    (BTN_TOOL_AIRBRUSH, None),
+    (BTN_TOOL_FINGER, None),
+    (BTN_TOOL_MOUSE, None),
+    (BTN_TOOL_LENS, None),
+    (BTN_TOOL_QUINTTAP, None),
+    (BTN_STYLUS3, None),
+    (BTN_TOUCH, None),
+    (BTN_STYLUS, None),
+    (BTN_STYLUS2, None),
+    (BTN_TOOL_DOUBLETAP, None),
+    (BTN_TOOL_TRIPLETAP, None),
+    (BTN_TOOL_QUADTAP, None),
+    (BTN_GEAR_DOWN, None),
+    (BTN_GEAR_UP, None),
+    (KEY_OK, None),
+    (KEY_SELECT, None),
+    (KEY_GOTO, None),
+    (KEY_CLEAR, None),
+    (KEY_POWER2, None),
+    (KEY_OPTION, None),
+    (KEY_INFO, None),
+    (KEY_TIME, None),
+    (KEY_VENDOR, None),
+    (KEY_ARCHIVE, None),
+    (KEY_PROGRAM, None),
+    (KEY_CHANNEL, None),
+    (KEY_FAVORITES, None),
+    (KEY_EPG, None),
+    (KEY_PVR, None),
+    (KEY_MHP, None),
+    (KEY_LANGUAGE, None),
+    (KEY_TITLE, None),
+    (KEY_SUBTITLE, None),
+    (KEY_ANGLE, None),
+    (KEY_FULL_SCREEN, None),
+    (KEY_MODE, None),
+    (KEY_KEYBOARD, None),
+    (KEY_ASPECT_RATIO, None),
+    (KEY_PC, None),
+    (KEY_TV, None),
+    (KEY_TV2, None),
+    (KEY_VCR, None),
+    (KEY_VCR2, None),
+    (KEY_SAT, None),
+    (KEY_SAT2, None),
+    (KEY_CD, None),
+    (KEY_TAPE, None),
+    (KEY_RADIO, None),
+    (KEY_TUNER, None),
+    (KEY_PLAYER, None),
+    (KEY_TEXT, None),
+    (KEY_DVD, None),
+    (KEY_AUX, None),
+    (KEY_MP3, None),
+    (KEY_AUDIO, None),
+    (KEY_VIDEO, None),
+    (KEY_DIRECTORY, None),
+    (KEY_LIST, None),
+    (KEY_MEMO, None),
+    (KEY_CALENDAR, None),
+    (KEY_RED, None),
+    (KEY_GREEN, None),
+    (KEY_YELLOW, None),
+    (KEY_BLUE, None),
+    (KEY_CHANNELUP, None),
+    (KEY_CHANNELDOWN, None),
+    (KEY_FIRST, None),
+    (KEY_LAST, None),
+    (KEY_AB, None),
+    (KEY_NEXT, None),
+    (KEY_RESTART, None),
+    (KEY_SLOW, None),
+    (KEY_SHUFFLE, None),
+    (KEY_BREAK, None),
+    (KEY_PREVIOUS, None),
+    (KEY_DIGITS, None),
+    (KEY_TEEN, None),
+    (KEY_TWEN, None),
+    (KEY_VIDEOPHONE, None),
+    (KEY_GAMES, None),
+    (KEY_ZOOMIN, None),
+    (KEY_ZOOMOUT, None),
+    (KEY_ZOOMRESET, None),
+    (KEY_WORDPROCESSOR, None),
+    (KEY_EDITOR, None),
+    (KEY_SPREADSHEET, None),
+    (KEY_GRAPHICSEDITOR, None),
+    (KEY_PRESENTATION, None),
+    (KEY_DATABASE, None),
+    (KEY_NEWS, None),
+    (KEY_VOICEMAIL, None),
+    (KEY_ADDRESSBOOK, None),
+    (KEY_MESSENGER, None),
+    (KEY_DISPLAYTOGGLE, None),
+    (KEY_SPELLCHECK, None),
+    (KEY_LOGOFF, None),
+    (KEY_DOLLAR, None),
+    (KEY_EURO, None),
+    (KEY_FRAMEBACK, None),
+    (KEY_FRAMEFORWARD, None),
+    (KEY_CONTEXT_MENU, None),
+    (KEY_MEDIA_REPEAT, None),
+    (KEY_10CHANNELSUP, None),
+    (KEY_10CHANNELSDOWN, None),
+    (KEY_IMAGES, None),
+    (KEY_NOTIFICATION_CENTER, None),
+    (KEY_PICKUP_PHONE, None),
+    (KEY_HANGUP_PHONE, None),
+    (KEY_LINK_PHONE, None),
+    (KEY_DEL_EOL, None),
+    (KEY_DEL_EOS, None),
+    (KEY_INS_LINE, None),
+    (KEY_DEL_LINE, None),
+    (KEY_FN, None),
+    (KEY_FN_ESC, None),
+    (KEY_FN_F1, None),
+    (KEY_FN_F2, None),
+    (KEY_FN_F3, None),
+    (KEY_FN_F4, None),
+    (KEY_FN_F5, None),
+    (KEY_FN_F6, None),
+    (KEY_FN_F7, None),
+    (KEY_FN_F8, None),
+    (KEY_FN_F9, None),
+    (KEY_FN_F10, None),
+    (KEY_FN_F11, None),
+    (KEY_FN_F12, None),
+    (KEY_FN_1, None),
+    (KEY_FN_2, None),
+    (KEY_FN_D, None),
+    (KEY_FN_E, None),
+    (KEY_FN_F, None),
+    (KEY_FN_S, None),
+    (KEY_FN_B, None),
+    (KEY_FN_RIGHT_SHIFT, None),
+    (KEY_BRL_DOT1, None),
+    (KEY_BRL_DOT2, None),
+    (KEY_BRL_DOT3, None),
+    (KEY_BRL_DOT4, None),
+    (KEY_BRL_DOT5, None),
+    (KEY_BRL_DOT6, None),
+    (KEY_BRL_DOT7, None),
+    (KEY_BRL_DOT8, None),
+    (KEY_BRL_DOT9, None),
+    (KEY_BRL_DOT10, None),
+    (KEY_NUMERIC_0, None),
+    (KEY_NUMERIC_1, None),
+    (KEY_NUMERIC_2, None),
+    (KEY_NUMERIC_3, None),
+    (KEY_NUMERIC_4, None),
+    (KEY_NUMERIC_5, None),
+    (KEY_NUMERIC_6, None),
+    (KEY_NUMERIC_7, None),
+    (KEY_NUMERIC_8, None),
+    (KEY_NUMERIC_9, None),
+    (KEY_NUMERIC_STAR, None),
+    (KEY_NUMERIC_POUND, None),
+    (KEY_NUMERIC_A, None),
+    (KEY_NUMERIC_B, None),
+    (KEY_NUMERIC_C, None),
+    (KEY_NUMERIC_D, None),
+    (KEY_CAMERA_FOCUS, None),
+    (KEY_WPS_BUTTON, None),
+    (KEY_TOUCHPAD_TOGGLE, None),
+    (KEY_TOUCHPAD_ON, None),
+    (KEY_TOUCHPAD_OFF, None),
+    (KEY_CAMERA_ZOOMIN, None),
+    (KEY_CAMERA_ZOOMOUT, None),
+    (KEY_CAMERA_UP, None),
+    (KEY_CAMERA_DOWN, None),
+    (KEY_CAMERA_LEFT, None),
+    (KEY_CAMERA_RIGHT, None),
+    (KEY_ATTENDANT_ON, None),
+    (KEY_ATTENDANT_OFF, None),
+    (KEY_ATTENDANT_TOGGLE, None),
+    (KEY_LIGHTS_TOGGLE, None),
+    (BTN_DPAD_UP, None),
+    (BTN_DPAD_DOWN, None),
+    (BTN_DPAD_LEFT, None),
+    (BTN_DPAD_RIGHT, None),
+    (KEY_ALS_TOGGLE, None),
+    (KEY_ROTATE_LOCK_TOGGLE, None),
+    (KEY_REFRESH_RATE_TOGGLE, None),
+    (KEY_BUTTONCONFIG, None),
+    (KEY_TASKMANAGER, None),
+    (KEY_JOURNAL, None),
+    (KEY_CONTROLPANEL, None),
+    (KEY_APPSELECT, None),
+    (KEY_SCREENSAVER, None),
+    (KEY_VOICECOMMAND, None),
+    (KEY_ASSISTANT, None),
+    (KEY_KBD_LAYOUT_NEXT, None),
+    (KEY_EMOJI_PICKER, None),
+    (KEY_DICTATE, None),
+    (KEY_BRIGHTNESS_MIN, None),
+    (KEY_BRIGHTNESS_MAX, None),
+    (KEY_KBDINPUTASSIST_PREV, None),
+    (KEY_KBDINPUTASSIST_NEXT, None),
+    (KEY_KBDINPUTASSIST_PREVGROUP, None),
+    (KEY_KBDINPUTASSIST_NEXTGROUP, None),
+    (KEY_KBDINPUTASSIST_ACCEPT, None),
+    (KEY_KBDINPUTASSIST_CANCEL, None),
+    (KEY_RIGHT_UP, None),
+    (KEY_RIGHT_DOWN, None),
+    (KEY_LEFT_UP, None),
+    (KEY_LEFT_DOWN, None),
+    (KEY_ROOT_MENU, None),
+    (KEY_MEDIA_TOP_MENU, None),
+    (KEY_NUMERIC_11, None),
+    (KEY_NUMERIC_12, None),
+    (KEY_AUDIO_DESC, None),
+    (KEY_3D_MODE, None),
+    (KEY_NEXT_FAVORITE, None),
+    (KEY_STOP_RECORD, None),
+    (KEY_PAUSE_RECORD, None),
+    (KEY_VOD, None),
+    (KEY_UNMUTE, None),
+    (KEY_FASTREVERSE, None),
+    (KEY_SLOWREVERSE, None),
+    (KEY_DATA, None),
+    (KEY_ONSCREEN_KEYBOARD, None),
+    (KEY_PRIVACY_SCREEN_TOGGLE, None),
+    (KEY_SELECTIVE_SCREENSHOT, None),
+    (KEY_NEXT_ELEMENT, None),
+    (KEY_PREVIOUS_ELEMENT, None),
+    (KEY_AUTOPILOT_ENGAGE_TOGGLE, None),
+    (KEY_MARK_WAYPOINT, None),
+    (KEY_SOS, None),
+    (KEY_NAV_CHART, None),
+    (KEY_FISHING_CHART, None),
+    (KEY_SINGLE_RANGE_RADAR, None),
+    (KEY_DUAL_RANGE_RADAR, None),
+    (KEY_RADAR_OVERLAY, None),
+    (KEY_TRADITIONAL_SONAR, None),
+    (KEY_CLEARVU_SONAR, None),
+    (KEY_SIDEVU_SONAR, None),
+    (KEY_NAV_INFO, None),
+    (KEY_BRIGHTNESS_MENU, None),
+    (KEY_MACRO1, None),
+    (KEY_MACRO2, None),
+    (KEY_MACRO3, None),
+    (KEY_MACRO4, None),
+    (KEY_MACRO5, None),
+    (KEY_MACRO6, None),
+    (KEY_MACRO7, None),
+    (KEY_MACRO8, None),
+    (KEY_MACRO9, None),
+    (KEY_MACRO10, None),
+    (KEY_MACRO11, None),
+    (KEY_MACRO12, None),
+    (KEY_MACRO13, None),
+    (KEY_MACRO14, None),
+    (KEY_MACRO15, None),
+    (KEY_MACRO16, None),
+    (KEY_MACRO17, None),
+    (KEY_MACRO18, None),
+    (KEY_MACRO19, None),
+    (KEY_MACRO20, None),
+    (KEY_MACRO21, None),
+    (KEY_MACRO22, None),
+    (KEY_MACRO23, None),
+    (KEY_MACRO24, None),
+    (KEY_MACRO25, None),
+    (KEY_MACRO26, None),
+    (KEY_MACRO27, None),
+    (KEY_MACRO28, None),
+    (KEY_MACRO29, None),
+    (KEY_MACRO30, None),
+    (KEY_MACRO_RECORD_START, None),
+    (KEY_MACRO_RECORD_STOP, None),
+    (KEY_MACRO_PRESET_CYCLE, None),
+    (KEY_MACRO_PRESET1, None),
+    (KEY_MACRO_PRESET2, None),
+    (KEY_MACRO_PRESET3, None),
+    (KEY_KBD_LCD_MENU1, None),
+    (KEY_KBD_LCD_MENU2, None),
+    (KEY_KBD_LCD_MENU3, None),
+    (KEY_KBD_LCD_MENU4, None),
+    (KEY_KBD_LCD_MENU5, None),
+    (BTN_TRIGGER_HAPPY1, None),
+    (BTN_TRIGGER_HAPPY2, None),
+    (BTN_TRIGGER_HAPPY3, None),
+    (BTN_TRIGGER_HAPPY4, None),
+    (BTN_TRIGGER_HAPPY5, None),
+    (BTN_TRIGGER_HAPPY6, None),
+    (BTN_TRIGGER_HAPPY7, None),
+    (BTN_TRIGGER_HAPPY8, None),
+    (BTN_TRIGGER_HAPPY9, None),
+    (BTN_TRIGGER_HAPPY10, None),
+    (BTN_TRIGGER_HAPPY11, None),
+    (BTN_TRIGGER_HAPPY12, None),
+    (BTN_TRIGGER_HAPPY13, None),
+    (BTN_TRIGGER_HAPPY14, None),
+    (BTN_TRIGGER_HAPPY15, None),
+    (BTN_TRIGGER_HAPPY16, None),
+    (BTN_TRIGGER_HAPPY17, None),
+    (BTN_TRIGGER_HAPPY18, None),
+    (BTN_TRIGGER_HAPPY19, None),
+    (BTN_TRIGGER_HAPPY20, None),
+    (BTN_TRIGGER_HAPPY21, None),
+    (BTN_TRIGGER_HAPPY22, None),
+    (BTN_TRIGGER_HAPPY23, None),
+    (BTN_TRIGGER_HAPPY24, None),
+    (BTN_TRIGGER_HAPPY25, None),
+    (BTN_TRIGGER_HAPPY26, None),
+    (BTN_TRIGGER_HAPPY27, None),
+    (BTN_TRIGGER_HAPPY28, None),
+    (BTN_TRIGGER_HAPPY29, None),
+    (BTN_TRIGGER_HAPPY30, None),
+    (BTN_TRIGGER_HAPPY31, None),
+    (BTN_TRIGGER_HAPPY32, None),
+    (BTN_TRIGGER_HAPPY33, None),
+    (BTN_TRIGGER_HAPPY34, None),
+    (BTN_TRIGGER_HAPPY35, None),
+    (BTN_TRIGGER_HAPPY36, None),
+    (BTN_TRIGGER_HAPPY37, None),
+    (BTN_TRIGGER_HAPPY38, None),
+    (BTN_TRIGGER_HAPPY39, None),
+    (BTN_TRIGGER_HAPPY40, None),
+];
+
+/// `KERNEL_KEYS` indexed by kernel key code.
+const BY_KERNEL_KEY: [Option<Keycode>; KEY_MAX as usize + 1] = {
+    let mut by_kernel_key = [None; KEY_MAX as usize + 1];
+    let mut index = 0;
+    while index < KERNEL_KEYS.len() {
+        let (code, keycode) = KERNEL_KEYS[index];
+        assert!(code <= KEY_MAX, "kernel key code out of range");
+        assert!(
+            index == 0 || KERNEL_KEYS[index - 1].0 < code,
+            "kernel key codes out of order"
+        );
+        by_kernel_key[code as usize] = keycode;
+        index += 1;
+    }
+    by_kernel_key
+};
+
+/// The kernel key code of each `Keycode`, indexed by `Keycode::index`, or `KEY_RESERVED` for the
+/// keys that don't exist on Linux.
+const BY_KEYCODE: [u16; Keycode::ALL.len()] = {
+    let mut by_keycode = [KEY_RESERVED; Keycode::ALL.len()];
+    let mut index = 0;
+    while index < KERNEL_KEYS.len() {
+        if let (code, Some(keycode)) = KERNEL_KEYS[index] {
+            let Some(keycode) = keycode.index() else {
+                panic!("kernel key code mapped to an unknown keycode");
+            };
+            assert!(
+                by_keycode[keycode] == KEY_RESERVED,
+                "keycode mapped from two kernel key codes"
+            );
+            by_keycode[keycode] = code;
+        }
+        index += 1;
+    }
+    by_keycode
+};
+
+/// Whether `kernel_code` is a mouse, joystick or other button rather than a key.
+fn is_button(kernel_code: u16) -> bool {
+    (BTN_0..=BTN_GEAR_UP).contains(&kernel_code)
+        || (BTN_DPAD_UP..=BTN_DPAD_RIGHT).contains(&kernel_code)
+        || (BTN_TRIGGER_HAPPY1..=BTN_TRIGGER_HAPPY40).contains(&kernel_code)
+}
+
+/// Converts a kernel keycode into a `Keycode`, or `Keycode::Unknown` if this library doesn't
+/// support it. Returns `None` for buttons and codes that aren't keys.
+pub fn kernel_key_to_keycode(kernel_code: u16) -> Option<Keycode> {
+    match BY_KERNEL_KEY.get(kernel_code as usize)? {
+        Some(keycode) => Some(*keycode),
+        None if kernel_code == KEY_RESERVED || is_button(kernel_code) => None,
+        None => Some(Keycode::Unknown {
+            platform_code: kernel_code as u32,
+        }),
+    }
+}
+
+/// Converts a `Keycode` into a kernel keycode, if the key exists on Linux.
+pub fn keycode_to_kernel_key(keycode: Keycode) -> Option<u16> {
+    let code = match keycode {
+        Keycode::Unknown { platform_code } => u16::try_from(platform_code).ok()?,
+        keycode => BY_KEYCODE[keycode.index()?],
+    };
+    (code != KEY_RESERVED && code <= KEY_MAX).then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Where distributions install the kernel headers.
+    const INPUT_EVENT_CODES: &str = "/usr/include/linux/input-event-codes.h";
+
+    #[test]
+    fn listed_keys_convert_both_ways() {
+        for (code, keycode) in KERNEL_KEYS.iter().copied() {
+            match keycode {
+                Some(keycode) => {
+                    assert_eq!(kernel_key_to_keycode(code), Some(keycode));
+                    assert_eq!(keycode_to_kernel_key(keycode), Some(code));
+                }
+                None if code == KEY_RESERVED || is_button(code) => {
+                    assert_eq!(kernel_key_to_keycode(code), None, "{:#x}", code);
+                }
+                None => {
+                    let unknown = Keycode::Unknown {
+                        platform_code: code as u32,
+                    };
+                    assert_eq!(kernel_key_to_keycode(code), Some(unknown));
+                    assert_eq!(keycode_to_kernel_key(unknown), Some(code));
+                }
+            }
+        }
+    }
+
+    /// Every code `input-event-codes.h` defines up to `KEY_MAX` is either mapped or listed as
+    /// deliberately unmapped in `KERNEL_KEYS`, so that new kernel keys aren't missed.
+    #[test]
+    fn every_kernel_key_is_listed() {
+        let Ok(header) = fs::read_to_string(INPUT_EVENT_CODES) else {
+            eprintln!("skipped: {} not found", INPUT_EVENT_CODES);
+            return;
+        };
+        let defined = header.lines().filter_map(|line| {
+            let mut words = line.split_whitespace();
+            if words.next() != Some("#define") {
+                return None;
+            }
+            let name = words.next()?;
+            let value = words.next()?;
+            if !(name.starts_with("KEY_") || name.starts_with("BTN_")) || name == "KEY_MAX" {
+                return None;
+            }
+            // Aliases like `KEY_MIN_INTERESTING` are defined by name.
+            let code = match value.strip_prefix("0x") {
+                Some(hex) => u16::from_str_radix(hex, 16).ok()?,
+                None => value.parse().ok()?,
+            };
+            Some((name, code))
+        });
+        let mut count = 0;
+        for (name, code) in defined.filter(|(_, code)| *code <= KEY_MAX) {
+            assert!(
+                KERNEL_KEYS.iter().any(|(listed, _)| *listed == code),
+                "{} ({:#x}) is missing from KERNEL_KEYS",
+                name,
+                code
+            );
+            count += 1;
+        }
+        assert!(count > 0, "no key codes read from {}", INPUT_EVENT_CODES);
+    }
+}
@@ -1,239 +1,276 @@
+extern crate libc;
 extern crate x11;
+extern crate x11_dl;
 
 use self::x11::xlib;
+use device_state::{DeviceBackend, DeviceState};
+use error::DeviceQueryError;
+use key_set::KeySet;
 use keymap::Keycode;
-use mouse_state::MouseState;
-use std::os::raw::c_char;
+use mouse_state::{MouseButton, MousePosition, MouseState};
+use screen::{Monitor, PointerLocation};
+use std::os::raw::{c_char, c_uint};
+use std::path::Path;
 use std::ptr;
-use std::rc::Rc;
-use std::slice;
+use std::sync::{Mutex, Once, PoisonError};
 
+mod evdev;
 mod kernel_key;
+mod keysym;
+mod monitors;
+mod translator;
+mod xinput2;
+mod xtest;
 
-#[derive(Debug, Clone)]
-/// Device state descriptor.
-pub struct DeviceState {
-    xc: Rc<X11Connection>,
+pub use self::evdev::EvdevBackend;
+pub use self::translator::KeyTranslator;
+pub use self::xinput2::XInput2Backend;
+pub use self::xtest::InputSimulator;
+
+use self::monitors::MonitorLibraries;
+
+/// The source of input used by a Linux `DeviceState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxBackend {
+    /// Query an X server (or XWayland) through Xlib.
+    X11,
+    /// Query an X server like `X11`, and receive XInput2 raw events instead of polling when
+    /// used by the event loop. Requires libXi at runtime.
+    XInput2,
+    /// Read `/dev/input/event*` directly. Works without a display server, but requires read
+    /// access to the devices, usually through membership of the `input` group.
+    Evdev,
 }
 
+/// Backend querying an X server through Xlib.
+///
+/// Coordinates are relative to the root window of the X screen the pointer is on. Monitors are
+/// listed with RandR 1.5, or Xinerama on older servers, and scaled by the `Xft.dpi` resource.
+///
+/// Every Xlib call on the display of the backend is made with a lock held, so the backend can be
+/// shared between threads.
 #[derive(Debug)]
-struct X11Connection {
-    display: *mut xlib::Display,
+pub struct X11Backend {
+    display: Mutex<Display>,
+    // Unloaded after `drop` closed the display.
+    monitor_libraries: MonitorLibraries,
 }
 
-impl Drop for X11Connection {
-    fn drop(&mut self) {
-        unsafe {
-            xlib::XCloseDisplay(self.display);
-        }
-    }
+/// An open Xlib display.
+#[derive(Debug)]
+struct Display(*mut xlib::Display);
+
+// Only used with the lock of its `X11Backend` held.
+unsafe impl Send for Display {}
+
+/// The pointer as seen from the root window of its screen.
+pub(super) struct RootPointer {
+    root: xlib::Window,
+    // The top-level window under the pointer, or 0.
+    child: xlib::Window,
+    pub(super) coords: MousePosition,
+    mask: c_uint,
 }
 
-impl DeviceState {
-    /// Creates a new DeviceState.
-    pub fn new() -> DeviceState {
+/// The Linux backends must stay shareable between threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<X11Backend>();
+    assert_send_sync::<XInput2Backend>();
+    assert_send_sync::<EvdevBackend>();
+    assert_send_sync::<InputSimulator>();
+    assert_send_sync::<KeyTranslator>();
+};
+
+static INIT_THREADS: Once = Once::new();
+
+impl X11Backend {
+    /// Opens the default X display.
+    pub fn open() -> Result<X11Backend, DeviceQueryError> {
+        // Each backend locks its own display. This protects the state Xlib shares between
+        // displays, when backends are opened and used from different threads.
+        INIT_THREADS.call_once(|| unsafe {
+            xlib::XInitThreads();
+        });
         unsafe {
             let display = xlib::XOpenDisplay(ptr::null());
-            if display.as_ref().is_none() {
-                panic!("Could not connect to a X display");
-            }
-            DeviceState {
-                xc: Rc::new(X11Connection { display }),
+            if display.is_null() {
+                return Err(DeviceQueryError::DisplayUnavailable);
             }
+            Ok(X11Backend {
+                display: Mutex::new(Display(display)),
+                monitor_libraries: MonitorLibraries::open(display),
+            })
         }
     }
 
-    /// Create a new DeviceState. In case of failure, doesn't panic.
-    pub fn checked_new() -> Option<DeviceState> {
-        unsafe {
-            let display = xlib::XOpenDisplay(ptr::null());
-            if display.as_ref().is_none() {
-                eprintln!("Could not connect to a X display");
-                return None;
+    /// Calls `f` with the display locked. Every Xlib call on the display must be made by `f`.
+    pub(super) fn with_display<T>(&self, f: impl FnOnce(*mut xlib::Display) -> T) -> T {
+        let display = self.display.lock().unwrap_or_else(PoisonError::into_inner);
+        f(display.0)
+    }
+
+    /// Queries the pointer on the root window of the screen it is on.
+    fn root_pointer(&self) -> RootPointer {
+        self.with_display(|display| unsafe { root_pointer(display) })
+    }
+
+    /// Passes each key currently pressed to `f`, in X11 keycode order.
+    fn pressed_keys(&self, mut f: impl FnMut(Keycode)) {
+        let mut keymap: [c_char; 32] = [0; 32];
+        self.with_display(|display| unsafe {
+            xlib::XQueryKeymap(display, keymap.as_mut_ptr());
+        });
+        for (ix, byte) in keymap.iter().enumerate() {
+            for bit in 0_u8..8_u8 {
+                let bitmask = 1 << bit;
+                if byte & bitmask != 0 {
+                    //x11 keycode uses kernel keycode with an offset of 8.
+                    let x11_key = ix as u8 * 8 + bit;
+                    let kernel_key = x11_key - 8;
+                    if let Some(k) = kernel_key::kernel_key_to_keycode(kernel_key as u16) {
+                        f(k)
+                    }
+                }
             }
-            Some(DeviceState {
-                xc: Rc::new(X11Connection { display }),
-            })
         }
     }
+}
 
-    /// Query the `MouseState`.
-    pub(crate) fn query_pointer(&self) -> MouseState {
-        let root;
-        let mut root_x = 0;
-        let mut root_y = 0;
-        let mut win_x = 0;
-        let mut win_y = 0;
-        let mut root_return = 0;
-        let mut child_return = 0;
-        let mut mask_return = 0;
-        unsafe {
-            root = xlib::XDefaultRootWindow(self.xc.display);
-            xlib::XQueryPointer(
-                self.xc.display,
-                root,
+/// Queries the pointer of `display` on the root window of the screen it is on.
+pub(super) unsafe fn root_pointer(display: *mut xlib::Display) -> RootPointer {
+    let mut root_return = 0;
+    let mut child = 0;
+    let mut root_x = 0;
+    let mut root_y = 0;
+    let mut win_x = 0;
+    let mut win_y = 0;
+    let mut mask = 0;
+    unsafe {
+        let mut window = xlib::XDefaultRootWindow(display);
+        // On another screen than `window`, only the root window and the root coordinates are
+        // reported: queries again on that root for the window under the pointer.
+        for _ in 0..2 {
+            if xlib::XQueryPointer(
+                display,
+                window,
                 &mut root_return,
-                &mut child_return,
+                &mut child,
                 &mut root_x,
                 &mut root_y,
                 &mut win_x,
                 &mut win_y,
-                &mut mask_return,
-            );
+                &mut mask,
+            ) != xlib::False
+            {
+                break;
+            }
+            window = root_return;
         }
-        let button1pressed = mask_return & xlib::Button1Mask > 0;
-        let button2pressed = mask_return & xlib::Button2Mask > 0;
-        let button3pressed = mask_return & xlib::Button3Mask > 0;
-        let button4pressed = mask_return & xlib::Button4Mask > 0;
-        let button5pressed = mask_return & xlib::Button5Mask > 0;
+    }
+    RootPointer {
+        root: root_return,
+        child,
+        coords: (root_x, root_y),
+        mask,
+    }
+}
 
+impl Drop for X11Backend {
+    fn drop(&mut self) {
+        let display = self
+            .display
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        unsafe {
+            xlib::XCloseDisplay(display.0);
+        }
+    }
+}
+
+impl DeviceState {
+    /// Creates a new DeviceState.
+    ///
+    /// # Panics
+    ///
+    /// If no X display could be opened. Use [`try_new`](Self::try_new) to handle the error.
+    pub fn new() -> DeviceState {
+        Self::try_new().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a new DeviceState. In case of failure, doesn't panic.
+    pub fn checked_new() -> Option<DeviceState> {
+        Self::try_new().ok()
+    }
+
+    /// Create a new DeviceState querying the X server.
+    pub fn try_new() -> Result<DeviceState, DeviceQueryError> {
+        Self::with_backend(LinuxBackend::X11)
+    }
+
+    /// Create a new DeviceState using the given backend. Fails if the backend is not available:
+    /// no X display could be opened, or no input device could be read.
+    pub fn with_backend(backend: LinuxBackend) -> Result<DeviceState, DeviceQueryError> {
+        match backend {
+            LinuxBackend::X11 => X11Backend::open().map(DeviceState::from_backend),
+            LinuxBackend::XInput2 => XInput2Backend::open().map(DeviceState::from_backend),
+            LinuxBackend::Evdev => EvdevBackend::open_all().map(DeviceState::from_backend),
+        }
+    }
+
+    /// Create a new evdev DeviceState reading only the given `/dev/input/event*` devices, e.g. a
+    /// virtual device created through uinput.
+    pub fn from_evdev_paths<P: AsRef<Path>>(paths: &[P]) -> Result<DeviceState, DeviceQueryError> {
+        EvdevBackend::open_paths(paths).map(DeviceState::from_backend)
+    }
+}
+
+impl DeviceBackend for X11Backend {
+    fn query_pointer(&self) -> MouseState {
+        let pointer = self.root_pointer();
+        // The core protocol has no state for the buttons above 5, `XInput2Backend` queries them.
+        // Its Button4 and Button5 masks are the scroll wheel.
         let button_pressed = [
-            button1pressed,
-            button2pressed,
-            button3pressed,
-            button4pressed,
-            button5pressed,
-        ];
+            (xlib::Button1Mask, MouseButton::Left),
+            (xlib::Button2Mask, MouseButton::Middle),
+            (xlib::Button3Mask, MouseButton::Right),
+        ]
+        .iter()
+        .filter(|(mask, _)| pointer.mask & mask != 0)
+        .map(|(_, button)| *button)
+        .collect();
         MouseState {
-            coords: (win_x, win_y),
+            coords: pointer.coords,
             button_pressed,
         }
     }
 
-    /// Query the Keyboard state.
-    pub(crate) fn query_keymap(&self) -> Vec<Code> {
-        let mut keycodes = vec![];
-        unsafe {
-            let keymap: *mut c_char = [0; 32].as_mut_ptr();
-            xlib::XQueryKeymap(self.xc.display, keymap);
-            for (ix, byte) in slice::from_raw_parts(keymap, 32).iter().enumerate() {
-                for bit in 0_u8..8_u8 {
-                    let bitmask = 1 << bit;
-                    if byte & bitmask != 0 {
-                        //x11 keycode uses kernel keycode with an offset of 8.
-                        let x11_key = ix as u8 * 8 + bit;
-                        let kernel_key = x11_key - 8;
-                        if let Some(k) = self.kernel_key_to_keycode(kernel_key) {
-                            keycodes.push(k)
-                        }
-                    }
-                }
-            }
+    fn query_monitors(&self) -> Vec<Monitor> {
+        self.monitors()
+    }
+
+    fn query_pointer_location(&self) -> PointerLocation {
+        let pointer = self.root_pointer();
+        let screen = self.screen_of_root(pointer.root);
+        PointerLocation {
+            // `Window` is a `c_ulong`, 32 bit on some targets.
+            #[allow(clippy::unnecessary_cast)]
+            window: Some(pointer.child as u64).filter(|window| *window != 0),
+            ..PointerLocation::locate(pointer.coords, screen, &self.monitors())
         }
+    }
+
+    fn query_keymap(&self) -> Vec<Keycode> {
+        let mut keycodes = vec![];
+        self.pressed_keys(|k| keycodes.push(k));
         keycodes
     }
 
-    fn kernel_key_to_keycode(&self, kernel_code: u8) -> Option<Keycode> {
-        match kernel_code as u16 {
-            kernel_key::KEY_0 => Some(Keycode::Key0),
-            kernel_key::KEY_1 => Some(Keycode::Key1),
-            kernel_key::KEY_2 => Some(Keycode::Key2),
-            kernel_key::KEY_3 => Some(Keycode::Key3),
-            kernel_key::KEY_4 => Some(Keycode::Key4),
-            kernel_key::KEY_5 => Some(Keycode::Key5),
-            kernel_key::KEY_6 => Some(Keycode::Key6),
-            kernel_key::KEY_7 => Some(Keycode::Key7),
-            kernel_key::KEY_8 => Some(Keycode::Key8),
-            kernel_key::KEY_9 => Some(Keycode::Key9),
-            kernel_key::KEY_A => Some(Keycode::A),
-            kernel_key::KEY_B => Some(Keycode::B),
-            kernel_key::KEY_C => Some(Keycode::C),
-            kernel_key::KEY_D => Some(Keycode::D),
-            kernel_key::KEY_E => Some(Keycode::E),
-            kernel_key::KEY_F => Some(Keycode::F),
-            kernel_key::KEY_G => Some(Keycode::G),
-            kernel_key::KEY_H => Some(Keycode::H),
-            kernel_key::KEY_I => Some(Keycode::I),
-            kernel_key::KEY_J => Some(Keycode::J),
-            kernel_key::KEY_K => Some(Keycode::K),
-            kernel_key::KEY_L => Some(Keycode::L),
-            kernel_key::KEY_M => Some(Keycode::M),
-            kernel_key::KEY_N => Some(Keycode::N),
-            kernel_key::KEY_O => Some(Keycode::O),
-            kernel_key::KEY_P => Some(Keycode::P),
-            kernel_key::KEY_Q => Some(Keycode::Q),
-            kernel_key::KEY_R => Some(Keycode::R),
-            kernel_key::KEY_S => Some(Keycode::S),
-            kernel_key::KEY_T => Some(Keycode::T),
-            kernel_key::KEY_U => Some(Keycode::U),
-            kernel_key::KEY_V => Some(Keycode::V),
-            kernel_key::KEY_W => Some(Keycode::W),
-            kernel_key::KEY_X => Some(Keycode::X),
-            kernel_key::KEY_Y => Some(Keycode::Y),
-            kernel_key::KEY_Z => Some(Keycode::Z),
-            kernel_key::KEY_F1 => Some(Keycode::F1),
-            kernel_key::KEY_F2 => Some(Keycode::F2),
-            kernel_key::KEY_F3 => Some(Keycode::F3),
-            kernel_key::KEY_F4 => Some(Keycode::F4),
-            kernel_key::KEY_F5 => Some(Keycode::F5),
-            kernel_key::KEY_F6 => Some(Keycode::F6),
-            kernel_key::KEY_F7 => Some(Keycode::F7),
-            kernel_key::KEY_F8 => Some(Keycode::F8),
-            kernel_key::KEY_F9 => Some(Keycode::F9),
-            kernel_key::KEY_F10 => Some(Keycode::F10),
-            kernel_key::KEY_F11 => Some(Keycode::F11),
-            kernel_key::KEY_F12 => Some(Keycode::F12),
-            kernel_key::KEY_F13 => Some(Keycode::F13),
-            kernel_key::KEY_F14 => Some(Keycode::F14),
-            kernel_key::KEY_F15 => Some(Keycode::F15),
-            kernel_key::KEY_F16 => Some(Keycode::F16),
-            kernel_key::KEY_F17 => Some(Keycode::F17),
-            kernel_key::KEY_F18 => Some(Keycode::F18),
-            kernel_key::KEY_F19 => Some(Keycode::F19),
-            kernel_key::KEY_F20 => Some(Keycode::F20),
-            kernel_key::KEY_KP0 => Some(Keycode::Numpad0),
-            kernel_key::KEY_KP1 => Some(Keycode::Numpad1),
-            kernel_key::KEY_KP2 => Some(Keycode::Numpad2),
-            kernel_key::KEY_KP3 => Some(Keycode::Numpad3),
-            kernel_key::KEY_KP4 => Some(Keycode::Numpad4),
-            kernel_key::KEY_KP5 => Some(Keycode::Numpad5),
-            kernel_key::KEY_KP6 => Some(Keycode::Numpad6),
-            kernel_key::KEY_KP7 => Some(Keycode::Numpad7),
-            kernel_key::KEY_KP8 => Some(Keycode::Numpad8),
-            kernel_key::KEY_KP9 => Some(Keycode::Numpad9),
-            kernel_key::KEY_KPENTER => Some(Keycode::NumpadEnter),
-            kernel_key::KEY_KPMINUS => Some(Keycode::NumpadSubtract),
-            kernel_key::KEY_KPPLUS => Some(Keycode::NumpadAdd),
-            kernel_key::KEY_KPSLASH => Some(Keycode::NumpadDivide),
-            kernel_key::KEY_KPASTERISK => Some(Keycode::NumpadMultiply),
-            kernel_key::KEY_KPEQUAL => Some(Keycode::NumpadEquals),
-            kernel_key::KEY_KPDOT => Some(Keycode::NumpadDecimal),
-            kernel_key::KEY_ESC => Some(Keycode::Escape),
-            kernel_key::KEY_SPACE => Some(Keycode::Space),
-            kernel_key::KEY_LEFTCTRL => Some(Keycode::LControl),
-            kernel_key::KEY_RIGHTCTRL => Some(Keycode::RControl),
-            kernel_key::KEY_LEFTSHIFT => Some(Keycode::LShift),
-            kernel_key::KEY_RIGHTSHIFT => Some(Keycode::RShift),
-            kernel_key::KEY_LEFTALT => Some(Keycode::LAlt),
-            kernel_key::KEY_RIGHTALT => Some(Keycode::RAlt),
-            kernel_key::KEY_LEFTMETA => Some(Keycode::LMeta),
-            kernel_key::KEY_RIGHTMETA => Some(Keycode::RMeta),
-            kernel_key::KEY_ENTER => Some(Keycode::Enter),
-            kernel_key::KEY_UP => Some(Keycode::Up),
-            kernel_key::KEY_DOWN => Some(Keycode::Down),
-            kernel_key::KEY_LEFT => Some(Keycode::Left),
-            kernel_key::KEY_RIGHT => Some(Keycode::Right),
-            kernel_key::KEY_BACKSPACE => Some(Keycode::Backspace),
-            kernel_key::KEY_CAPSLOCK => Some(Keycode::CapsLock),
-            kernel_key::KEY_TAB => Some(Keycode::Tab),
-            kernel_key::KEY_HOME => Some(Keycode::Home),
-            kernel_key::KEY_END => Some(Keycode::End),
-            kernel_key::KEY_PAGEUP => Some(Keycode::PageUp),
-            kernel_key::KEY_PAGEDOWN => Some(Keycode::PageDown),
-            kernel_key::KEY_INSERT => Some(Keycode::Insert),
-            kernel_key::KEY_DELETE => Some(Keycode::Delete),
-            kernel_key::KEY_GRAVE => Some(Keycode::Grave),
-            kernel_key::KEY_MINUS => Some(Keycode::Minus),
-            kernel_key::KEY_EQUAL => Some(Keycode::Equal),
-            kernel_key::KEY_LEFTBRACE => Some(Keycode::LeftBracket),
-            kernel_key::KEY_RIGHTBRACE => Some(Keycode::RightBracket),
-            kernel_key::KEY_BACKSLASH => Some(Keycode::BackSlash),
-            kernel_key::KEY_SEMICOLON => Some(Keycode::Semicolon),
-            kernel_key::KEY_APOSTROPHE => Some(Keycode::Apostrophe),
-            kernel_key::KEY_COMMA => Some(Keycode::Comma),
-            kernel_key::KEY_DOT => Some(Keycode::Dot),
-            kernel_key::KEY_SLASH => Some(Keycode::Slash),
-            _ => None,
-        }
+    fn query_key_set(&self) -> KeySet {
+        let mut keys = KeySet::new();
+        self.pressed_keys(|k| {
+            keys.insert(k);
+        });
+        keys
     }
 }
@@ -0,0 +1,209 @@
+//! Monitors of the X screens, from RandR 1.5 or Xinerama when the server supports them.
+
+use super::x11::xlib;
+use super::x11_dl::xinerama;
+use super::x11_dl::xrandr::Xrandr;
+use super::X11Backend;
+use screen::Monitor;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_int;
+use std::slice;
+
+/// The DPI of a display at 100% scale.
+const BASE_DPI: f64 = 96.0;
+
+/// libXrandr and libXinerama, if they are installed.
+///
+/// Once used on a display, they must stay loaded until it is closed: they register callbacks
+/// called by `XCloseDisplay`.
+pub(super) struct MonitorLibraries {
+    xrandr: Option<Xrandr>,
+    // Whether the server supports RandR 1.5, which lists monitors rather than outputs.
+    randr_monitors: bool,
+    xinerama: Option<xinerama::Xlib>,
+}
+
+impl MonitorLibraries {
+    /// Loads the libraries and checks the RandR version of `display`.
+    pub(super) fn open(display: *mut xlib::Display) -> MonitorLibraries {
+        let xrandr = Xrandr::open().ok();
+        let randr_monitors = xrandr.as_ref().is_some_and(|xrandr| {
+            let (mut major, mut minor) = (0, 0);
+            // Returns 0 without sending a request if the server lacks the extension.
+            unsafe {
+                (xrandr.XRRQueryVersion)(display as *mut _, &mut major, &mut minor) != 0
+                    && (major, minor) >= (1, 5)
+            }
+        });
+        MonitorLibraries {
+            xrandr,
+            randr_monitors,
+            xinerama: xinerama::Xlib::open().ok(),
+        }
+    }
+}
+
+impl fmt::Debug for MonitorLibraries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MonitorLibraries")
+            .field("randr_monitors", &self.randr_monitors)
+            .field("xinerama", &self.xinerama.is_some())
+            .finish()
+    }
+}
+
+impl X11Backend {
+    /// The monitors of every screen, in screen order. A screen whose monitors can't be listed
+    /// is one monitor.
+    pub(super) fn monitors(&self) -> Vec<Monitor> {
+        self.with_display(|display| {
+            let scale = scale(display);
+            let screens = unsafe { xlib::XScreenCount(display) };
+            let mut monitors = vec![];
+            for screen in 0..screens.max(0) as usize {
+                let screen_monitors = self
+                    .randr_monitors(display, screen, scale)
+                    .or_else(|| self.xinerama_monitors(display, screen, scale))
+                    .unwrap_or_else(|| vec![screen_monitor(display, screen, scale)]);
+                monitors.extend(screen_monitors);
+            }
+            monitors
+        })
+    }
+
+    /// The index of the screen whose root window is `root`.
+    pub(super) fn screen_of_root(&self, root: xlib::Window) -> usize {
+        self.with_display(|display| unsafe {
+            let screens = xlib::XScreenCount(display);
+            (0..screens)
+                .find(|screen| xlib::XRootWindow(display, *screen) == root)
+                .unwrap_or(0) as usize
+        })
+    }
+
+    fn randr_monitors(
+        &self,
+        display: *mut xlib::Display,
+        screen: usize,
+        scale: f64,
+    ) -> Option<Vec<Monitor>> {
+        let xrandr = self.monitor_libraries.xrandr.as_ref()?;
+        if !self.monitor_libraries.randr_monitors {
+            return None;
+        }
+        let mut monitors = vec![];
+        unsafe {
+            let root = xlib::XRootWindow(display, screen as c_int);
+            let mut count = 0;
+            let infos = (xrandr.XRRGetMonitors)(display as *mut _, root, xlib::True, &mut count);
+            if infos.is_null() {
+                return None;
+            }
+            for info in slice::from_raw_parts(infos, count.max(0) as usize) {
+                monitors.push(Monitor {
+                    name: atom_name(display, info.name),
+                    screen,
+                    position: (info.x, info.y),
+                    size: (info.width.max(0) as u32, info.height.max(0) as u32),
+                    scale,
+                    primary: info.primary != xlib::False,
+                });
+            }
+            (xrandr.XRRFreeMonitors)(infos);
+        }
+        Some(monitors).filter(|monitors| !monitors.is_empty())
+    }
+
+    /// The Xinerama screens, which are the monitors of the single X screen 0 when Xinerama is
+    /// active. The first one is the primary monitor.
+    fn xinerama_monitors(
+        &self,
+        display: *mut xlib::Display,
+        screen: usize,
+        scale: f64,
+    ) -> Option<Vec<Monitor>> {
+        let xinerama = self.monitor_libraries.xinerama.as_ref()?;
+        if screen != 0 {
+            return None;
+        }
+        let mut monitors = vec![];
+        unsafe {
+            let display = display as *mut _;
+            if (xinerama.XineramaIsActive)(display) == xlib::False {
+                return None;
+            }
+            let mut count = 0;
+            let infos = (xinerama.XineramaQueryScreens)(display, &mut count);
+            if infos.is_null() {
+                return None;
+            }
+            for (index, info) in slice::from_raw_parts(infos, count.max(0) as usize)
+                .iter()
+                .enumerate()
+            {
+                monitors.push(Monitor {
+                    name: None,
+                    screen,
+                    position: (i32::from(info.x_org), i32::from(info.y_org)),
+                    size: (info.width.max(0) as u32, info.height.max(0) as u32),
+                    scale,
+                    primary: index == 0,
+                });
+            }
+            xlib::XFree(infos as *mut _);
+        }
+        Some(monitors).filter(|monitors| !monitors.is_empty())
+    }
+}
+
+/// The desktop scale set by the `Xft.dpi` resource, which desktop environments set to
+/// scale applications, as read when the display was opened.
+fn scale(display: *mut xlib::Display) -> f64 {
+    let resources = unsafe { xlib::XResourceManagerString(display) };
+    if resources.is_null() {
+        return 1.0;
+    }
+    let resources = unsafe { CStr::from_ptr(resources) }.to_string_lossy();
+    resources
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim() == "Xft.dpi")
+        .and_then(|(_, dpi)| dpi.trim().parse::<f64>().ok())
+        .filter(|dpi| *dpi > 0.0)
+        .map_or(1.0, |dpi| dpi / BASE_DPI)
+}
+
+/// The whole of `screen` as a single monitor, primary if it is the default screen.
+fn screen_monitor(display: *mut xlib::Display, screen: usize, scale: f64) -> Monitor {
+    unsafe {
+        let number = screen as c_int;
+        Monitor {
+            name: None,
+            screen,
+            position: (0, 0),
+            size: (
+                xlib::XDisplayWidth(display, number).max(0) as u32,
+                xlib::XDisplayHeight(display, number).max(0) as u32,
+            ),
+            scale,
+            primary: xlib::XDefaultScreen(display) == number,
+        }
+    }
+}
+
+fn atom_name(display: *mut xlib::Display, atom: xlib::Atom) -> Option<String> {
+    // Asking for the name of atom 0 is a protocol error.
+    if atom == 0 {
+        return None;
+    }
+    unsafe {
+        let name = xlib::XGetAtomName(display, atom);
+        if name.is_null() {
+            return None;
+        }
+        let string = CStr::from_ptr(name).to_string_lossy().into_owned();
+        xlib::XFree(name as *mut _);
+        Some(string)
+    }
+}
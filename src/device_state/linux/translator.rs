@@ -0,0 +1,446 @@
+//! Translation of key events into text with the XKB keyboard layout of the X server.
+
+use super::keysym::keysym_to_char;
+use super::x11::xlib;
+use super::{kernel_key, X11Backend};
+use error::DeviceQueryError;
+use key_set::KeySet;
+use keymap::Keycode;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::env;
+use std::ffi::CString;
+use std::fmt;
+use std::fs;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_uint};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::slice;
+
+/// XkbUseCoreKbd: the core keyboard device.
+const USE_CORE_KBD: c_uint = 0x0100;
+/// Modifiers that make a key a shortcut rather than text: Control, Mod1 (Alt) and Mod4 (Super).
+const SHORTCUT_MASK: c_uint = xlib::ControlMask | xlib::Mod1Mask | xlib::Mod4Mask;
+/// Default directory of the X locale files, holding the Compose files.
+const LOCALE_DIR: &str = "/usr/share/X11/locale";
+/// Locale whose Compose file is used when the current locale has none.
+const DEFAULT_LOCALE: &str = "en_US.UTF-8";
+
+/// Dead keysyms, from `XK_dead_grave` to `XK_dead_lowline` and the later additions.
+fn is_dead_keysym(keysym: u32) -> bool {
+    (0xfe50..=0xfe93).contains(&keysym)
+}
+
+/// Modifier keysyms, which neither type text nor cancel a dead key: shifts, controls, locks,
+/// `Alt`, `Meta`, `Super`, `Hyper`, the ISO level and group shifts, `Mode_switch` and `Num_Lock`.
+fn is_modifier_keysym(keysym: u32) -> bool {
+    (0xffe1..=0xffee).contains(&keysym)
+        || (0xfe01..=0xfe0f).contains(&keysym)
+        || keysym == 0xff7e
+        || keysym == 0xff7f
+}
+
+/// Keysyms of the keys that toggle a locked modifier, whose state is read from the server.
+fn is_lock_keysym(keysym: u32) -> bool {
+    matches!(keysym, 0xffe5 | 0xffe6 | 0xff7f)
+}
+
+/// Translates key events into the text they type with the current keyboard layout of the X
+/// server, like a focused X11 application would see it.
+///
+/// Keys are translated with the held modifiers, so that shift selects uppercase letters and
+/// AltGr the third level of the layout, and with the state of the locks and the active layout
+/// group read from the server. Keys pressed with Control, Alt or Super type no text.
+///
+/// Dead keys are combined with the next key according to the X Compose file of the current
+/// locale, or of `en_US.UTF-8` if it has none. A dead key followed by a key it doesn't combine
+/// with types both on their own.
+///
+/// The translator only sees the key events it is given: they should all be passed in order, for
+/// example from the key callbacks of a `DeviceEvents` implementation.
+pub struct KeyTranslator {
+    x11: X11Backend,
+    /// The modifier mask set by each X11 keycode while held.
+    modifiers: [c_uint; 256],
+    held: KeySet,
+    dead_keys: DeadKeys,
+}
+
+impl KeyTranslator {
+    /// Opens the default X display and reads its keyboard layout.
+    pub fn new() -> Result<KeyTranslator, DeviceQueryError> {
+        let x11 = X11Backend::open()?;
+        let modifiers = x11.with_display(|display| unsafe { modifier_masks(display) });
+        Ok(KeyTranslator {
+            x11,
+            modifiers,
+            held: KeySet::new(),
+            dead_keys: DeadKeys::new(read_compose_table()),
+        })
+    }
+
+    /// Translates a key press into the text it types, if any.
+    pub fn key_down(&mut self, key: Keycode) -> Option<String> {
+        self.held.insert(key);
+        let x11_key = x11_keycode(key)?;
+        let state = self.state();
+        let keysym = self.lookup_keysym(x11_key, state);
+        if is_modifier_keysym(keysym) {
+            return None;
+        }
+        if state & SHORTCUT_MASK != 0 {
+            self.dead_keys.cancel();
+            return None;
+        }
+        self.dead_keys.type_keysym(keysym)
+    }
+
+    /// Records a key release, so that released modifiers stop applying.
+    pub fn key_up(&mut self, key: Keycode) {
+        self.held.remove(key);
+    }
+
+    /// The core event state: the held and locked modifiers, and the active group.
+    fn state(&self) -> c_uint {
+        let mut held = 0;
+        for key in &self.held {
+            if let Some(x11_key) = x11_keycode(key) {
+                held |= self.modifiers[x11_key as usize];
+            }
+        }
+        let mut xkb_state: xlib::XkbStateRec = unsafe { mem::zeroed() };
+        self.x11.with_display(|display| unsafe {
+            xlib::XkbGetState(display, USE_CORE_KBD, &mut xkb_state);
+        });
+        let mods = held | xkb_state.locked_mods as c_uint | xkb_state.latched_mods as c_uint;
+        // The group is stored in bits 13 and 14 of the core state.
+        mods | (xkb_state.group as c_uint & 0x3) << 13
+    }
+
+    fn lookup_keysym(&self, x11_key: u8, state: c_uint) -> u32 {
+        let mut event: xlib::XKeyEvent = unsafe { mem::zeroed() };
+        event.type_ = xlib::KeyPress;
+        event.keycode = x11_key as c_uint;
+        event.state = state;
+        let mut buffer: [c_char; 32] = [0; 32];
+        let mut keysym = 0;
+        self.x11.with_display(|display| unsafe {
+            event.display = display;
+            xlib::XLookupString(
+                &mut event,
+                buffer.as_mut_ptr(),
+                buffer.len() as c_int,
+                &mut keysym,
+                ptr::null_mut(),
+            );
+        });
+        keysym as u32
+    }
+}
+
+impl fmt::Debug for KeyTranslator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyTranslator")
+            .field("held", &self.held)
+            .field("dead_key", &self.dead_keys.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Combines dead keys with the next typed keysym according to a Compose table.
+#[derive(Debug, Default)]
+struct DeadKeys {
+    /// The text of each dead keysym followed by another keysym.
+    compose: HashMap<(u32, u32), String>,
+    pending: Option<u32>,
+}
+
+impl DeadKeys {
+    fn new(compose: HashMap<(u32, u32), String>) -> DeadKeys {
+        DeadKeys {
+            compose,
+            pending: None,
+        }
+    }
+
+    /// The text typed by `keysym`, given the pending dead key.
+    fn type_keysym(&mut self, keysym: u32) -> Option<String> {
+        match self.pending.take() {
+            Some(dead_key) => {
+                if let Some(text) = self.compose.get(&(dead_key, keysym)) {
+                    return Some(text.clone());
+                }
+                if is_dead_keysym(keysym) {
+                    self.pending = Some(keysym);
+                    return self.spacing(dead_key);
+                }
+                let typed = keysym_to_char(keysym)?;
+                let mut text = self.spacing(dead_key).unwrap_or_default();
+                text.push(typed);
+                Some(text)
+            }
+            None if is_dead_keysym(keysym) => {
+                self.pending = Some(keysym);
+                None
+            }
+            None => keysym_to_char(keysym).map(String::from),
+        }
+    }
+
+    /// Drops the pending dead key.
+    fn cancel(&mut self) {
+        self.pending = None;
+    }
+
+    /// The text a dead key types on its own, as when followed by a space.
+    fn spacing(&self, dead_key: u32) -> Option<String> {
+        // XK_space.
+        self.compose.get(&(dead_key, 0x20)).cloned()
+    }
+}
+
+/// The X11 keycode of `key`: its kernel keycode with an offset of 8.
+fn x11_keycode(key: Keycode) -> Option<u8> {
+    let kernel_key = kernel_key::keycode_to_kernel_key(key)?;
+    u8::try_from(kernel_key + 8).ok()
+}
+
+/// Reads the modifier mapping of the server: the mask of the modifiers each keycode sets while
+/// held. Lock keys are left out, their state is read from the server instead.
+unsafe fn modifier_masks(display: *mut xlib::Display) -> [c_uint; 256] {
+    let mut masks = [0; 256];
+    let mapping = xlib::XGetModifierMapping(display);
+    if mapping.is_null() {
+        return masks;
+    }
+    let per_modifier = (*mapping).max_keypermod as usize;
+    let keycodes = slice::from_raw_parts((*mapping).modifiermap, 8 * per_modifier);
+    for (index, keycode) in keycodes.iter().enumerate() {
+        if *keycode == 0 {
+            continue;
+        }
+        let keysym = xlib::XkbKeycodeToKeysym(display, *keycode, 0, 0) as u32;
+        if !is_lock_keysym(keysym) {
+            masks[*keycode as usize] |= 1 << (index / per_modifier);
+        }
+    }
+    xlib::XFreeModifiermap(mapping);
+    masks
+}
+
+/// The current locale, as used for `LC_CTYPE`.
+fn current_locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+}
+
+/// The Compose file of `locale`, from the `compose.dir` index of the X locale directory.
+fn compose_file(locale_dir: &Path, locale: &str) -> Option<PathBuf> {
+    let index = fs::read_to_string(locale_dir.join("compose.dir")).ok()?;
+    index
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let mut words = line.split_whitespace();
+            let file = words.next()?.trim_end_matches(':');
+            (words.next()? == locale).then(|| locale_dir.join(file))
+        })
+}
+
+/// Reads the two key dead key sequences of the Compose file of the current locale.
+fn read_compose_table() -> HashMap<(u32, u32), String> {
+    let locale_dir = PathBuf::from(env::var("XLOCALEDIR").unwrap_or_else(|_| LOCALE_DIR.into()));
+    let files = [current_locale(), Some(DEFAULT_LOCALE.into())];
+    let contents = files
+        .iter()
+        .flatten()
+        .filter_map(|locale| compose_file(&locale_dir, locale))
+        // Files of locales that aren't UTF-8 have other encodings.
+        .find_map(|file| fs::read_to_string(file).ok())
+        .unwrap_or_default();
+    contents.lines().filter_map(parse_compose_line).collect()
+}
+
+/// Parses a line like `<dead_acute> <a> : "á" aacute`. The text may contain the escapes `\\`,
+/// `\"`, and octal (`\ooo`) or hexadecimal (`\xhh`) bytes of its UTF-8 encoding.
+fn parse_compose_line(line: &str) -> Option<((u32, u32), String)> {
+    let (sequence, result) = line.split_once(':')?;
+    let mut keys = sequence.split_whitespace();
+    let dead_key = keysym_from_name(keys.next()?.strip_prefix("<dead_")?, "dead_")?;
+    let key = keysym_from_name(keys.next()?.strip_prefix('<')?, "")?;
+    if keys.next().is_some() {
+        return None;
+    }
+    let quoted = result.trim_start().strip_prefix('"')?;
+    let mut text = vec![];
+    let mut chars = quoted.chars().peekable();
+    loop {
+        match chars.next()? {
+            '"' => break,
+            '\\' => {
+                let (radix, digits) = match chars.peek()? {
+                    'x' | 'X' => {
+                        chars.next();
+                        (16, 2)
+                    }
+                    '0'..='7' => (8, 3),
+                    _ => {
+                        push_char(&mut text, chars.next()?);
+                        continue;
+                    }
+                };
+                let mut byte = 0u32;
+                let mut read = 0;
+                while read < digits {
+                    match chars.peek().and_then(|c| c.to_digit(radix)) {
+                        Some(digit) => byte = byte * radix + digit,
+                        None => break,
+                    }
+                    chars.next();
+                    read += 1;
+                }
+                if read == 0 {
+                    return None;
+                }
+                text.push(u8::try_from(byte).ok()?);
+            }
+            c => push_char(&mut text, c),
+        }
+    }
+    Some(((dead_key, key), String::from_utf8(text).ok()?))
+}
+
+fn push_char(text: &mut Vec<u8>, c: char) {
+    text.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+/// The keysym named `prefix` followed by `name` up to its closing `>`.
+fn keysym_from_name(name: &str, prefix: &str) -> Option<u32> {
+    let name = CString::new(format!("{}{}", prefix, name.strip_suffix('>')?)).ok()?;
+    match unsafe { xlib::XStringToKeysym(name.as_ptr()) } {
+        0 => None,
+        keysym => Some(keysym as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEAD_GRAVE: u32 = 0xfe50;
+    const DEAD_ACUTE: u32 = 0xfe51;
+
+    fn dead_keys() -> DeadKeys {
+        let table = [
+            "<dead_acute> <a> : \"á\" aacute",
+            "<dead_acute> <space> : \"´\" acute",
+            "<dead_acute> <dead_acute> : \"´\" acute",
+            "<dead_grave> <space> : \"`\" grave",
+        ];
+        DeadKeys::new(
+            table
+                .iter()
+                .copied()
+                .filter_map(parse_compose_line)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn compose_lines_are_parsed() {
+        assert_eq!(
+            parse_compose_line("<dead_acute> <a> : \"á\" aacute # LATIN SMALL LETTER A WITH ACUTE"),
+            Some(((DEAD_ACUTE, 0x61), "á".into()))
+        );
+        assert_eq!(
+            parse_compose_line("  <dead_grave>   <space>:\"`\""),
+            Some(((DEAD_GRAVE, 0x20), "`".into()))
+        );
+        // A colon in the text doesn't end the sequence.
+        assert_eq!(
+            parse_compose_line("<dead_acute> <colon> : \":\""),
+            Some(((DEAD_ACUTE, 0x3a), ":".into()))
+        );
+    }
+
+    #[test]
+    fn compose_escapes_are_unescaped() {
+        let text = |line| parse_compose_line(line).map(|(_, text)| text);
+        assert_eq!(text("<dead_acute> <a> : \"\\\"\""), Some("\"".into()));
+        assert_eq!(text("<dead_acute> <a> : \"\\\\\""), Some("\\".into()));
+        assert_eq!(text("<dead_acute> <a> : \"\\303\\241\""), Some("á".into()));
+        assert_eq!(text("<dead_acute> <a> : \"\\xc3\\xA1\""), Some("á".into()));
+        assert_eq!(text("<dead_acute> <a> : \"\\101b\""), Some("Ab".into()));
+        // Escaped bytes that aren't UTF-8, and escapes without digits.
+        assert_eq!(text("<dead_acute> <a> : \"\\xe1\""), None);
+        assert_eq!(text("<dead_acute> <a> : \"\\x\""), None);
+    }
+
+    #[test]
+    fn other_compose_lines_are_skipped() {
+        let lines = [
+            "",
+            "# <dead_acute> <a> : \"á\" aacute",
+            "include \"%L\"",
+            "include \"%S/en_US.UTF-8/Compose\"",
+            // Sequences that don't start with a dead key, or aren't two keys long.
+            "<Multi_key> <a> <apostrophe> : \"á\" aacute",
+            "<a> <b> : \"c\"",
+            "<dead_acute> : \"´\"",
+            "<dead_acute> <dead_acute> <a> : \"á\"",
+            // Malformed lines.
+            "<dead_acute> <a> \"á\"",
+            "<dead_acute> <a> : aacute",
+            "<dead_acute> <a> : \"á",
+            "<dead_acute> <a : \"á\"",
+            "<dead_nonexistent> <a> : \"á\"",
+            "<dead_acute> <nonexistent> : \"á\"",
+        ];
+        for line in lines {
+            assert_eq!(parse_compose_line(line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn dead_key_combines_with_the_next_key() {
+        let mut dead_keys = dead_keys();
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), None);
+        assert_eq!(dead_keys.type_keysym(0x61), Some("á".into()));
+        assert_eq!(dead_keys.type_keysym(0x61), Some("a".into()));
+    }
+
+    #[test]
+    fn dead_key_types_alone_before_other_keys() {
+        let mut dead_keys = dead_keys();
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), None);
+        assert_eq!(dead_keys.type_keysym(0x71), Some("´q".into()));
+        // Keys that type nothing drop the dead key.
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), None);
+        assert_eq!(dead_keys.type_keysym(0xff0d), None);
+        assert_eq!(dead_keys.type_keysym(0x61), Some("a".into()));
+    }
+
+    #[test]
+    fn double_dead_keys() {
+        let mut dead_keys = dead_keys();
+        // Dead keys combined in the table.
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), None);
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), Some("´".into()));
+        assert_eq!(dead_keys.type_keysym(0x61), Some("a".into()));
+        // The first dead key types alone and the second one stays pending.
+        assert_eq!(dead_keys.type_keysym(DEAD_GRAVE), None);
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), Some("`".into()));
+        assert_eq!(dead_keys.type_keysym(0x61), Some("á".into()));
+    }
+
+    #[test]
+    fn cancelled_dead_key_types_nothing() {
+        let mut dead_keys = dead_keys();
+        assert_eq!(dead_keys.type_keysym(DEAD_ACUTE), None);
+        dead_keys.cancel();
+        assert_eq!(dead_keys.type_keysym(0x61), Some("a".into()));
+    }
+}
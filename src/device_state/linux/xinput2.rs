@@ -0,0 +1,483 @@
+//! Event-driven backend receiving XInput2 raw events from the X server.
+
+use super::libc;
+use super::x11::xlib;
+use super::x11_dl::xinput2::{self, XInput2};
+use super::{kernel_key, root_pointer, X11Backend};
+use button_set::ButtonSet;
+use device_events::{DeviceEvent, TimedEvent};
+use device_state::DeviceBackend;
+use error::DeviceQueryError;
+use key_set::KeySet;
+use keymap::Keycode;
+use mouse_state::{MouseButton, MouseDelta, MousePosition, MouseState, ScrollDelta};
+use screen::{Monitor, PointerLocation};
+use std::convert::TryFrom;
+use std::mem;
+use std::os::raw::{c_int, c_uint};
+use std::slice;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Raw events this backend listens to on the root window.
+const RAW_EVENTS: [c_int; 5] = [
+    xinput2::XI_RawKeyPress,
+    xinput2::XI_RawKeyRelease,
+    xinput2::XI_RawButtonPress,
+    xinput2::XI_RawButtonRelease,
+    xinput2::XI_RawMotion,
+];
+
+/// Longest time `wait` polls the X connection before checking the event queue again.
+const POLL_SLICE: Duration = Duration::from_millis(10);
+
+/// A scroll valuator of a slave device: its motion, divided by `increment`, is the number of
+/// notches scrolled.
+#[derive(Debug, Clone, Copy)]
+struct ScrollAxis {
+    device: c_int,
+    number: c_int,
+    horizontal: bool,
+    increment: f64,
+}
+
+/// The valuators of the slave devices, updated when devices are added or removed.
+#[derive(Debug, Default)]
+struct Valuators {
+    scroll_axes: Vec<ScrollAxis>,
+    /// The devices whose valuators 0 and 1 are relative motion, like mice and touchpads.
+    relative_devices: Vec<c_int>,
+}
+
+/// The valuators of a raw motion event.
+#[derive(Debug, Default)]
+struct RawMotion {
+    scroll: ScrollDelta,
+    /// The motion along valuators 0 and 1 of a relative device, before acceleration.
+    motion: (f64, f64),
+    /// Whether a valuator other than a scroll valuator changed, so that the pointer may have
+    /// moved.
+    moved: bool,
+}
+
+fn unsupported_extension() -> DeviceQueryError {
+    DeviceQueryError::BackendUnsupported(String::from("the X server doesn't support XInput 2.1"))
+}
+
+/// Backend querying an X server like [`X11Backend`], which additionally blocks on the X
+/// connection for XInput2 raw events instead of being polled.
+///
+/// Raw events are reported for every key press and button press, however short, and regardless
+/// of which window has the focus or holds a grab.
+///
+/// Scrolling is reported in fractions of a notch by devices with XInput 2.1 smooth scrolling,
+/// such as touchpads and high resolution wheels, and in whole notches from the legacy wheel
+/// buttons 4 to 7 otherwise. Unlike the core protocol, every button is reported, including the
+/// back and forward buttons.
+///
+/// Mouse motion is reported from the raw values of relative devices, in device units before
+/// acceleration. Absolute devices, like tablets and touchscreens, report none.
+pub struct XInput2Backend {
+    // Declared before `xinput2` so that the display is closed before libXi is unloaded.
+    x11: X11Backend,
+    opcode: c_int,
+    valuators: Mutex<Valuators>,
+    // Fractions of a device unit of motion, added to the next raw motion.
+    motion_remainder: Mutex<(f64, f64)>,
+    // The pointer position last reported by a `MouseMove`.
+    position: Mutex<MousePosition>,
+    // libXi must stay loaded: it converts the raw events read through Xlib.
+    xinput2: XInput2,
+}
+
+impl XInput2Backend {
+    /// Opens the default X display and selects raw events on its root window. Fails if no
+    /// display could be opened, or if libXi or XInput 2.1 is not available.
+    pub fn open() -> Result<XInput2Backend, DeviceQueryError> {
+        let xinput2 = XInput2::open()
+            .map_err(|_| DeviceQueryError::BackendUnsupported(String::from("libXi not found")))?;
+        let x11 = X11Backend::open()?;
+        let opcode = x11.with_display(|display| unsafe {
+            let mut opcode = 0;
+            let mut event = 0;
+            let mut error = 0;
+            let name = b"XInputExtension\0";
+            if xlib::XQueryExtension(
+                display,
+                name.as_ptr() as *const _,
+                &mut opcode,
+                &mut event,
+                &mut error,
+            ) == 0
+            {
+                return Err(unsupported_extension());
+            }
+
+            // Raw events are delivered to the root window regardless of grabs since XI 2.1.
+            let mut major = 2;
+            let mut minor = 1;
+            if (xinput2.XIQueryVersion)(display as *mut _, &mut major, &mut minor)
+                != xlib::Success as c_int
+                || (major, minor) < (2, 1)
+            {
+                return Err(unsupported_extension());
+            }
+
+            let mut mask = [0; (xinput2::XI_LASTEVENT as usize >> 3) + 1];
+            for event in RAW_EVENTS {
+                xinput2::XISetMask(&mut mask, event);
+            }
+            // Devices being added or removed change the scroll valuators.
+            let mut hierarchy_mask = [0; (xinput2::XI_LASTEVENT as usize >> 3) + 1];
+            xinput2::XISetMask(&mut hierarchy_mask, xinput2::XI_HierarchyChanged);
+            let mut event_masks = [
+                xinput2::XIEventMask {
+                    deviceid: xinput2::XIAllMasterDevices,
+                    mask_len: mask.len() as c_int,
+                    mask: mask.as_mut_ptr(),
+                },
+                xinput2::XIEventMask {
+                    deviceid: xinput2::XIAllDevices,
+                    mask_len: hierarchy_mask.len() as c_int,
+                    mask: hierarchy_mask.as_mut_ptr(),
+                },
+            ];
+            let root = xlib::XDefaultRootWindow(display);
+            (xinput2.XISelectEvents)(
+                display as *mut _,
+                root,
+                event_masks.as_mut_ptr(),
+                event_masks.len() as c_int,
+            );
+            xlib::XSync(display, xlib::False);
+            Ok(opcode)
+        })?;
+        let position = x11.query_pointer().coords;
+        let backend = XInput2Backend {
+            x11,
+            opcode,
+            valuators: Mutex::new(Valuators::default()),
+            motion_remainder: Mutex::new((0.0, 0.0)),
+            position: Mutex::new(position),
+            xinput2,
+        };
+        backend
+            .x11
+            .with_display(|display| backend.update_valuators(display));
+        Ok(backend)
+    }
+
+    /// Queries the valuators of every device on the locked `display`. Querying every device at
+    /// once can't fail, unlike querying a device that may have been removed since.
+    fn update_valuators(&self, display: *mut xlib::Display) {
+        let mut valuators = Valuators::default();
+        unsafe {
+            let mut count = 0;
+            let display = display as *mut _;
+            let devices = (self.xinput2.XIQueryDevice)(display, xinput2::XIAllDevices, &mut count);
+            if devices.is_null() {
+                return;
+            }
+            for device in slice::from_raw_parts(devices, count as usize) {
+                let classes = slice::from_raw_parts(device.classes, device.num_classes as usize);
+                for class in classes {
+                    if (**class)._type == xinput2::XIValuatorClass {
+                        let valuator = &*(*class as *const xinput2::XIValuatorClassInfo);
+                        if valuator.number == 0 && valuator.mode == xinput2::XIModeRelative {
+                            valuators.relative_devices.push(device.deviceid);
+                        }
+                    }
+                    if (**class)._type != xinput2::XIScrollClass {
+                        continue;
+                    }
+                    let scroll = &*(*class as *const xinput2::XIScrollClassInfo);
+                    if scroll.increment != 0.0 {
+                        valuators.scroll_axes.push(ScrollAxis {
+                            device: device.deviceid,
+                            number: scroll.number,
+                            horizontal: scroll.scroll_type == xinput2::XIScrollTypeHorizontal,
+                            increment: scroll.increment,
+                        });
+                    }
+                }
+            }
+            (self.xinput2.XIFreeDeviceInfo)(devices);
+        }
+        if let Ok(mut current) = self.valuators.lock() {
+            *current = valuators;
+        }
+    }
+
+    /// Blocks until events are queued, the X connection has data to read, or `timeout` elapses.
+    fn wait(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let connection = self.x11.with_display(|display| unsafe {
+                Some(xlib::XConnectionNumber(display)).filter(|_| xlib::XPending(display) == 0)
+            });
+            let Some(connection) = connection else {
+                return;
+            };
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return;
+            }
+            // Polled without the display lock, so that queries aren't blocked meanwhile. A query
+            // may then read the events into the queue of the display, leaving nothing to poll,
+            // hence the queue is checked again after each slice.
+            let mut fd = libc::pollfd {
+                fd: connection,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let slice = remaining.min(POLL_SLICE).as_millis().max(1) as c_int;
+            if unsafe { libc::poll(&mut fd, 1, slice) } > 0 {
+                return;
+            }
+        }
+    }
+
+    fn raw_event(&self, evtype: c_int, raw: &xinput2::XIRawEvent) -> Option<DeviceEvent> {
+        match evtype {
+            xinput2::XI_RawKeyPress | xinput2::XI_RawKeyRelease => {
+                if raw.flags & xinput2::XIKeyRepeat != 0 {
+                    return None;
+                }
+                // X11 keycodes are kernel keycodes with an offset of 8.
+                let kernel_key = u16::try_from(raw.detail).ok()?.checked_sub(8)?;
+                let key = kernel_key::kernel_key_to_keycode(kernel_key)?;
+                if evtype == xinput2::XI_RawKeyPress {
+                    Some(DeviceEvent::KeyDown(key))
+                } else {
+                    Some(DeviceEvent::KeyUp(key))
+                }
+            }
+            xinput2::XI_RawButtonPress | xinput2::XI_RawButtonRelease => {
+                let x11_button = c_uint::try_from(raw.detail).ok()?;
+                if let 4..=7 = x11_button {
+                    // Smooth scrolling devices also emulate wheel buttons, which are skipped
+                    // since their valuators were already reported. Each wheel notch is a press.
+                    if evtype != xinput2::XI_RawButtonPress
+                        || raw.flags & xinput2::XIPointerEmulated != 0
+                    {
+                        return None;
+                    }
+                    let (horizontal, vertical) = match x11_button {
+                        4 => (0, -1),
+                        5 => (0, 1),
+                        6 => (-1, 0),
+                        _ => (1, 0),
+                    };
+                    return Some(DeviceEvent::Scroll(ScrollDelta::from_notches(
+                        horizontal, vertical,
+                    )));
+                }
+                let button = MouseButton::from_x11_button(x11_button)?;
+                if evtype == xinput2::XI_RawButtonPress {
+                    Some(DeviceEvent::MouseDown(button))
+                } else {
+                    Some(DeviceEvent::MouseUp(button))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Splits the valuators of a raw motion event into the scrolling done, the motion of the
+    /// device, and whether the pointer itself moved.
+    fn raw_motion(&self, raw: &xinput2::XIRawEvent) -> RawMotion {
+        let Ok(valuators) = self.valuators.lock() else {
+            return RawMotion {
+                moved: true,
+                ..RawMotion::default()
+            };
+        };
+        let relative = valuators.relative_devices.contains(&raw.sourceid);
+        let mask =
+            unsafe { slice::from_raw_parts(raw.valuators.mask, raw.valuators.mask_len as usize) };
+        let mut notches = [0.0; 2];
+        let mut motion = RawMotion::default();
+        // `values` and `raw_values` hold one value per valuator set in `mask`, in order. The
+        // motion is read from `raw_values`, before acceleration.
+        let mut index = 0;
+        for number in 0..(mask.len() * 8) as c_int {
+            if !xinput2::XIMaskIsSet(mask, number) {
+                continue;
+            }
+            let (value, raw_value) =
+                unsafe { (*raw.valuators.values.add(index), *raw.raw_values.add(index)) };
+            index += 1;
+            match valuators
+                .scroll_axes
+                .iter()
+                .find(|axis| axis.device == raw.sourceid && axis.number == number)
+            {
+                Some(axis) => notches[axis.horizontal as usize] += value / axis.increment,
+                None => {
+                    motion.moved = true;
+                    match number {
+                        0 if relative => motion.motion.0 += raw_value,
+                        1 if relative => motion.motion.1 += raw_value,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        let units = |notches: f64| (notches * f64::from(ScrollDelta::NOTCH)).round() as i32;
+        motion.scroll = ScrollDelta {
+            horizontal: units(notches[1]),
+            vertical: units(notches[0]),
+        };
+        motion
+    }
+
+    /// Adds `motion` to the fractions of a unit not reported yet, and takes the whole units.
+    fn take_motion(&self, motion: (f64, f64)) -> MouseDelta {
+        let mut remainder = self
+            .motion_remainder
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let (x, y) = (remainder.0 + motion.0, remainder.1 + motion.1);
+        *remainder = (x.fract(), y.fract());
+        (x.trunc() as i32, y.trunc() as i32)
+    }
+
+    /// Inserts a `MouseMove` at `index` of `events` if the pointer of the locked `display` moved
+    /// since the last one. Raw motion is unaccelerated device motion, so the pointer position is
+    /// queried instead.
+    fn insert_move(
+        &self,
+        display: *mut xlib::Display,
+        (index, time): (usize, Option<Duration>),
+        events: &mut Vec<TimedEvent>,
+    ) {
+        let coords = unsafe { root_pointer(display) }.coords;
+        let mut position = self.position.lock().unwrap_or_else(PoisonError::into_inner);
+        if *position != coords {
+            *position = coords;
+            events.insert(index, TimedEvent::new(DeviceEvent::MouseMove(coords), time));
+        }
+    }
+
+    /// The buttons held down, including the ones above 5 that the core protocol doesn't report.
+    fn pressed_buttons(&self) -> Option<ButtonSet> {
+        let mut pressed = ButtonSet::new();
+        self.x11.with_display(|display| unsafe {
+            let root = xlib::XDefaultRootWindow(display);
+            let display = display as *mut _;
+            let mut pointer = 0;
+            if (self.xinput2.XIGetClientPointer)(display, 0, &mut pointer) == xlib::False {
+                return None;
+            }
+            let (mut root_return, mut child_return) = (0, 0);
+            let (mut root_x, mut root_y, mut win_x, mut win_y) = (0.0, 0.0, 0.0, 0.0);
+            let mut buttons = xinput2::XIButtonState::default();
+            let mut modifiers = xinput2::XIModifierState::default();
+            let mut group = xinput2::XIGroupState::default();
+            if (self.xinput2.XIQueryPointer)(
+                display,
+                pointer,
+                root,
+                &mut root_return,
+                &mut child_return,
+                &mut root_x,
+                &mut root_y,
+                &mut win_x,
+                &mut win_y,
+                &mut buttons,
+                &mut modifiers,
+                &mut group,
+            ) == xlib::False
+            {
+                return None;
+            }
+            if !buttons.mask.is_null() {
+                let mask = slice::from_raw_parts(buttons.mask, buttons.mask_len as usize);
+                let x11_buttons = (0..(mask.len() * 8) as c_int)
+                    .filter(|bit| xinput2::XIMaskIsSet(mask, *bit))
+                    .filter_map(|bit| MouseButton::from_x11_button(bit as c_uint));
+                pressed.extend(x11_buttons);
+                xlib::XFree(buttons.mask as *mut _);
+            }
+            Some(pressed)
+        })
+    }
+}
+
+impl DeviceBackend for XInput2Backend {
+    fn query_pointer(&self) -> MouseState {
+        let mut state = self.x11.query_pointer();
+        if let Some(pressed) = self.pressed_buttons() {
+            state.button_pressed = pressed;
+        }
+        state
+    }
+
+    fn query_monitors(&self) -> Vec<Monitor> {
+        self.x11.query_monitors()
+    }
+
+    fn query_pointer_location(&self) -> PointerLocation {
+        self.x11.query_pointer_location()
+    }
+
+    fn query_keymap(&self) -> Vec<Keycode> {
+        self.x11.query_keymap()
+    }
+
+    fn query_key_set(&self) -> KeySet {
+        self.x11.query_key_set()
+    }
+
+    fn read_events(&self, timeout: Duration, events: &mut Vec<TimedEvent>) -> bool {
+        self.wait(timeout);
+        self.x11.with_display(|display| unsafe {
+            // The index and time of the last raw motion that may have moved the pointer. The
+            // pointer is queried once before the next key or button event rather than for every
+            // motion.
+            let mut moved = None;
+            while xlib::XPending(display) > 0 {
+                let mut event: xlib::XEvent = mem::zeroed();
+                xlib::XNextEvent(display, &mut event);
+                let mut cookie = event.generic_event_cookie;
+                if cookie.type_ != xlib::GenericEvent || cookie.extension != self.opcode {
+                    continue;
+                }
+                if xlib::XGetEventData(display, &mut cookie) == xlib::False {
+                    continue;
+                }
+                if cookie.evtype == xinput2::XI_HierarchyChanged {
+                    xlib::XFreeEventData(display, &mut cookie);
+                    self.update_valuators(display);
+                    continue;
+                }
+                let raw = &*(cookie.data as *const xinput2::XIRawEvent);
+                // The X server time is in milliseconds.
+                let time = Some(Duration::from_millis(raw.time as u64));
+                if cookie.evtype == xinput2::XI_RawMotion {
+                    let motion = self.raw_motion(raw);
+                    if motion.moved {
+                        moved = Some((events.len(), time));
+                    }
+                    if !motion.scroll.is_zero() {
+                        events.push(TimedEvent::new(DeviceEvent::Scroll(motion.scroll), time));
+                    }
+                    let delta = self.take_motion(motion.motion);
+                    if delta != (0, 0) {
+                        events.push(TimedEvent::new(DeviceEvent::MouseMotion(delta), time));
+                    }
+                } else if let Some(event) = self.raw_event(cookie.evtype, raw) {
+                    if let Some(moved) = moved.take() {
+                        self.insert_move(display, moved, events);
+                    }
+                    events.push(TimedEvent::new(event, time));
+                }
+                xlib::XFreeEventData(display, &mut cookie);
+            }
+            if let Some(moved) = moved {
+                self.insert_move(display, moved, events);
+            }
+        });
+        true
+    }
+}
@@ -0,0 +1,142 @@
+//! Input synthesis through the XTest extension.
+
+use super::x11::xlib;
+// Despite its name, `Xf86vmode` is the x11-dl struct of the libXtst functions.
+use super::x11_dl::xtest::Xf86vmode as XTest;
+use super::{kernel_key, X11Backend};
+use device_simulate::DeviceSimulate;
+use device_state::DeviceBackend;
+use error::DeviceQueryError;
+use keymap::Keycode;
+use mouse_state::{MouseButton, MousePosition};
+use std::os::raw::{c_int, c_uint};
+
+/// The most wheel ticks sent per axis and call to `scroll`, since the clicks are sent while the
+/// display is locked.
+const MAX_SCROLL_TICKS: u32 = 1000;
+
+/// Generates keyboard and mouse input on an X server through the XTest extension.
+///
+/// Scrolling sends at most 1000 wheel ticks per axis and call.
+pub struct InputSimulator {
+    // Declared before `xtest` so that the display is closed before libXtst is unloaded.
+    x11: X11Backend,
+    xtest: XTest,
+}
+
+impl InputSimulator {
+    /// Opens the default X display.
+    ///
+    /// Fails with [`DeviceQueryError::DisplayUnavailable`] if no display could be opened, or
+    /// [`DeviceQueryError::BackendUnsupported`] if libXtst or the XTest extension is missing.
+    pub fn new() -> Result<InputSimulator, DeviceQueryError> {
+        let xtest = XTest::open()
+            .map_err(|_| DeviceQueryError::BackendUnsupported(String::from("libXtst not found")))?;
+        let x11 = X11Backend::open()?;
+        let supported = x11.with_display(|display| unsafe {
+            let mut event = 0;
+            let mut error = 0;
+            let mut major = 0;
+            let mut minor = 0;
+            let display = display as *mut _;
+            (xtest.XTestQueryExtension)(display, &mut event, &mut error, &mut major, &mut minor)
+                != xlib::False
+        });
+        if !supported {
+            return Err(DeviceQueryError::BackendUnsupported(String::from(
+                "the X server doesn't support XTest",
+            )));
+        }
+        Ok(InputSimulator { x11, xtest })
+    }
+
+    fn send_key(&self, key: Keycode, down: bool) {
+        let Some(kernel_key) = kernel_key::keycode_to_kernel_key(key) else {
+            return;
+        };
+        // X11 keycodes are kernel keycodes with an offset of 8, and fit in a byte.
+        let x11_key = kernel_key as c_uint + 8;
+        if x11_key > 255 {
+            return;
+        }
+        self.x11.with_display(|display| unsafe {
+            (self.xtest.XTestFakeKeyEvent)(
+                display as *mut _,
+                x11_key,
+                down as c_int,
+                xlib::CurrentTime,
+            );
+            // Waits for the server to process the event, so that it can be queried right away.
+            xlib::XSync(display, xlib::False);
+        });
+    }
+
+    /// Sends X11 pointer button `x11_button`, numbered from 1.
+    fn send_button(&self, x11_button: c_uint, down: bool) {
+        self.x11.with_display(|display| unsafe {
+            (self.xtest.XTestFakeButtonEvent)(
+                display as *mut _,
+                x11_button,
+                down as c_int,
+                xlib::CurrentTime,
+            );
+            xlib::XSync(display, xlib::False);
+        });
+    }
+
+    /// Clicks `x11_button` `ticks` times, at most `MAX_SCROLL_TICKS`.
+    fn scroll_ticks(&self, x11_button: c_uint, ticks: u32) {
+        self.x11.with_display(|display| unsafe {
+            for _ in 0..ticks.min(MAX_SCROLL_TICKS) {
+                for down in [true, false] {
+                    (self.xtest.XTestFakeButtonEvent)(
+                        display as *mut _,
+                        x11_button,
+                        down as c_int,
+                        xlib::CurrentTime,
+                    );
+                }
+            }
+            xlib::XSync(display, xlib::False);
+        });
+    }
+}
+
+impl DeviceSimulate for InputSimulator {
+    fn key_down(&self, key: Keycode) {
+        self.send_key(key, true)
+    }
+
+    fn key_up(&self, key: Keycode) {
+        self.send_key(key, false)
+    }
+
+    fn mouse_move_to(&self, (x, y): MousePosition) {
+        self.x11.with_display(|display| unsafe {
+            // Screen -1 moves the pointer on the screen it is currently on.
+            (self.xtest.XTestFakeMotionEvent)(display as *mut _, -1, x, y, xlib::CurrentTime);
+            xlib::XSync(display, xlib::False);
+        });
+    }
+
+    fn mouse_move_by(&self, dx: i32, dy: i32) {
+        let (x, y) = self.x11.query_pointer().coords;
+        self.mouse_move_to((x.saturating_add(dx), y.saturating_add(dy)))
+    }
+
+    fn mouse_down(&self, button: MouseButton) {
+        self.send_button(button.x11_button(), true)
+    }
+
+    fn mouse_up(&self, button: MouseButton) {
+        self.send_button(button.x11_button(), false)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) {
+        // X11 reports wheel ticks as clicks of buttons 4 (up), 5 (down), 6 (left) and 7 (right).
+        let vertical = if dy < 0 { 4 } else { 5 };
+        self.scroll_ticks(vertical, dy.unsigned_abs());
+        let horizontal = if dx < 0 { 6 } else { 7 };
+        self.scroll_ticks(horizontal, dx.unsigned_abs());
+    }
+}
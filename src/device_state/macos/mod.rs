@@ -1,10 +1,101 @@
 extern crate macos_accessibility_client;
 
+use device_simulate::DeviceSimulate;
+use device_state::{DeviceBackend, DeviceState};
+use error::DeviceQueryError;
+use key_set::KeySet;
 use keymap::Keycode;
-use mouse_state::MouseState;
+use mouse_state::{MouseButton, MousePosition, MouseState};
+use screen::Monitor;
+use std::convert::TryFrom;
+use std::os::raw::{c_ulong, c_void};
+use std::ptr;
 
+type CGEventRef = *mut c_void;
+type CGDirectDisplayID = u32;
+type CGDisplayModeRef = *mut c_void;
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct CGPoint {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct CGSize {
+    width: f64,
+    height: f64,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct CGRect {
+    origin: CGPoint,
+    size: CGSize,
+}
+
+/// kCGHIDEventTap: post events where HID system events enter the window server.
+const HID_EVENT_TAP: u32 = 0;
+/// kCGEventMouseMoved.
+const MOUSE_MOVED: u32 = 5;
+/// kCGEventSourceStateCombinedSessionState.
+const COMBINED_SESSION_STATE: i32 = 0;
+/// kCGScrollEventUnitLine.
+const SCROLL_UNIT_LINE: u32 = 1;
+/// Maximum number of displays listed, more than a Mac can drive.
+const MAX_DISPLAYS: usize = 32;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventCreateKeyboardEvent(
+        source: *const c_void,
+        keycode: u16,
+        key_down: bool,
+    ) -> CGEventRef;
+    fn CGEventKeyboardSetUnicodeString(event: CGEventRef, length: c_ulong, string: *const u16);
+    fn CGEventCreateMouseEvent(
+        source: *const c_void,
+        event_type: u32,
+        position: CGPoint,
+        button: u32,
+    ) -> CGEventRef;
+    fn CGEventCreateScrollWheelEvent(
+        source: *const c_void,
+        units: u32,
+        wheel_count: u32,
+        wheel1: i32,
+        ...
+    ) -> CGEventRef;
+    fn CGEventPost(tap: u32, event: CGEventRef);
+    fn CGEventSourceButtonState(state: i32, button: u32) -> bool;
+    fn CGGetActiveDisplayList(
+        max_displays: u32,
+        active_displays: *mut CGDirectDisplayID,
+        display_count: *mut u32,
+    ) -> i32;
+    fn CGDisplayBounds(display: CGDirectDisplayID) -> CGRect;
+    fn CGMainDisplayID() -> CGDirectDisplayID;
+    fn CGDisplayCopyDisplayMode(display: CGDirectDisplayID) -> CGDisplayModeRef;
+    fn CGDisplayModeGetWidth(mode: CGDisplayModeRef) -> usize;
+    fn CGDisplayModeGetPixelWidth(mode: CGDisplayModeRef) -> usize;
+    fn CGDisplayModeRelease(mode: CGDisplayModeRef);
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFRelease(cf: *const c_void);
+}
+
+/// Backend querying the macOS input APIs.
+///
+/// Coordinates and monitor sizes are in points, which are pixels scaled by the
+/// [`scale`](crate::Monitor::scale) of the display. The window under the pointer is not known.
+///
+/// Only the keys of `MAPPING` can be queried, so it never reports `Keycode::Unknown`.
 #[derive(Debug, Clone)]
-pub struct DeviceState;
+pub struct MacosBackend;
 const MAPPING: &[(readkey::Keycode, Keycode)] = &[
     (readkey::Keycode::_0, Keycode::Key0),
     (readkey::Keycode::_1, Keycode::Key1),
@@ -113,37 +204,61 @@ const MAPPING: &[(readkey::Keycode, Keycode)] = &[
     (readkey::Keycode::Comma, Keycode::Comma),
     (readkey::Keycode::Period, Keycode::Dot),
     (readkey::Keycode::Slash, Keycode::Slash),
+    (readkey::Keycode::KeypadClear, Keycode::NumLock),
+    (readkey::Keycode::VolumeUp, Keycode::VolumeUp),
+    (readkey::Keycode::VolumeDown, Keycode::VolumeDown),
+    (readkey::Keycode::Mute, Keycode::VolumeMute),
+    (readkey::Keycode::Section, Keycode::IntlBackslash),
+    (readkey::Keycode::Underscore, Keycode::IntlRo),
+    (readkey::Keycode::Yen, Keycode::IntlYen),
+    (readkey::Keycode::KeypadComma, Keycode::NumpadComma),
+    (readkey::Keycode::Kana, Keycode::Lang1),
+    (readkey::Keycode::Eisu, Keycode::Lang2),
 ];
 
+/// The keys currently pressed, in `MAPPING` order.
+fn pressed_keys() -> impl Iterator<Item = Keycode> {
+    MAPPING
+        .iter()
+        .filter(|(from, _)| from.is_pressed())
+        .map(|(_, to)| *to)
+}
+
 impl DeviceState {
+    /// Creates a new DeviceState.
+    ///
+    /// # Panics
+    ///
+    /// If the app doesn't have Accessibility permissions. Use [`try_new`](Self::try_new) to
+    /// handle the error.
     pub fn new() -> DeviceState {
-        // TODO: remove this
-        assert!(
-            has_accessibility(),
-            "This app does not have Accessibility Permissions enabled and will not work"
-        );
-
-        DeviceState {}
+        Self::try_new()
+            .expect("This app does not have Accessibility Permissions enabled and will not work")
     }
 
     /// returns `None` if app doesn't accessibility permissions.
     pub fn checked_new() -> Option<DeviceState> {
+        Self::try_new().ok()
+    }
+
+    /// Fails with [`DeviceQueryError::PermissionDenied`] if the app doesn't have Accessibility
+    /// permissions.
+    pub fn try_new() -> Result<DeviceState, DeviceQueryError> {
         if has_accessibility() {
-            Some(DeviceState {})
+            Ok(DeviceState::from_backend(MacosBackend))
         } else {
-            None
+            Err(DeviceQueryError::PermissionDenied)
         }
     }
+}
 
-    pub(crate) fn query_pointer(&self) -> MouseState {
+impl DeviceBackend for MacosBackend {
+    fn query_pointer(&self) -> MouseState {
         let (x, y) = readmouse::Mouse::location();
-        let button_pressed = [
-            readmouse::Mouse::Left.is_pressed(),
-            readmouse::Mouse::Right.is_pressed(),
-            readmouse::Mouse::Center.is_pressed(),
-            false,
-            false,
-        ];
+        let button_pressed = (0..32)
+            .filter(|button| unsafe { CGEventSourceButtonState(COMBINED_SESSION_STATE, *button) })
+            .filter_map(MouseButton::from_macos_button)
+            .collect();
 
         MouseState {
             coords: (x as i32, y as i32),
@@ -151,12 +266,193 @@ impl DeviceState {
         }
     }
 
-    pub(crate) fn query_keymap(&self) -> Vec<Code> {
-        MAPPING
-            .iter()
-            .filter(|(from, _)| from.is_pressed())
-            .map(|(_, to)| *to)
-            .collect()
+    fn query_monitors(&self) -> Vec<Monitor> {
+        let mut displays = [0; MAX_DISPLAYS];
+        let mut count = 0;
+        unsafe {
+            if CGGetActiveDisplayList(MAX_DISPLAYS as u32, displays.as_mut_ptr(), &mut count) != 0 {
+                return vec![];
+            }
+            let main = CGMainDisplayID();
+            displays[..count as usize]
+                .iter()
+                .map(|display| {
+                    let bounds = CGDisplayBounds(*display);
+                    Monitor {
+                        name: None,
+                        screen: 0,
+                        position: (bounds.origin.x as i32, bounds.origin.y as i32),
+                        size: (bounds.size.width as u32, bounds.size.height as u32),
+                        scale: display_scale(*display),
+                        primary: *display == main,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    fn query_keymap(&self) -> Vec<Keycode> {
+        pressed_keys().collect()
+    }
+
+    fn query_key_set(&self) -> KeySet {
+        pressed_keys().collect()
+    }
+}
+
+/// The pixels per point of the current mode of `display`.
+fn display_scale(display: CGDirectDisplayID) -> f64 {
+    unsafe {
+        let mode = CGDisplayCopyDisplayMode(display);
+        if mode.is_null() {
+            return 1.0;
+        }
+        let width = CGDisplayModeGetWidth(mode);
+        let pixel_width = CGDisplayModeGetPixelWidth(mode);
+        CGDisplayModeRelease(mode);
+        if width == 0 {
+            1.0
+        } else {
+            pixel_width as f64 / width as f64
+        }
+    }
+}
+
+/// Generates keyboard and mouse input by posting Quartz events.
+#[derive(Debug, Clone)]
+pub struct InputSimulator;
+
+impl InputSimulator {
+    /// Fails with [`DeviceQueryError::PermissionDenied`] if the app doesn't have Accessibility
+    /// permissions.
+    pub fn new() -> Result<InputSimulator, DeviceQueryError> {
+        if has_accessibility() {
+            Ok(InputSimulator)
+        } else {
+            Err(DeviceQueryError::PermissionDenied)
+        }
+    }
+
+    fn post_key(&self, virtual_key: u16, down: bool, text: Option<&[u16]>) {
+        unsafe {
+            let event = CGEventCreateKeyboardEvent(ptr::null(), virtual_key, down);
+            if event.is_null() {
+                return;
+            }
+            if let Some(text) = text {
+                CGEventKeyboardSetUnicodeString(event, text.len() as c_ulong, text.as_ptr());
+            }
+            post(event);
+        }
+    }
+
+    fn post_mouse(&self, event_type: u32, button: u32, (x, y): MousePosition) {
+        let position = CGPoint {
+            x: x as f64,
+            y: y as f64,
+        };
+        unsafe {
+            post(CGEventCreateMouseEvent(
+                ptr::null(),
+                event_type,
+                position,
+                button,
+            ));
+        }
+    }
+
+    fn send_button(&self, button: MouseButton, down: bool) {
+        // kCGEventLeftMouseDown, kCGEventRightMouseDown and kCGEventOtherMouseDown, each followed
+        // by the matching mouse up event type.
+        let Some(number) = button.macos_button() else {
+            return;
+        };
+        let event_type = match button {
+            MouseButton::Left => 1,
+            MouseButton::Right => 3,
+            _ => 25,
+        };
+        let event_type = if down { event_type } else { event_type + 1 };
+        let position = MacosBackend.query_pointer().coords;
+        self.post_mouse(event_type, number, position);
+    }
+
+    fn send_key(&self, key: Keycode, down: bool) {
+        if let Keycode::Unknown { platform_code } = key {
+            if let Ok(virtual_key) = u16::try_from(platform_code) {
+                self.post_key(virtual_key, down, None);
+            }
+        } else if let Some((from, _)) = MAPPING.iter().find(|(_, to)| *to == key) {
+            self.post_key(*from as u16, down, None);
+        }
+    }
+}
+
+impl DeviceSimulate for InputSimulator {
+    fn key_down(&self, key: Keycode) {
+        self.send_key(key, true)
+    }
+
+    fn key_up(&self, key: Keycode) {
+        self.send_key(key, false)
+    }
+
+    /// Types `text` as unicode characters, independently of the keyboard layout. Never fails.
+    fn type_text(&self, text: &str) -> Result<(), char> {
+        let mut buffer = [0; 2];
+        for c in text.chars() {
+            let units = c.encode_utf16(&mut buffer);
+            self.post_key(0, true, Some(units));
+            self.post_key(0, false, Some(units));
+        }
+        Ok(())
+    }
+
+    fn mouse_move_to(&self, position: MousePosition) {
+        // Moves with a button held are drags: kCGEventLeftMouseDragged, kCGEventRightMouseDragged
+        // and kCGEventOtherMouseDragged.
+        let pressed = MacosBackend.query_pointer().button_pressed;
+        let (event_type, button) = match pressed.iter().next() {
+            Some(MouseButton::Left) => (6, 0),
+            Some(MouseButton::Right) => (7, 1),
+            Some(button) => (27, button.macos_button().unwrap_or(2)),
+            None => (MOUSE_MOVED, 0),
+        };
+        self.post_mouse(event_type, button, position);
+    }
+
+    fn mouse_move_by(&self, dx: i32, dy: i32) {
+        let (x, y) = MacosBackend.query_pointer().coords;
+        self.mouse_move_to((x.saturating_add(dx), y.saturating_add(dy)))
+    }
+
+    fn mouse_down(&self, button: MouseButton) {
+        self.send_button(button, true)
+    }
+
+    fn mouse_up(&self, button: MouseButton) {
+        self.send_button(button, false)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) {
+        // Positive wheel deltas scroll up and left, hence the negated deltas.
+        unsafe {
+            post(CGEventCreateScrollWheelEvent(
+                ptr::null(),
+                SCROLL_UNIT_LINE,
+                2,
+                dy.saturating_neg(),
+                dx.saturating_neg(),
+            ));
+        }
+    }
+}
+
+/// Posts and releases `event`, if it could be created.
+unsafe fn post(event: CGEventRef) {
+    if !event.is_null() {
+        CGEventPost(HID_EVENT_TAP, event);
+        CFRelease(event);
     }
 }
 
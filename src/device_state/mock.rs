@@ -0,0 +1,265 @@
+//! Scriptable backend for tests.
+
+use std::sync::{Arc, Mutex};
+use ScrollDelta;
+use {DeviceBackend, DeviceSimulate, DeviceState};
+use {Keycode, Monitor, MouseButton, MouseDelta, MousePosition, MouseState, PointerLocation};
+
+#[derive(Debug, Default)]
+struct MockState {
+    keys: Vec<Keycode>,
+    mouse: MouseState,
+    scroll: ScrollDelta,
+    motion: MouseDelta,
+    monitors: Vec<Monitor>,
+    window: Option<u64>,
+}
+
+/// In-memory backend whose keys and mouse state are set by the test instead of read from a
+/// device. Clones share the same state, so a clone can be handed to a `DeviceState` or to the
+/// event loop while the test keeps scripting the original.
+///
+/// ```
+/// use device_query::{DeviceQuery, DeviceState, Keycode, MockDeviceState};
+///
+/// let mock = MockDeviceState::new();
+/// let device_state = DeviceState::from_backend(mock.clone());
+///
+/// mock.press(Keycode::A);
+/// mock.move_to((10, 20));
+/// assert_eq!(device_state.get_keys(), vec![Keycode::A]);
+/// assert_eq!(device_state.get_mouse().coords, (10, 20));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockDeviceState {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockDeviceState {
+    /// Creates a mock with no key or button pressed and the mouse at `(0, 0)`.
+    pub fn new() -> MockDeviceState {
+        MockDeviceState::default()
+    }
+
+    /// Creates a `DeviceState` querying a clone of this mock.
+    pub fn device_state(&self) -> DeviceState {
+        DeviceState::from_backend(self.clone())
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut MockState) -> T) -> T {
+        let mut state = self.state.lock().expect("Couldn't lock mock state");
+        f(&mut state)
+    }
+
+    /// Presses `key`, if it isn't already pressed.
+    pub fn press(&self, key: Keycode) {
+        self.with_state(|state| {
+            if !state.keys.contains(&key) {
+                state.keys.push(key);
+            }
+        })
+    }
+
+    /// Releases `key`, if it is pressed.
+    pub fn release(&self, key: Keycode) {
+        self.with_state(|state| state.keys.retain(|pressed| *pressed != key))
+    }
+
+    /// Replaces the set of pressed keys.
+    pub fn set_keys(&self, keys: &[Keycode]) {
+        self.with_state(|state| state.keys = keys.to_vec())
+    }
+
+    /// Moves the mouse to `position`.
+    pub fn move_to(&self, position: MousePosition) {
+        self.with_state(|state| state.mouse.coords = position)
+    }
+
+    /// Presses mouse `button`.
+    pub fn press_button(&self, button: MouseButton) {
+        self.with_state(|state| {
+            state.mouse.button_pressed.insert(button);
+        })
+    }
+
+    /// Releases mouse `button`.
+    pub fn release_button(&self, button: MouseButton) {
+        self.with_state(|state| {
+            state.mouse.button_pressed.remove(button);
+        })
+    }
+
+    /// Replaces the whole mouse state.
+    pub fn set_mouse(&self, mouse: MouseState) {
+        self.with_state(|state| state.mouse = mouse)
+    }
+
+    /// Moves the mouse device by `(dx, dy)` without moving the pointer, like a pointer stopped by
+    /// the edge of the screen. The motion is reported by the next
+    /// [`query_motion`](DeviceBackend::query_motion), along with the motion of
+    /// [`mouse_move_by`](DeviceSimulate::mouse_move_by).
+    ///
+    /// ```
+    /// use device_query::{DeviceEvents, DeviceQuery, MockDeviceState, MockEventsHandler};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mock = MockDeviceState::new();
+    /// let handler = MockEventsHandler::new(mock.clone());
+    /// let motion = Arc::new(Mutex::new(vec![]));
+    /// let _guard = handler.on_mouse_motion({
+    ///     let motion = motion.clone();
+    ///     move |dx, dy| motion.lock().unwrap().push((dx, dy))
+    /// });
+    ///
+    /// mock.move_device_by(-5, 0);
+    /// mock.move_device_by(-3, 2);
+    /// handler.step();
+    /// handler.step();
+    /// assert_eq!(*motion.lock().unwrap(), [(-8, 2)]);
+    /// assert_eq!(mock.device_state().get_mouse().coords, (0, 0));
+    /// ```
+    pub fn move_device_by(&self, dx: i32, dy: i32) {
+        self.with_state(|state| {
+            state.motion = (
+                state.motion.0.saturating_add(dx),
+                state.motion.1.saturating_add(dy),
+            );
+        })
+    }
+
+    /// Replaces the monitors. The pointer is located on the first one containing it, always on
+    /// screen 0.
+    ///
+    /// ```
+    /// use device_query::{DeviceQuery, MockDeviceState, Monitor};
+    ///
+    /// let mock = MockDeviceState::new();
+    /// let primary = Monitor {
+    ///     name: Some(String::from("eDP-1")),
+    ///     screen: 0,
+    ///     position: (0, 0),
+    ///     size: (2560, 1600),
+    ///     scale: 2.0,
+    ///     primary: true,
+    /// };
+    /// let external = Monitor {
+    ///     name: Some(String::from("DP-1")),
+    ///     position: (2560, 0),
+    ///     size: (1920, 1080),
+    ///     scale: 1.0,
+    ///     primary: false,
+    ///     ..primary.clone()
+    /// };
+    /// mock.set_monitors(vec![primary, external]);
+    /// mock.set_window(Some(0x2a00007));
+    /// mock.move_to((3000, 500));
+    ///
+    /// let device_state = mock.device_state();
+    /// let location = device_state.get_pointer_location();
+    /// assert_eq!(location.coords, (3000, 500));
+    /// assert_eq!(location.monitor, Some(1));
+    /// assert_eq!(location.window, Some(0x2a00007));
+    /// assert_eq!(device_state.get_monitors()[1].name.as_deref(), Some("DP-1"));
+    /// ```
+    pub fn set_monitors(&self, monitors: Vec<Monitor>) {
+        self.with_state(|state| state.monitors = monitors)
+    }
+
+    /// Sets the window under the pointer, `None` by default.
+    pub fn set_window(&self, window: Option<u64>) {
+        self.with_state(|state| state.window = window)
+    }
+
+    /// Scrolls by `delta`, reported by the next [`query_scroll`](DeviceBackend::query_scroll).
+    ///
+    /// ```
+    /// use device_query::{DeviceEvents, MockDeviceState, MockEventsHandler, ScrollDelta};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mock = MockDeviceState::new();
+    /// let handler = MockEventsHandler::new(mock.clone());
+    /// let scrolled = Arc::new(Mutex::new(vec![]));
+    /// let _guard = handler.on_scroll({
+    ///     let scrolled = scrolled.clone();
+    ///     move |delta| scrolled.lock().unwrap().push(delta)
+    /// });
+    ///
+    /// mock.scroll_by(ScrollDelta { horizontal: 0, vertical: 30 });
+    /// mock.scroll_by(ScrollDelta { horizontal: 0, vertical: 30 });
+    /// handler.step();
+    /// handler.step();
+    /// assert_eq!(
+    ///     *scrolled.lock().unwrap(),
+    ///     [ScrollDelta { horizontal: 0, vertical: 60 }]
+    /// );
+    /// ```
+    pub fn scroll_by(&self, delta: ScrollDelta) {
+        self.with_state(|state| {
+            state.scroll.horizontal = state.scroll.horizontal.saturating_add(delta.horizontal);
+            state.scroll.vertical = state.scroll.vertical.saturating_add(delta.vertical);
+        })
+    }
+}
+
+impl DeviceBackend for MockDeviceState {
+    fn query_keymap(&self) -> Vec<Keycode> {
+        self.with_state(|state| state.keys.clone())
+    }
+
+    fn query_pointer(&self) -> MouseState {
+        self.with_state(|state| state.mouse.clone())
+    }
+
+    fn query_monitors(&self) -> Vec<Monitor> {
+        self.with_state(|state| state.monitors.clone())
+    }
+
+    fn query_pointer_location(&self) -> PointerLocation {
+        self.with_state(|state| PointerLocation {
+            window: state.window,
+            ..PointerLocation::locate(state.mouse.coords, 0, &state.monitors)
+        })
+    }
+
+    fn query_scroll(&self) -> ScrollDelta {
+        self.with_state(|state| std::mem::take(&mut state.scroll))
+    }
+
+    fn query_motion(&self) -> MouseDelta {
+        self.with_state(|state| std::mem::take(&mut state.motion))
+    }
+}
+
+impl DeviceSimulate for MockDeviceState {
+    fn key_down(&self, key: Keycode) {
+        self.press(key)
+    }
+
+    fn key_up(&self, key: Keycode) {
+        self.release(key)
+    }
+
+    fn mouse_move_to(&self, position: MousePosition) {
+        self.move_to(position)
+    }
+
+    fn mouse_move_by(&self, dx: i32, dy: i32) {
+        self.with_state(|state| {
+            let (x, y) = state.mouse.coords;
+            state.mouse.coords = (x.saturating_add(dx), y.saturating_add(dy));
+        });
+        self.move_device_by(dx, dy)
+    }
+
+    fn mouse_down(&self, button: MouseButton) {
+        self.press_button(button)
+    }
+
+    fn mouse_up(&self, button: MouseButton) {
+        self.release_button(button)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) {
+        self.scroll_by(ScrollDelta::from_notches(dx, dy))
+    }
+}
@@ -1,19 +1,106 @@
 //! DeviceState implementation.
 
+mod backend;
+#[cfg(feature = "testing")]
+mod mock;
+
+pub use self::backend::*;
+#[cfg(feature = "testing")]
+pub use self::mock::MockDeviceState;
+
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "linux")]
-pub use self::linux::DeviceState;
+pub use self::linux::{
+    EvdevBackend, InputSimulator, KeyTranslator, LinuxBackend, X11Backend, XInput2Backend,
+};
 
 #[cfg(target_os = "windows")]
 mod windows;
 #[cfg(target_os = "windows")]
-pub use self::windows::DeviceState;
+pub use self::windows::{InputSimulator, WindowsBackend};
 
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "macos")]
-pub use self::macos::DeviceState;
+pub use self::macos::{InputSimulator, MacosBackend};
+
+#[cfg(not(target_os = "linux"))]
+mod unsupported_translator;
+#[cfg(not(target_os = "linux"))]
+pub use self::unsupported_translator::KeyTranslator;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use {KeySet, Keycode, Monitor, MouseDelta, MouseState, PointerLocation, ScrollDelta, TimedEvent};
+
+#[derive(Clone)]
+/// Device state descriptor.
+///
+/// Queries are forwarded to a [`DeviceBackend`], which is the platform backend unless another
+/// one was given to [`DeviceState::from_backend`].
+///
+/// A `DeviceState` is `Send + Sync`, and clones share the same backend, so a single one can be
+/// queried from several threads.
+pub struct DeviceState {
+    backend: Arc<dyn DeviceBackend>,
+}
+
+/// `DeviceState` must stay shareable between threads.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DeviceState>();
+};
+
+impl DeviceState {
+    /// Creates a DeviceState querying the given backend.
+    pub fn from_backend<B: DeviceBackend + 'static>(backend: B) -> DeviceState {
+        DeviceState {
+            backend: Arc::new(backend),
+        }
+    }
+}
+
+impl DeviceBackend for DeviceState {
+    fn query_keymap(&self) -> Vec<Keycode> {
+        self.backend.query_keymap()
+    }
+
+    fn query_key_set(&self) -> KeySet {
+        self.backend.query_key_set()
+    }
+
+    fn query_pointer(&self) -> MouseState {
+        self.backend.query_pointer()
+    }
+
+    fn query_monitors(&self) -> Vec<Monitor> {
+        self.backend.query_monitors()
+    }
+
+    fn query_pointer_location(&self) -> PointerLocation {
+        self.backend.query_pointer_location()
+    }
+
+    fn query_scroll(&self) -> ScrollDelta {
+        self.backend.query_scroll()
+    }
+
+    fn query_motion(&self) -> MouseDelta {
+        self.backend.query_motion()
+    }
+
+    fn read_events(&self, timeout: Duration, events: &mut Vec<TimedEvent>) -> bool {
+        self.backend.read_events(timeout, events)
+    }
+}
+
+impl fmt::Debug for DeviceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeviceState").finish_non_exhaustive()
+    }
+}
 
 impl Default for DeviceState {
     fn default() -> Self {
@@ -0,0 +1,28 @@
+//! Key translation on platforms without a layout-aware implementation yet.
+
+use error::DeviceQueryError;
+use keymap::Keycode;
+
+/// Translates key events into text with the current keyboard layout. Only implemented on Linux,
+/// where it uses XKB: on this platform [`new`](Self::new) always fails.
+#[derive(Debug)]
+pub struct KeyTranslator {
+    _private: (),
+}
+
+impl KeyTranslator {
+    /// Fails with [`DeviceQueryError::BackendUnsupported`].
+    pub fn new() -> Result<KeyTranslator, DeviceQueryError> {
+        Err(DeviceQueryError::BackendUnsupported(String::from(
+            "layout-aware key translation is only available on Linux",
+        )))
+    }
+
+    /// Translates a key press into the text it types, if any.
+    pub fn key_down(&mut self, _key: Keycode) -> Option<String> {
+        None
+    }
+
+    /// Records a key release.
+    pub fn key_up(&mut self, _key: Keycode) {}
+}
@@ -1,19 +1,41 @@
 extern crate windows;
 
-use keymap::Keycode;
-use mouse_state::MouseState;
-use self::windows::Win32::Foundation::POINT;
+use self::windows::Win32::Foundation::{BOOL, LPARAM, POINT, RECT, TRUE};
+use self::windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+};
+use self::windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 use self::windows::Win32::UI::Input::KeyboardAndMouse;
-use self::windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VIRTUAL_KEY};
-use self::windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
-
+use self::windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT,
+    KEYBD_EVENT_FLAGS, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE,
+    MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN,
+    MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_WHEEL,
+    MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT, MOUSE_EVENT_FLAGS, VIRTUAL_KEY,
+};
+use self::windows::Win32::UI::WindowsAndMessaging::{
+    GetAncestor, GetCursorPos, SetCursorPos, WindowFromPoint, GA_ROOT, MONITORINFOF_PRIMARY,
+    USER_DEFAULT_SCREEN_DPI, WHEEL_DELTA, XBUTTON1, XBUTTON2,
+};
+use device_simulate::DeviceSimulate;
+use device_state::{DeviceBackend, DeviceState};
+use error::DeviceQueryError;
+use key_set::KeySet;
+use keymap::Keycode;
+use mouse_state::{MouseButton, MousePosition, MouseState};
+use screen::{Monitor, PointerLocation};
+use std::mem;
 
+/// Backend querying the Win32 API.
+///
+/// Coordinates and monitor sizes are in physical pixels if the process is DPI aware, and scaled
+/// by Windows otherwise.
 #[derive(Debug, Clone)]
-pub struct DeviceState;
+pub struct WindowsBackend;
 
 impl DeviceState {
     pub fn new() -> Self {
-        Self {}
+        Self::from_backend(WindowsBackend)
     }
 
     // Adding because Linux and OSX supports this where `new` can panic.
@@ -21,13 +43,15 @@ impl DeviceState {
         Some(Self::new())
     }
 
-    pub(crate) fn query_pointer(&self) -> MouseState {
+    /// Create a new DeviceState. Never fails on Windows.
+    pub fn try_new() -> Result<Self, DeviceQueryError> {
+        Ok(Self::new())
+    }
+}
+
+impl DeviceBackend for WindowsBackend {
+    fn query_pointer(&self) -> MouseState {
         let point = &mut POINT { x: 0, y: 0 };
-        let button1pressed;
-        let button2pressed;
-        let button3pressed;
-        let button4pressed;
-        let button5pressed;
         let coords;
         unsafe {
             coords = if GetCursorPos(point).is_ok() {
@@ -35,53 +59,110 @@ impl DeviceState {
             } else {
                 (0, 0)
             };
-            button1pressed =
-                GetAsyncKeyState(KeyboardAndMouse::VK_LBUTTON.0 as i32) as u32 & 0x8000 != 0;
-            button2pressed =
-                GetAsyncKeyState(KeyboardAndMouse::VK_RBUTTON.0 as i32) as u32 & 0x8000 != 0;
-            button3pressed =
-                GetAsyncKeyState(KeyboardAndMouse::VK_MBUTTON.0 as i32) as u32 & 0x8000 != 0;
-            button4pressed =
-                GetAsyncKeyState(KeyboardAndMouse::VK_XBUTTON1.0 as i32) as u32 & 0x8000 != 0;
-            button5pressed =
-                GetAsyncKeyState(KeyboardAndMouse::VK_XBUTTON2.0 as i32) as u32 & 0x8000 != 0;
         }
+        let named_buttons = [
+            MouseButton::Left,
+            MouseButton::Right,
+            MouseButton::Middle,
+            MouseButton::Back,
+            MouseButton::Forward,
+        ];
+        let button_pressed = named_buttons
+            .iter()
+            .copied()
+            .filter(|button| {
+                button
+                    .windows_virtual_key()
+                    .is_some_and(|virtual_key| unsafe {
+                        GetAsyncKeyState(virtual_key as i32) as u32 & 0x8000 != 0
+                    })
+            })
+            .collect();
         MouseState {
             coords,
-            // button_pressed: vec![
-            //     false,
-            //     button1pressed,
-            //     button2pressed,
-            //     button3pressed,
-            //     button4pressed,
-            //     button5pressed,
-            // ],
-            button_pressed: [
-                button1pressed,
-                button2pressed,
-                button3pressed,
-                button4pressed,
-                button5pressed,
-            ],
+            button_pressed,
         }
     }
 
-    pub(crate) fn query_keymap(&self) -> Vec<Keycode> {
-        let mut keycodes = vec![];
-        let mut keymap = vec![];
+    fn query_monitors(&self) -> Vec<Monitor> {
+        let mut monitors: Vec<Monitor> = vec![];
         unsafe {
-            for key in 0..256 {
-                keymap.push(GetAsyncKeyState(key));
-            }
+            let _ = EnumDisplayMonitors(
+                HDC::default(),
+                None,
+                Some(push_monitor),
+                LPARAM(&mut monitors as *mut Vec<Monitor> as isize),
+            );
         }
-        for (ix, byte) in keymap.iter().enumerate() {
-            if *byte as u32 & 0x8000 != 0 {
-                if let Some(k) = self.win_key_to_keycode(ix as u16) {
-                    keycodes.push(k)
-                }
-            }
+        monitors
+    }
+
+    fn query_pointer_location(&self) -> PointerLocation {
+        let coords = self.query_pointer().coords;
+        let window = unsafe {
+            let (x, y) = coords;
+            GetAncestor(WindowFromPoint(POINT { x, y }), GA_ROOT)
+        };
+        PointerLocation {
+            window: Some(window.0 as usize as u64).filter(|window| *window != 0),
+            ..PointerLocation::locate(coords, 0, &self.query_monitors())
         }
-        keycodes
+    }
+
+    fn query_keymap(&self) -> Vec<Keycode> {
+        self.pressed_keys().collect()
+    }
+
+    fn query_key_set(&self) -> KeySet {
+        self.pressed_keys().collect()
+    }
+}
+
+/// `EnumDisplayMonitors` callback appending `monitor` to the `Vec<Monitor>` of `monitors`.
+unsafe extern "system" fn push_monitor(
+    monitor: HMONITOR,
+    _: HDC,
+    _: *mut RECT,
+    monitors: LPARAM,
+) -> BOOL {
+    let monitors = &mut *(monitors.0 as *mut Vec<Monitor>);
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = mem::size_of::<MONITORINFOEXW>() as u32;
+    if GetMonitorInfoW(monitor, &mut info.monitorInfo).as_bool() {
+        let rect = info.monitorInfo.rcMonitor;
+        let name_len = info
+            .szDevice
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(info.szDevice.len());
+        let (mut dpi_x, mut dpi_y) = (0, 0);
+        let scale = if GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_ok()
+        {
+            f64::from(dpi_x) / f64::from(USER_DEFAULT_SCREEN_DPI)
+        } else {
+            1.0
+        };
+        monitors.push(Monitor {
+            name: Some(String::from_utf16_lossy(&info.szDevice[..name_len])),
+            screen: 0,
+            position: (rect.left, rect.top),
+            size: (
+                (rect.right - rect.left).max(0) as u32,
+                (rect.bottom - rect.top).max(0) as u32,
+            ),
+            scale,
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        });
+    }
+    TRUE
+}
+
+impl WindowsBackend {
+    /// The keys currently pressed, in virtual key order.
+    fn pressed_keys(&self) -> impl Iterator<Item = Keycode> + '_ {
+        (0..256)
+            .filter(|key| unsafe { GetAsyncKeyState(*key) } as u32 & 0x8000 != 0)
+            .filter_map(move |key| self.win_key_to_keycode(key as u16))
     }
 
     fn win_key_to_keycode(&self, win_key: u16) -> Option<Keycode> {
@@ -157,6 +238,38 @@ impl DeviceState {
             KeyboardAndMouse::VK_OEM_COMMA => Some(Keycode::Comma),
             KeyboardAndMouse::VK_OEM_PERIOD => Some(Keycode::Dot),
             KeyboardAndMouse::VK_OEM_2 => Some(Keycode::Slash),
+            KeyboardAndMouse::VK_NUMLOCK => Some(Keycode::NumLock),
+            KeyboardAndMouse::VK_SCROLL => Some(Keycode::ScrollLock),
+            KeyboardAndMouse::VK_SNAPSHOT => Some(Keycode::PrintScreen),
+            KeyboardAndMouse::VK_PAUSE => Some(Keycode::Pause),
+            KeyboardAndMouse::VK_APPS => Some(Keycode::Menu),
+            KeyboardAndMouse::VK_F21 => Some(Keycode::F21),
+            KeyboardAndMouse::VK_F22 => Some(Keycode::F22),
+            KeyboardAndMouse::VK_F23 => Some(Keycode::F23),
+            KeyboardAndMouse::VK_F24 => Some(Keycode::F24),
+            KeyboardAndMouse::VK_VOLUME_UP => Some(Keycode::VolumeUp),
+            KeyboardAndMouse::VK_VOLUME_DOWN => Some(Keycode::VolumeDown),
+            KeyboardAndMouse::VK_VOLUME_MUTE => Some(Keycode::VolumeMute),
+            KeyboardAndMouse::VK_MEDIA_PLAY_PAUSE => Some(Keycode::MediaPlayPause),
+            KeyboardAndMouse::VK_MEDIA_STOP => Some(Keycode::MediaStop),
+            KeyboardAndMouse::VK_MEDIA_NEXT_TRACK => Some(Keycode::MediaNextTrack),
+            KeyboardAndMouse::VK_MEDIA_PREV_TRACK => Some(Keycode::MediaPrevTrack),
+            KeyboardAndMouse::VK_BROWSER_BACK => Some(Keycode::BrowserBack),
+            KeyboardAndMouse::VK_BROWSER_FORWARD => Some(Keycode::BrowserForward),
+            KeyboardAndMouse::VK_BROWSER_REFRESH => Some(Keycode::BrowserRefresh),
+            KeyboardAndMouse::VK_BROWSER_STOP => Some(Keycode::BrowserStop),
+            KeyboardAndMouse::VK_BROWSER_SEARCH => Some(Keycode::BrowserSearch),
+            KeyboardAndMouse::VK_BROWSER_FAVORITES => Some(Keycode::BrowserFavorites),
+            KeyboardAndMouse::VK_BROWSER_HOME => Some(Keycode::BrowserHome),
+            KeyboardAndMouse::VK_LAUNCH_MAIL => Some(Keycode::LaunchMail),
+            KeyboardAndMouse::VK_OEM_102 => Some(Keycode::IntlBackslash),
+            KeyboardAndMouse::VK_ABNT_C1 => Some(Keycode::IntlRo),
+            KeyboardAndMouse::VK_ABNT_C2 => Some(Keycode::NumpadComma),
+            KeyboardAndMouse::VK_HANGUL => Some(Keycode::Lang1),
+            KeyboardAndMouse::VK_HANJA => Some(Keycode::Lang2),
+            KeyboardAndMouse::VK_CONVERT => Some(Keycode::Convert),
+            KeyboardAndMouse::VK_NONCONVERT => Some(Keycode::NonConvert),
+            KeyboardAndMouse::VK_DBE_HIRAGANA => Some(Keycode::KanaMode),
 
             _ => None,
         };
@@ -203,6 +316,340 @@ impl DeviceState {
                 _ => None,
             }
         }
+        if keycode.is_none() && !is_ignored_win_key(VIRTUAL_KEY(win_key)) {
+            keycode = Some(Keycode::Unknown {
+                platform_code: win_key as u32,
+            });
+        }
         keycode
     }
 }
+
+/// Virtual keys that aren't reported as keys: mouse buttons, and the modifiers that are also
+/// reported with their side.
+fn is_ignored_win_key(win_key: VIRTUAL_KEY) -> bool {
+    matches!(
+        win_key,
+        KeyboardAndMouse::VK_LBUTTON
+            | KeyboardAndMouse::VK_RBUTTON
+            | KeyboardAndMouse::VK_MBUTTON
+            | KeyboardAndMouse::VK_XBUTTON1
+            | KeyboardAndMouse::VK_XBUTTON2
+            | KeyboardAndMouse::VK_SHIFT
+            | KeyboardAndMouse::VK_CONTROL
+            | KeyboardAndMouse::VK_MENU
+    )
+}
+
+/// Generates keyboard and mouse input through `SendInput`.
+#[derive(Debug, Clone)]
+pub struct InputSimulator;
+
+impl InputSimulator {
+    /// Creates a simulator, which always succeeds on Windows.
+    pub fn new() -> Result<InputSimulator, DeviceQueryError> {
+        Ok(InputSimulator)
+    }
+
+    fn send(&self, inputs: &[INPUT]) {
+        unsafe {
+            SendInput(inputs, mem::size_of::<INPUT>() as i32);
+        }
+    }
+
+    fn send_key(&self, key: Keycode, down: bool) {
+        let Some(win_key) = keycode_to_win_key(key) else {
+            return;
+        };
+        let mut flags = if down {
+            KEYBD_EVENT_FLAGS(0)
+        } else {
+            KEYEVENTF_KEYUP
+        };
+        if is_extended_key(key) {
+            flags |= KEYEVENTF_EXTENDEDKEY;
+        }
+        self.send(&[keyboard_input(win_key, 0, flags)]);
+    }
+
+    fn send_button(&self, button: MouseButton, down: bool) {
+        // Windows has no `Other` buttons.
+        let (flags, data) = match (button, down) {
+            (MouseButton::Left, true) => (MOUSEEVENTF_LEFTDOWN, 0),
+            (MouseButton::Left, false) => (MOUSEEVENTF_LEFTUP, 0),
+            (MouseButton::Right, true) => (MOUSEEVENTF_RIGHTDOWN, 0),
+            (MouseButton::Right, false) => (MOUSEEVENTF_RIGHTUP, 0),
+            (MouseButton::Middle, true) => (MOUSEEVENTF_MIDDLEDOWN, 0),
+            (MouseButton::Middle, false) => (MOUSEEVENTF_MIDDLEUP, 0),
+            (MouseButton::Back, true) => (MOUSEEVENTF_XDOWN, XBUTTON1),
+            (MouseButton::Back, false) => (MOUSEEVENTF_XUP, XBUTTON1),
+            (MouseButton::Forward, true) => (MOUSEEVENTF_XDOWN, XBUTTON2),
+            (MouseButton::Forward, false) => (MOUSEEVENTF_XUP, XBUTTON2),
+            (MouseButton::Other(_), _) => return,
+        };
+        self.send(&[mouse_input(flags, data as u32)]);
+    }
+}
+
+impl DeviceSimulate for InputSimulator {
+    fn key_down(&self, key: Keycode) {
+        self.send_key(key, true)
+    }
+
+    fn key_up(&self, key: Keycode) {
+        self.send_key(key, false)
+    }
+
+    /// Types `text` as unicode characters, independently of the keyboard layout. Never fails.
+    fn type_text(&self, text: &str) -> Result<(), char> {
+        let mut inputs = vec![];
+        for unit in text.encode_utf16() {
+            inputs.push(keyboard_input(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE));
+            inputs.push(keyboard_input(
+                VIRTUAL_KEY(0),
+                unit,
+                KEYEVENTF_UNICODE | KEYEVENTF_KEYUP,
+            ));
+        }
+        self.send(&inputs);
+        Ok(())
+    }
+
+    fn mouse_move_to(&self, (x, y): MousePosition) {
+        unsafe {
+            let _ = SetCursorPos(x, y);
+        }
+    }
+
+    fn mouse_move_by(&self, dx: i32, dy: i32) {
+        // Relative `SendInput` moves are subject to pointer acceleration, so the move is absolute.
+        let (x, y) = WindowsBackend.query_pointer().coords;
+        self.mouse_move_to((x.saturating_add(dx), y.saturating_add(dy)))
+    }
+
+    fn mouse_down(&self, button: MouseButton) {
+        self.send_button(button, true)
+    }
+
+    fn mouse_up(&self, button: MouseButton) {
+        self.send_button(button, false)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) {
+        // Positive wheel deltas scroll up, hence the negated vertical delta.
+        let mut inputs = vec![];
+        if dy != 0 {
+            let data = dy.saturating_neg().saturating_mul(WHEEL_DELTA as i32);
+            inputs.push(mouse_input(MOUSEEVENTF_WHEEL, data as u32));
+        }
+        if dx != 0 {
+            let data = dx.saturating_mul(WHEEL_DELTA as i32);
+            inputs.push(mouse_input(MOUSEEVENTF_HWHEEL, data as u32));
+        }
+        self.send(&inputs);
+    }
+}
+
+fn mouse_input(flags: MOUSE_EVENT_FLAGS, data: u32) -> INPUT {
+    INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx: 0,
+                dy: 0,
+                mouseData: data,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+fn keyboard_input(win_key: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: win_key,
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Keys sharing their virtual key with another key, told apart by the extended key flag.
+fn is_extended_key(key: Keycode) -> bool {
+    matches!(
+        key,
+        Keycode::Delete
+            | Keycode::Down
+            | Keycode::End
+            | Keycode::Home
+            | Keycode::Insert
+            | Keycode::LMeta
+            | Keycode::Left
+            | Keycode::NumpadDivide
+            | Keycode::PageDown
+            | Keycode::PageUp
+            | Keycode::RAlt
+            | Keycode::RControl
+            | Keycode::RMeta
+            | Keycode::Right
+            | Keycode::Up
+            | Keycode::NumpadEnter
+    )
+}
+
+fn keycode_to_win_key(key: Keycode) -> Option<VIRTUAL_KEY> {
+    let win_key = match key {
+        Keycode::F1 => KeyboardAndMouse::VK_F1,
+        Keycode::F2 => KeyboardAndMouse::VK_F2,
+        Keycode::F3 => KeyboardAndMouse::VK_F3,
+        Keycode::F4 => KeyboardAndMouse::VK_F4,
+        Keycode::F5 => KeyboardAndMouse::VK_F5,
+        Keycode::F6 => KeyboardAndMouse::VK_F6,
+        Keycode::F7 => KeyboardAndMouse::VK_F7,
+        Keycode::F8 => KeyboardAndMouse::VK_F8,
+        Keycode::F9 => KeyboardAndMouse::VK_F9,
+        Keycode::F10 => KeyboardAndMouse::VK_F10,
+        Keycode::F11 => KeyboardAndMouse::VK_F11,
+        Keycode::F12 => KeyboardAndMouse::VK_F12,
+        Keycode::F13 => KeyboardAndMouse::VK_F13,
+        Keycode::F14 => KeyboardAndMouse::VK_F14,
+        Keycode::F15 => KeyboardAndMouse::VK_F15,
+        Keycode::F16 => KeyboardAndMouse::VK_F16,
+        Keycode::F17 => KeyboardAndMouse::VK_F17,
+        Keycode::F18 => KeyboardAndMouse::VK_F18,
+        Keycode::F19 => KeyboardAndMouse::VK_F19,
+        Keycode::F20 => KeyboardAndMouse::VK_F20,
+        Keycode::Numpad0 => KeyboardAndMouse::VK_NUMPAD0,
+        Keycode::Numpad1 => KeyboardAndMouse::VK_NUMPAD1,
+        Keycode::Numpad2 => KeyboardAndMouse::VK_NUMPAD2,
+        Keycode::Numpad3 => KeyboardAndMouse::VK_NUMPAD3,
+        Keycode::Numpad4 => KeyboardAndMouse::VK_NUMPAD4,
+        Keycode::Numpad5 => KeyboardAndMouse::VK_NUMPAD5,
+        Keycode::Numpad6 => KeyboardAndMouse::VK_NUMPAD6,
+        Keycode::Numpad7 => KeyboardAndMouse::VK_NUMPAD7,
+        Keycode::Numpad8 => KeyboardAndMouse::VK_NUMPAD8,
+        Keycode::Numpad9 => KeyboardAndMouse::VK_NUMPAD9,
+        Keycode::NumpadAdd => KeyboardAndMouse::VK_ADD,
+        Keycode::NumpadSubtract => KeyboardAndMouse::VK_SUBTRACT,
+        Keycode::NumpadDivide => KeyboardAndMouse::VK_DIVIDE,
+        Keycode::NumpadMultiply => KeyboardAndMouse::VK_MULTIPLY,
+        Keycode::NumpadEquals => KeyboardAndMouse::VK_OEM_NEC_EQUAL,
+        Keycode::NumpadDecimal => KeyboardAndMouse::VK_DECIMAL,
+        Keycode::Space => KeyboardAndMouse::VK_SPACE,
+        Keycode::LControl => KeyboardAndMouse::VK_LCONTROL,
+        Keycode::RControl => KeyboardAndMouse::VK_RCONTROL,
+        Keycode::LShift => KeyboardAndMouse::VK_LSHIFT,
+        Keycode::RShift => KeyboardAndMouse::VK_RSHIFT,
+        Keycode::LAlt => KeyboardAndMouse::VK_LMENU,
+        Keycode::RAlt => KeyboardAndMouse::VK_RMENU,
+        Keycode::LMeta => KeyboardAndMouse::VK_LWIN,
+        Keycode::RMeta => KeyboardAndMouse::VK_RWIN,
+        Keycode::Enter => KeyboardAndMouse::VK_RETURN,
+        Keycode::Escape => KeyboardAndMouse::VK_ESCAPE,
+        Keycode::Up => KeyboardAndMouse::VK_UP,
+        Keycode::Down => KeyboardAndMouse::VK_DOWN,
+        Keycode::Left => KeyboardAndMouse::VK_LEFT,
+        Keycode::Right => KeyboardAndMouse::VK_RIGHT,
+        Keycode::Backspace => KeyboardAndMouse::VK_BACK,
+        Keycode::CapsLock => KeyboardAndMouse::VK_CAPITAL,
+        Keycode::Tab => KeyboardAndMouse::VK_TAB,
+        Keycode::Home => KeyboardAndMouse::VK_HOME,
+        Keycode::End => KeyboardAndMouse::VK_END,
+        Keycode::PageUp => KeyboardAndMouse::VK_PRIOR,
+        Keycode::PageDown => KeyboardAndMouse::VK_NEXT,
+        Keycode::Insert => KeyboardAndMouse::VK_INSERT,
+        Keycode::Delete => KeyboardAndMouse::VK_DELETE,
+        Keycode::Grave => KeyboardAndMouse::VK_OEM_3,
+        Keycode::Minus => KeyboardAndMouse::VK_OEM_MINUS,
+        Keycode::Equal => KeyboardAndMouse::VK_OEM_PLUS,
+        Keycode::LeftBracket => KeyboardAndMouse::VK_OEM_4,
+        Keycode::RightBracket => KeyboardAndMouse::VK_OEM_6,
+        Keycode::BackSlash => KeyboardAndMouse::VK_OEM_5,
+        Keycode::Semicolon => KeyboardAndMouse::VK_OEM_1,
+        Keycode::Apostrophe => KeyboardAndMouse::VK_OEM_7,
+        Keycode::Comma => KeyboardAndMouse::VK_OEM_COMMA,
+        Keycode::Dot => KeyboardAndMouse::VK_OEM_PERIOD,
+        Keycode::Slash => KeyboardAndMouse::VK_OEM_2,
+        Keycode::NumpadEnter => KeyboardAndMouse::VK_RETURN,
+        Keycode::NumLock => KeyboardAndMouse::VK_NUMLOCK,
+        Keycode::ScrollLock => KeyboardAndMouse::VK_SCROLL,
+        Keycode::PrintScreen => KeyboardAndMouse::VK_SNAPSHOT,
+        Keycode::Pause => KeyboardAndMouse::VK_PAUSE,
+        Keycode::Menu => KeyboardAndMouse::VK_APPS,
+        Keycode::F21 => KeyboardAndMouse::VK_F21,
+        Keycode::F22 => KeyboardAndMouse::VK_F22,
+        Keycode::F23 => KeyboardAndMouse::VK_F23,
+        Keycode::F24 => KeyboardAndMouse::VK_F24,
+        Keycode::VolumeUp => KeyboardAndMouse::VK_VOLUME_UP,
+        Keycode::VolumeDown => KeyboardAndMouse::VK_VOLUME_DOWN,
+        Keycode::VolumeMute => KeyboardAndMouse::VK_VOLUME_MUTE,
+        Keycode::MediaPlayPause => KeyboardAndMouse::VK_MEDIA_PLAY_PAUSE,
+        Keycode::MediaStop => KeyboardAndMouse::VK_MEDIA_STOP,
+        Keycode::MediaNextTrack => KeyboardAndMouse::VK_MEDIA_NEXT_TRACK,
+        Keycode::MediaPrevTrack => KeyboardAndMouse::VK_MEDIA_PREV_TRACK,
+        Keycode::BrowserBack => KeyboardAndMouse::VK_BROWSER_BACK,
+        Keycode::BrowserForward => KeyboardAndMouse::VK_BROWSER_FORWARD,
+        Keycode::BrowserRefresh => KeyboardAndMouse::VK_BROWSER_REFRESH,
+        Keycode::BrowserStop => KeyboardAndMouse::VK_BROWSER_STOP,
+        Keycode::BrowserSearch => KeyboardAndMouse::VK_BROWSER_SEARCH,
+        Keycode::BrowserFavorites => KeyboardAndMouse::VK_BROWSER_FAVORITES,
+        Keycode::BrowserHome => KeyboardAndMouse::VK_BROWSER_HOME,
+        Keycode::LaunchMail => KeyboardAndMouse::VK_LAUNCH_MAIL,
+        Keycode::IntlBackslash => KeyboardAndMouse::VK_OEM_102,
+        Keycode::IntlRo => KeyboardAndMouse::VK_ABNT_C1,
+        Keycode::NumpadComma => KeyboardAndMouse::VK_ABNT_C2,
+        Keycode::Lang1 => KeyboardAndMouse::VK_HANGUL,
+        Keycode::Lang2 => KeyboardAndMouse::VK_HANJA,
+        Keycode::Convert => KeyboardAndMouse::VK_CONVERT,
+        Keycode::NonConvert => KeyboardAndMouse::VK_NONCONVERT,
+        Keycode::KanaMode => KeyboardAndMouse::VK_DBE_HIRAGANA,
+        Keycode::Key0 => VIRTUAL_KEY(b'0' as u16),
+        Keycode::Key1 => VIRTUAL_KEY(b'1' as u16),
+        Keycode::Key2 => VIRTUAL_KEY(b'2' as u16),
+        Keycode::Key3 => VIRTUAL_KEY(b'3' as u16),
+        Keycode::Key4 => VIRTUAL_KEY(b'4' as u16),
+        Keycode::Key5 => VIRTUAL_KEY(b'5' as u16),
+        Keycode::Key6 => VIRTUAL_KEY(b'6' as u16),
+        Keycode::Key7 => VIRTUAL_KEY(b'7' as u16),
+        Keycode::Key8 => VIRTUAL_KEY(b'8' as u16),
+        Keycode::Key9 => VIRTUAL_KEY(b'9' as u16),
+        Keycode::A => VIRTUAL_KEY(b'A' as u16),
+        Keycode::B => VIRTUAL_KEY(b'B' as u16),
+        Keycode::C => VIRTUAL_KEY(b'C' as u16),
+        Keycode::D => VIRTUAL_KEY(b'D' as u16),
+        Keycode::E => VIRTUAL_KEY(b'E' as u16),
+        Keycode::F => VIRTUAL_KEY(b'F' as u16),
+        Keycode::G => VIRTUAL_KEY(b'G' as u16),
+        Keycode::H => VIRTUAL_KEY(b'H' as u16),
+        Keycode::I => VIRTUAL_KEY(b'I' as u16),
+        Keycode::J => VIRTUAL_KEY(b'J' as u16),
+        Keycode::K => VIRTUAL_KEY(b'K' as u16),
+        Keycode::L => VIRTUAL_KEY(b'L' as u16),
+        Keycode::M => VIRTUAL_KEY(b'M' as u16),
+        Keycode::N => VIRTUAL_KEY(b'N' as u16),
+        Keycode::O => VIRTUAL_KEY(b'O' as u16),
+        Keycode::P => VIRTUAL_KEY(b'P' as u16),
+        Keycode::Q => VIRTUAL_KEY(b'Q' as u16),
+        Keycode::R => VIRTUAL_KEY(b'R' as u16),
+        Keycode::S => VIRTUAL_KEY(b'S' as u16),
+        Keycode::T => VIRTUAL_KEY(b'T' as u16),
+        Keycode::U => VIRTUAL_KEY(b'U' as u16),
+        Keycode::V => VIRTUAL_KEY(b'V' as u16),
+        Keycode::W => VIRTUAL_KEY(b'W' as u16),
+        Keycode::X => VIRTUAL_KEY(b'X' as u16),
+        Keycode::Y => VIRTUAL_KEY(b'Y' as u16),
+        Keycode::Z => VIRTUAL_KEY(b'Z' as u16),
+        Keycode::Unknown { platform_code } if platform_code <= 0xff => {
+            VIRTUAL_KEY(platform_code as u16)
+        }
+        _ => return None,
+    };
+    Some(win_key)
+}
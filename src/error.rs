@@ -0,0 +1,40 @@
+//! Errors reported when creating device states and event handlers.
+
+use std::error::Error;
+use std::fmt;
+
+/// Why a `DeviceState`, a `DeviceEventsHandler` or an `InputSimulator` couldn't be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeviceQueryError {
+    /// No X display could be opened.
+    DisplayUnavailable,
+    /// The process isn't allowed to read input: Accessibility permissions are missing on macOS,
+    /// or input devices can't be opened on Linux.
+    PermissionDenied,
+    /// The event loop was already started by another `DeviceEventsHandler`.
+    EventLoopAlreadyRunning,
+    /// The backend can't run on this system, e.g. because a library or an X extension is
+    /// missing. The string describes what is missing.
+    BackendUnsupported(String),
+    /// A lock was poisoned by a thread that panicked while holding it.
+    LockPoisoned,
+}
+
+impl fmt::Display for DeviceQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceQueryError::DisplayUnavailable => write!(f, "could not connect to a X display"),
+            DeviceQueryError::PermissionDenied => write!(f, "permission to read input denied"),
+            DeviceQueryError::EventLoopAlreadyRunning => {
+                write!(f, "the event loop is already running")
+            }
+            DeviceQueryError::BackendUnsupported(reason) => {
+                write!(f, "backend unsupported: {}", reason)
+            }
+            DeviceQueryError::LockPoisoned => write!(f, "a lock was poisoned"),
+        }
+    }
+}
+
+impl Error for DeviceQueryError {}
@@ -0,0 +1,323 @@
+//! Set of keycodes stored as a bitset.
+
+use std::fmt;
+use std::iter::FromIterator;
+use Keycode;
+
+/// Number of `Keycode::Unknown` platform codes a set can hold, enough for the kernel key codes
+/// and the Windows and macOS virtual key codes.
+const UNKNOWN_CODES: usize = 0x300;
+
+/// Number of 64 bit words holding one bit per keycode, then one bit per unknown platform code.
+const WORDS: usize = (Keycode::ALL.len() + UNKNOWN_CODES).div_ceil(64);
+
+// `Keycode::ALL` must list every keycode at its index.
+const _: () = {
+    let mut index = 0;
+    while index < Keycode::ALL.len() {
+        assert!(matches!(Keycode::ALL[index].index(), Some(i) if i == index));
+        index += 1;
+    }
+};
+
+/// A set of keys, with one bit per [`Keycode`]. Unlike a `Vec<Keycode>`, it is `Copy` and
+/// never allocates, and its keys are always in `Keycode` declaration order, followed by the
+/// `Unknown` keys in platform code order.
+///
+/// `Unknown` keys are held for platform codes below `0x300`, which covers every code the
+/// backends report; larger codes are never inserted.
+///
+/// ```
+/// use device_query::{KeySet, Keycode};
+///
+/// let previous: KeySet = vec![Keycode::LShift, Keycode::A].into_iter().collect();
+/// let current: KeySet = vec![Keycode::LShift, Keycode::B].into_iter().collect();
+/// assert!(current.contains(Keycode::B));
+/// assert_eq!(current.difference(&previous).iter().collect::<Vec<_>>(), [Keycode::B]);
+/// assert_eq!(previous.difference(&current).iter().collect::<Vec<_>>(), [Keycode::A]);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct KeySet {
+    bits: [u64; WORDS],
+}
+
+impl KeySet {
+    /// Creates an empty set.
+    pub const fn new() -> KeySet {
+        KeySet { bits: [0; WORDS] }
+    }
+
+    fn position(key: Keycode) -> Option<(usize, u64)> {
+        let index = match key {
+            Keycode::Unknown { platform_code } if (platform_code as usize) < UNKNOWN_CODES => {
+                Keycode::ALL.len() + platform_code as usize
+            }
+            key => key.index()?,
+        };
+        Some((index / 64, 1 << (index % 64)))
+    }
+
+    /// Adds `key`. Returns whether it wasn't already in the set, and could be inserted.
+    pub fn insert(&mut self, key: Keycode) -> bool {
+        let Some((word, bit)) = Self::position(key) else {
+            return false;
+        };
+        let inserted = self.bits[word] & bit == 0;
+        self.bits[word] |= bit;
+        inserted
+    }
+
+    /// Removes `key`. Returns whether it was in the set.
+    pub fn remove(&mut self, key: Keycode) -> bool {
+        let Some((word, bit)) = Self::position(key) else {
+            return false;
+        };
+        let removed = self.bits[word] & bit != 0;
+        self.bits[word] &= !bit;
+        removed
+    }
+
+    /// Whether `key` is in the set.
+    pub fn contains(&self, key: Keycode) -> bool {
+        Self::position(key).is_some_and(|(word, bit)| self.bits[word] & bit != 0)
+    }
+
+    /// Number of keys in the set.
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// Removes every key.
+    pub fn clear(&mut self) {
+        self.bits = [0; WORDS];
+    }
+
+    /// The keys in `self` but not in `other`.
+    pub fn difference(&self, other: &KeySet) -> KeySet {
+        let mut difference = *self;
+        for (word, other_word) in difference.bits.iter_mut().zip(other.bits.iter()) {
+            *word &= !other_word;
+        }
+        difference
+    }
+
+    /// The keys in `self`, `other` or both.
+    pub fn union(&self, other: &KeySet) -> KeySet {
+        let mut union = *self;
+        for (word, other_word) in union.bits.iter_mut().zip(other.bits.iter()) {
+            *word |= other_word;
+        }
+        union
+    }
+
+    /// The keys in both `self` and `other`.
+    pub fn intersection(&self, other: &KeySet) -> KeySet {
+        let mut intersection = *self;
+        for (word, other_word) in intersection.bits.iter_mut().zip(other.bits.iter()) {
+            *word &= other_word;
+        }
+        intersection
+    }
+
+    /// Iterates over the keys, in `Keycode` declaration order then `Unknown` platform code order.
+    pub fn iter(&self) -> Iter {
+        Iter {
+            bits: self.bits,
+            word: 0,
+        }
+    }
+}
+
+/// Iterator over the keys of a [`KeySet`].
+#[derive(Debug, Clone)]
+pub struct Iter {
+    bits: [u64; WORDS],
+    word: usize,
+}
+
+impl Iterator for Iter {
+    type Item = Keycode;
+
+    fn next(&mut self) -> Option<Keycode> {
+        while self.word < WORDS {
+            let bits = &mut self.bits[self.word];
+            if *bits == 0 {
+                self.word += 1;
+                continue;
+            }
+            let bit = bits.trailing_zeros() as usize;
+            // Clears the lowest set bit.
+            *bits &= *bits - 1;
+            let index = self.word * 64 + bit;
+            return Some(match Keycode::ALL.get(index) {
+                Some(key) => *key,
+                None => Keycode::Unknown {
+                    platform_code: (index - Keycode::ALL.len()) as u32,
+                },
+            });
+        }
+        None
+    }
+}
+
+impl IntoIterator for KeySet {
+    type Item = Keycode;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &KeySet {
+    type Item = Keycode;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Keycode> for KeySet {
+    fn from_iter<I: IntoIterator<Item = Keycode>>(iter: I) -> KeySet {
+        let mut set = KeySet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Keycode> for KeySet {
+    fn extend<I: IntoIterator<Item = Keycode>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl fmt::Debug for KeySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unknown(platform_code: u32) -> Keycode {
+        Keycode::Unknown { platform_code }
+    }
+
+    fn set(keys: &[Keycode]) -> KeySet {
+        keys.iter().copied().collect()
+    }
+
+    #[test]
+    fn insert_and_remove_report_changes() {
+        let mut keys = KeySet::new();
+        assert!(keys.is_empty());
+        assert!(keys.insert(Keycode::A));
+        assert!(!keys.insert(Keycode::A));
+        assert!(keys.insert(unknown(0x1d3)));
+        assert!(!keys.insert(unknown(0x1d3)));
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.is_empty());
+        assert!(keys.contains(Keycode::A));
+        assert!(!keys.contains(Keycode::B));
+
+        assert!(keys.remove(Keycode::A));
+        assert!(!keys.remove(Keycode::A));
+        assert!(!keys.remove(Keycode::B));
+        assert_eq!(keys.len(), 1);
+        assert!(keys.remove(unknown(0x1d3)));
+        assert!(keys.is_empty());
+        assert_eq!(keys, KeySet::new());
+    }
+
+    #[test]
+    fn every_key_is_held() {
+        let mut keys = KeySet::new();
+        for key in Keycode::ALL {
+            assert!(keys.insert(key));
+        }
+        for code in 0..UNKNOWN_CODES as u32 {
+            assert!(keys.insert(unknown(code)));
+        }
+        assert_eq!(keys.len(), Keycode::ALL.len() + UNKNOWN_CODES);
+        keys.clear();
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn large_platform_codes_are_never_held() {
+        let mut keys = KeySet::new();
+        for code in [UNKNOWN_CODES as u32, 0x1000, u32::MAX] {
+            assert!(!keys.insert(unknown(code)));
+            assert!(!keys.contains(unknown(code)));
+            assert!(!keys.remove(unknown(code)));
+        }
+        assert!(keys.is_empty());
+        assert!(keys.insert(unknown(UNKNOWN_CODES as u32 - 1)));
+        assert_eq!(keys.iter().collect::<Vec<_>>(), [unknown(0x2ff)]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let left = set(&[Keycode::A, Keycode::B, unknown(1), unknown(0x200)]);
+        let right = set(&[Keycode::B, Keycode::C, unknown(0x200), unknown(0x2ff)]);
+        assert_eq!(
+            left.union(&right),
+            set(&[
+                Keycode::A,
+                Keycode::B,
+                Keycode::C,
+                unknown(1),
+                unknown(0x200),
+                unknown(0x2ff),
+            ])
+        );
+        assert_eq!(
+            left.intersection(&right),
+            set(&[Keycode::B, unknown(0x200)])
+        );
+        assert_eq!(left.difference(&right), set(&[Keycode::A, unknown(1)]));
+        assert_eq!(right.difference(&left), set(&[Keycode::C, unknown(0x2ff)]));
+        assert!(left.difference(&left).is_empty());
+        assert_eq!(left.union(&KeySet::new()), left);
+        assert!(left.intersection(&KeySet::new()).is_empty());
+    }
+
+    #[test]
+    fn keys_iterate_in_declaration_then_platform_code_order() {
+        let keys = set(&[
+            unknown(0x2ff),
+            Keycode::LShift,
+            unknown(0),
+            Keycode::A,
+            unknown(0x40),
+            Keycode::Key0,
+            unknown(0x3f),
+        ]);
+        let expected = [
+            Keycode::Key0,
+            Keycode::A,
+            Keycode::LShift,
+            unknown(0),
+            unknown(0x3f),
+            unknown(0x40),
+            unknown(0x2ff),
+        ];
+        assert_eq!(keys.iter().collect::<Vec<_>>(), expected);
+        assert_eq!((&keys).into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(keys.into_iter().count(), expected.len());
+        assert_eq!(format!("{:?}", set(&[Keycode::B, Keycode::A])), "{A, B}");
+    }
+}
@@ -3,10 +3,70 @@
 use std::fmt;
 use std::str::FromStr;
 
-/// A list of supported keys that we can query from the OS. Outside of mod.
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-#[allow(missing_docs)]
-pub enum Keycode {
+/// Declares `Keycode` with the listed variants followed by `Unknown`, and generates from the
+/// list `Keycode::ALL`, `Keycode::index` and `FromStr`, which parses the name of each variant.
+macro_rules! keycodes {
+    ($($(#[$attr:meta])* $key:ident,)*) => {
+        /// A list of supported keys that we can query from the OS. Outside of mod.
+        #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+        #[allow(missing_docs)]
+        pub enum Keycode {
+            $($(#[$attr])* $key,)*
+            /// A key without a variant of its own, identified by its platform key code: the kernel
+            /// key code on Linux and the virtual key code on Windows and macOS.
+            ///
+            /// ```
+            /// use device_query::Keycode;
+            ///
+            /// let key = Keycode::Unknown { platform_code: 0x1d3 };
+            /// assert_eq!(key.to_string(), "Unknown(0x1d3)");
+            /// assert_eq!("Unknown(0x1d3)".parse::<Keycode>(), Ok(key));
+            /// ```
+            Unknown { platform_code: u32 },
+        }
+
+        /// The position of each keycode in `Keycode::ALL`.
+        enum KeyIndex {
+            $($key,)*
+        }
+
+        /// The number of keycodes but `Unknown`.
+        const KEY_COUNT: usize = [$(stringify!($key),)*].len();
+
+        impl Keycode {
+            /// Every keycode but `Unknown`, in declaration order.
+            ///
+            /// ```
+            /// use device_query::Keycode;
+            ///
+            /// for key in Keycode::ALL.iter() {
+            ///     assert_eq!(key.to_string().parse::<Keycode>(), Ok(*key));
+            /// }
+            /// ```
+            pub const ALL: [Keycode; KEY_COUNT] = [$(Keycode::$key,)*];
+
+            /// The position of this keycode in `Keycode::ALL`, or `None` for `Unknown`.
+            pub(crate) const fn index(self) -> Option<usize> {
+                Some(match self {
+                    $(Keycode::$key => KeyIndex::$key as usize,)*
+                    Keycode::Unknown { .. } => return None,
+                })
+            }
+        }
+
+        impl FromStr for Keycode {
+            type Err = String;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($key) => Ok(Keycode::$key),)*
+                    s => parse_unknown(s).ok_or_else(|| String::from("failed to parse keycode")),
+                }
+            }
+        }
+    };
+}
+
+keycodes! {
     Key0,
     Key1,
     Key2,
@@ -91,7 +151,6 @@ pub enum Keycode {
     Insert,
     Delete,
 
-    // Numpad keys which have not been implemented: NumpadSeparator NumLock
     Numpad0,
     Numpad1,
     Numpad2,
@@ -123,130 +182,279 @@ pub enum Keycode {
     Comma,
     Dot,
     Slash,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+    /// The context menu key.
+    Menu,
+    F21,
+    F22,
+    F23,
+    F24,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    MediaPlayPause,
+    MediaStop,
+    MediaNextTrack,
+    MediaPrevTrack,
+    BrowserBack,
+    BrowserForward,
+    BrowserRefresh,
+    BrowserStop,
+    BrowserSearch,
+    BrowserFavorites,
+    BrowserHome,
+    LaunchMail,
+    /// The key between left shift and Z on ISO keyboards.
+    IntlBackslash,
+    /// The Ro key, left of right shift on Japanese keyboards.
+    IntlRo,
+    /// The Yen key, left of backspace on Japanese keyboards.
+    IntlYen,
+    NumpadComma,
+    /// Hangul/English toggle on Korean keyboards, Kana on Apple Japanese keyboards.
+    Lang1,
+    /// Hanja conversion on Korean keyboards, Eisu on Apple Japanese keyboards.
+    Lang2,
+    /// Henkan on Japanese keyboards.
+    Convert,
+    /// Muhenkan on Japanese keyboards.
+    NonConvert,
+    /// Katakana/Hiragana/Romaji toggle on Japanese keyboards.
+    KanaMode,
+    Katakana,
+    Hiragana,
+    ZenkakuHankaku,
 }
 
-impl FromStr for Keycode {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Key0" => Ok(Self::Key0),
-            "Key1" => Ok(Self::Key1),
-            "Key2" => Ok(Self::Key2),
-            "Key3" => Ok(Self::Key3),
-            "Key4" => Ok(Self::Key4),
-            "Key5" => Ok(Self::Key5),
-            "Key6" => Ok(Self::Key6),
-            "Key7" => Ok(Self::Key7),
-            "Key8" => Ok(Self::Key8),
-            "Key9" => Ok(Self::Key9),
-            "A" => Ok(Self::A),
-            "B" => Ok(Self::B),
-            "C" => Ok(Self::C),
-            "D" => Ok(Self::D),
-            "E" => Ok(Self::E),
-            "F" => Ok(Self::F),
-            "G" => Ok(Self::G),
-            "H" => Ok(Self::H),
-            "I" => Ok(Self::I),
-            "J" => Ok(Self::J),
-            "K" => Ok(Self::K),
-            "L" => Ok(Self::L),
-            "M" => Ok(Self::M),
-            "N" => Ok(Self::N),
-            "O" => Ok(Self::O),
-            "P" => Ok(Self::P),
-            "Q" => Ok(Self::Q),
-            "R" => Ok(Self::R),
-            "S" => Ok(Self::S),
-            "T" => Ok(Self::T),
-            "U" => Ok(Self::U),
-            "V" => Ok(Self::V),
-            "W" => Ok(Self::W),
-            "X" => Ok(Self::X),
-            "Y" => Ok(Self::Y),
-            "Z" => Ok(Self::Z),
-            "F1" => Ok(Self::F1),
-            "F2" => Ok(Self::F2),
-            "F3" => Ok(Self::F3),
-            "F4" => Ok(Self::F4),
-            "F5" => Ok(Self::F5),
-            "F6" => Ok(Self::F6),
-            "F7" => Ok(Self::F7),
-            "F8" => Ok(Self::F8),
-            "F9" => Ok(Self::F9),
-            "F10" => Ok(Self::F10),
-            "F11" => Ok(Self::F11),
-            "F12" => Ok(Self::F12),
-            "F13" => Ok(Self::F13),
-            "F14" => Ok(Self::F14),
-            "F15" => Ok(Self::F15),
-            "F16" => Ok(Self::F16),
-            "F17" => Ok(Self::F17),
-            "F18" => Ok(Self::F18),
-            "F19" => Ok(Self::F19),
-            "F20" => Ok(Self::F20),
-            "Escape" => Ok(Self::Escape),
-            "Space" => Ok(Self::Space),
-            "LControl" => Ok(Self::LControl),
-            "RControl" => Ok(Self::RControl),
-            "LShift" => Ok(Self::LShift),
-            "RShift" => Ok(Self::RShift),
-            "LAlt" => Ok(Self::LAlt),
-            "RAlt" => Ok(Self::RAlt),
-            "Command" => Ok(Self::Command),
-            "LOption" => Ok(Self::LOption),
-            "ROption" => Ok(Self::ROption),
-            "LMeta" => Ok(Self::LMeta),
-            "RMeta" => Ok(Self::RMeta),
-            "Enter" => Ok(Self::Enter),
-            "Up" => Ok(Self::Up),
-            "Down" => Ok(Self::Down),
-            "Left" => Ok(Self::Left),
-            "Right" => Ok(Self::Right),
-            "Backspace" => Ok(Self::Backspace),
-            "CapsLock" => Ok(Self::CapsLock),
-            "Tab" => Ok(Self::Tab),
-            "Home" => Ok(Self::Home),
-            "End" => Ok(Self::End),
-            "PageUp" => Ok(Self::PageUp),
-            "PageDown" => Ok(Self::PageDown),
-            "Insert" => Ok(Self::Insert),
-            "Delete" => Ok(Self::Delete),
-            "Numpad0" => Ok(Self::Numpad0),
-            "Numpad1" => Ok(Self::Numpad1),
-            "Numpad2" => Ok(Self::Numpad2),
-            "Numpad3" => Ok(Self::Numpad3),
-            "Numpad4" => Ok(Self::Numpad4),
-            "Numpad5" => Ok(Self::Numpad5),
-            "Numpad6" => Ok(Self::Numpad6),
-            "Numpad7" => Ok(Self::Numpad7),
-            "Numpad8" => Ok(Self::Numpad8),
-            "Numpad9" => Ok(Self::Numpad9),
-            "NumpadSubtract" => Ok(Self::NumpadSubtract),
-            "NumpadAdd" => Ok(Self::NumpadAdd),
-            "NumpadDivide" => Ok(Self::NumpadDivide),
-            "NumpadMultiply" => Ok(Self::NumpadMultiply),
-            "NumpadEquals" => Ok(Self::NumpadEquals),
-            "NumpadEnter" => Ok(Self::NumpadEnter),
-            "NumpadDecimal" => Ok(Self::NumpadDecimal),
-            "Grave" => Ok(Self::Grave),
-            "Minus" => Ok(Self::Minus),
-            "Equal" => Ok(Self::Equal),
-            "LeftBracket" => Ok(Self::LeftBracket),
-            "RightBracket" => Ok(Self::RightBracket),
-            "BackSlash" => Ok(Self::BackSlash),
-            "Semicolon" => Ok(Self::Semicolon),
-            "Apostrophe" => Ok(Self::Apostrophe),
-            "Comma" => Ok(Self::Comma),
-            "Dot" => Ok(Self::Dot),
-            "Slash" => Ok(Self::Slash),
-            _ => Err(String::from("failed to parse keycode")),
+/// A modifier key regardless of its side, such as [`Modifier::Shift`] for both
+/// [`Keycode::LShift`] and [`Keycode::RShift`].
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Modifier {
+    /// `LControl` or `RControl`.
+    Control,
+    /// `LShift` or `RShift`.
+    Shift,
+    /// `LAlt` or `RAlt`.
+    Alt,
+    /// `LMeta` or `RMeta`: the Windows or Super key.
+    Meta,
+    /// `LOption` or `ROption`, on macOS.
+    Option,
+    /// `Command`, on macOS.
+    Command,
+}
+
+impl Modifier {
+    /// The left key of this modifier, or `Command` for `Command`.
+    pub fn left(self) -> Keycode {
+        match self {
+            Modifier::Control => Keycode::LControl,
+            Modifier::Shift => Keycode::LShift,
+            Modifier::Alt => Keycode::LAlt,
+            Modifier::Meta => Keycode::LMeta,
+            Modifier::Option => Keycode::LOption,
+            Modifier::Command => Keycode::Command,
+        }
+    }
+
+    /// The right key of this modifier, or `Command` for `Command`.
+    pub fn right(self) -> Keycode {
+        match self {
+            Modifier::Control => Keycode::RControl,
+            Modifier::Shift => Keycode::RShift,
+            Modifier::Alt => Keycode::RAlt,
+            Modifier::Meta => Keycode::RMeta,
+            Modifier::Option => Keycode::ROption,
+            Modifier::Command => Keycode::Command,
         }
     }
 }
 
+impl Keycode {
+    /// The modifier of a modifier key, regardless of its side.
+    ///
+    /// ```
+    /// use device_query::{Keycode, Modifier};
+    ///
+    /// assert_eq!(Keycode::RShift.modifier(), Some(Modifier::Shift));
+    /// assert_eq!(Modifier::Shift.left(), Keycode::LShift);
+    /// assert_eq!(Keycode::A.modifier(), None);
+    /// ```
+    pub fn modifier(self) -> Option<Modifier> {
+        match self {
+            Keycode::LControl | Keycode::RControl => Some(Modifier::Control),
+            Keycode::LShift | Keycode::RShift => Some(Modifier::Shift),
+            Keycode::LAlt | Keycode::RAlt => Some(Modifier::Alt),
+            Keycode::LMeta | Keycode::RMeta => Some(Modifier::Meta),
+            Keycode::LOption | Keycode::ROption => Some(Modifier::Option),
+            Keycode::Command => Some(Modifier::Command),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a modifier key: control, shift, alt, meta, option or command. Lock keys
+    /// aren't modifiers.
+    pub fn is_modifier(self) -> bool {
+        self.modifier().is_some()
+    }
+
+    /// Whether this is a key of the numeric keypad, `NumLock` excepted.
+    pub fn is_numpad(self) -> bool {
+        matches!(
+            self,
+            Keycode::Numpad0
+                | Keycode::Numpad1
+                | Keycode::Numpad2
+                | Keycode::Numpad3
+                | Keycode::Numpad4
+                | Keycode::Numpad5
+                | Keycode::Numpad6
+                | Keycode::Numpad7
+                | Keycode::Numpad8
+                | Keycode::Numpad9
+                | Keycode::NumpadSubtract
+                | Keycode::NumpadAdd
+                | Keycode::NumpadDivide
+                | Keycode::NumpadMultiply
+                | Keycode::NumpadEquals
+                | Keycode::NumpadEnter
+                | Keycode::NumpadDecimal
+                | Keycode::NumpadComma
+        )
+    }
+
+    /// Whether this is one of the function keys `F1` to `F24`.
+    pub fn is_function(self) -> bool {
+        matches!(
+            self,
+            Keycode::F1
+                | Keycode::F2
+                | Keycode::F3
+                | Keycode::F4
+                | Keycode::F5
+                | Keycode::F6
+                | Keycode::F7
+                | Keycode::F8
+                | Keycode::F9
+                | Keycode::F10
+                | Keycode::F11
+                | Keycode::F12
+                | Keycode::F13
+                | Keycode::F14
+                | Keycode::F15
+                | Keycode::F16
+                | Keycode::F17
+                | Keycode::F18
+                | Keycode::F19
+                | Keycode::F20
+                | Keycode::F21
+                | Keycode::F22
+                | Keycode::F23
+                | Keycode::F24
+        )
+    }
+
+    /// Whether this is an arrow key, `Home`, `End`, `PageUp` or `PageDown`.
+    pub fn is_navigation(self) -> bool {
+        matches!(
+            self,
+            Keycode::Up
+                | Keycode::Down
+                | Keycode::Left
+                | Keycode::Right
+                | Keycode::Home
+                | Keycode::End
+                | Keycode::PageUp
+                | Keycode::PageDown
+        )
+    }
+
+    /// Whether this key types a character or a space on a US or international layout without
+    /// modifiers: digits, letters, punctuation, `Space` and the numeric keypad but `NumpadEnter`.
+    ///
+    /// ```
+    /// use device_query::Keycode;
+    ///
+    /// assert!(Keycode::Slash.is_printable());
+    /// assert!(!Keycode::Enter.is_printable());
+    /// ```
+    pub fn is_printable(self) -> bool {
+        match self {
+            Keycode::Key0
+            | Keycode::Key1
+            | Keycode::Key2
+            | Keycode::Key3
+            | Keycode::Key4
+            | Keycode::Key5
+            | Keycode::Key6
+            | Keycode::Key7
+            | Keycode::Key8
+            | Keycode::Key9
+            | Keycode::A
+            | Keycode::B
+            | Keycode::C
+            | Keycode::D
+            | Keycode::E
+            | Keycode::F
+            | Keycode::G
+            | Keycode::H
+            | Keycode::I
+            | Keycode::J
+            | Keycode::K
+            | Keycode::L
+            | Keycode::M
+            | Keycode::N
+            | Keycode::O
+            | Keycode::P
+            | Keycode::Q
+            | Keycode::R
+            | Keycode::S
+            | Keycode::T
+            | Keycode::U
+            | Keycode::V
+            | Keycode::W
+            | Keycode::X
+            | Keycode::Y
+            | Keycode::Z
+            | Keycode::Space
+            | Keycode::Grave
+            | Keycode::Minus
+            | Keycode::Equal
+            | Keycode::LeftBracket
+            | Keycode::RightBracket
+            | Keycode::BackSlash
+            | Keycode::Semicolon
+            | Keycode::Apostrophe
+            | Keycode::Comma
+            | Keycode::Dot
+            | Keycode::Slash
+            | Keycode::IntlBackslash
+            | Keycode::IntlRo
+            | Keycode::IntlYen => true,
+            Keycode::NumpadEnter => false,
+            key => key.is_numpad(),
+        }
+    }
+}
+
+/// Parses the platform code of `Unknown(0x1d3)` or `Unknown(467)`.
+fn parse_unknown(s: &str) -> Option<Keycode> {
+    let code = s.strip_prefix("Unknown(")?.strip_suffix(')')?;
+    let platform_code = match code.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => code.parse().ok()?,
+    };
+    Some(Keycode::Unknown { platform_code })
+}
+
 impl fmt::Display for Keycode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Keycode::Unknown { platform_code } => write!(f, "Unknown({:#x})", platform_code),
+            key => write!(f, "{:?}", key),
+        }
     }
 }
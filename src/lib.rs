@@ -17,26 +17,56 @@
 //! ```no_run
 //!  use device_query::{DeviceEvents, DeviceEventsHandler};
 //!  use std::time::Duration;
-//! 
+//!
 //!  let event_handler = DeviceEventsHandler::new(Duration::from_millis(100)).unwrap();
-//! 
+//!
 //!  // The hotkey will be deregistered when the guard is dropped
-//!  let _guard = device_state.on_mouse_move(|position| {
+//!  let _guard = event_handler.on_mouse_move(|position| {
 //!     println!("Mouse position: {:#?}", position);
 //!  });
-//! 
+//!
 //!  // Keep the main thread alive
 //!  loop {}
 //! ```
+//!
+//! Or to receive all events, in order, from a channel.
+//! ```no_run
+//! use device_query::{DeviceEvent, DeviceEvents, DeviceEventsHandler};
+//! use std::time::Duration;
+//!
+//! let event_handler = DeviceEventsHandler::new(Duration::from_millis(100)).unwrap();
+//! for event in event_handler.subscribe() {
+//!     if let DeviceEvent::KeyDown(key) = event {
+//!         println!("Pressed: {}", key);
+//!     }
+//! }
+//! ```
+
+#[cfg(feature = "serde")]
+extern crate serde;
 
+pub mod button_set;
+pub mod conversions;
 pub mod device_events;
 pub mod device_query;
+pub mod device_simulate;
 pub mod device_state;
+pub mod error;
+pub mod key_set;
 pub mod keymap;
 pub mod mouse_state;
+pub mod screen;
+#[cfg(feature = "serde")]
+mod serialization;
 
+pub use button_set::*;
+pub use conversions::*;
 pub use device_events::*;
 pub use device_query::*;
+pub use device_simulate::*;
 pub use device_state::*;
+pub use error::*;
+pub use key_set::*;
 pub use keymap::*;
 pub use mouse_state::*;
+pub use screen::*;
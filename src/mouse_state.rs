@@ -1,18 +1,160 @@
 //! Description of mouse coordinates and state of buttons.
 
+use std::convert::TryFrom;
+use ButtonSet;
+
 /// Mouse position.
 pub type MousePosition = (i32, i32);
 
-/// MouseButton.
-pub type MouseButton = usize;
+/// Relative mouse motion: the horizontal and vertical distance the device moved, positive right
+/// and down.
+pub type MouseDelta = (i32, i32);
+
+/// A mouse button, the same on every platform and backend.
+///
+/// | `MouseButton` | X11    | evdev                       | Windows       | macOS        |
+/// |---------------|--------|-----------------------------|---------------|--------------|
+/// | `Left`        | 1      | `BTN_LEFT`                  | `VK_LBUTTON`  | 0            |
+/// | `Right`       | 3      | `BTN_RIGHT`                 | `VK_RBUTTON`  | 1            |
+/// | `Middle`      | 2      | `BTN_MIDDLE`                | `VK_MBUTTON`  | 2            |
+/// | `Back`        | 8      | `BTN_SIDE`                  | `VK_XBUTTON1` | 3            |
+/// | `Forward`     | 9      | `BTN_EXTRA`                 | `VK_XBUTTON2` | 4            |
+/// | `Other(n)`    | 10 + n | `BTN_FORWARD` + n, to 0x11f | none          | 5 + n, to 31 |
+///
+/// The X server numbers the evdev buttons the same way, so that a button is the same with every
+/// Linux backend. X11 buttons 4 to 7 are the scroll wheel, which isn't a button: its motion is
+/// reported by [`DeviceEvent::Scroll`](crate::DeviceEvent::Scroll) events.
+///
+/// The conversions are available on every platform, with
+/// [`from_x11_button`](Self::from_x11_button) and [`x11_button`](Self::x11_button),
+/// [`from_evdev_code`](Self::from_evdev_code) and [`evdev_code`](Self::evdev_code),
+/// [`from_windows_virtual_key`](Self::from_windows_virtual_key) and
+/// [`windows_virtual_key`](Self::windows_virtual_key), and
+/// [`from_macos_button`](Self::from_macos_button) and [`macos_button`](Self::macos_button).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum MouseButton {
+    /// The primary button.
+    Left,
+    /// The secondary button.
+    Right,
+    /// The middle button, usually the wheel being pressed.
+    Middle,
+    /// The back side button, the one nearest the user.
+    Back,
+    /// The forward side button.
+    Forward,
+    /// Any other button, numbered from 0 in platform order.
+    Other(u8),
+}
+
+impl MouseButton {
+    /// Number of buttons before the `Other` ones.
+    const NAMED: usize = 5;
+
+    /// The position of this button in a [`ButtonSet`](crate::ButtonSet): the named buttons in
+    /// declaration order, then the `Other` buttons.
+    pub(crate) fn index(self) -> usize {
+        match self {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Back => 3,
+            MouseButton::Forward => 4,
+            MouseButton::Other(number) => Self::NAMED + number as usize,
+        }
+    }
+
+    /// The button at `index`, the inverse of [`index`](Self::index).
+    pub(crate) fn from_index(index: usize) -> Option<MouseButton> {
+        Some(match index {
+            0 => MouseButton::Left,
+            1 => MouseButton::Right,
+            2 => MouseButton::Middle,
+            3 => MouseButton::Back,
+            4 => MouseButton::Forward,
+            index => MouseButton::Other(u8::try_from(index - Self::NAMED).ok()?),
+        })
+    }
+}
+
+/// Scroll wheel motion, in 1/120 of a wheel notch so that high resolution wheels and touchpads
+/// can report fractions of a notch. Positive values scroll down and right, like
+/// [`DeviceSimulate::scroll`](crate::DeviceSimulate::scroll).
+///
+/// ```
+/// use device_query::ScrollDelta;
+///
+/// let delta = ScrollDelta::from_notches(0, -2);
+/// assert_eq!(delta.vertical, -2 * ScrollDelta::NOTCH);
+/// assert_eq!(delta.vertical_notches(), -2.0);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct ScrollDelta {
+    /// Horizontal motion: positive to the right, negative to the left.
+    pub horizontal: i32,
+    /// Vertical motion: positive down, towards the user, negative up.
+    pub vertical: i32,
+}
 
-#[derive(Debug, PartialEq, Default, Clone)]
+impl ScrollDelta {
+    /// The motion of one wheel notch.
+    pub const NOTCH: i32 = 120;
+
+    /// The motion of `horizontal` and `vertical` whole notches.
+    pub fn from_notches(horizontal: i32, vertical: i32) -> ScrollDelta {
+        ScrollDelta {
+            horizontal: horizontal.saturating_mul(Self::NOTCH),
+            vertical: vertical.saturating_mul(Self::NOTCH),
+        }
+    }
+
+    /// Whether there is no motion on either axis.
+    pub fn is_zero(&self) -> bool {
+        self.horizontal == 0 && self.vertical == 0
+    }
+
+    /// The horizontal motion in notches.
+    pub fn horizontal_notches(&self) -> f64 {
+        f64::from(self.horizontal) / f64::from(Self::NOTCH)
+    }
+
+    /// The vertical motion in notches.
+    pub fn vertical_notches(&self) -> f64 {
+        f64::from(self.vertical) / f64::from(Self::NOTCH)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// A simple structure containing the current mouse coordinates and the
-/// state of each mouse button that we can query. Currently, Windows and
-/// Linux provide nice ways to query five mouse buttons.
+/// mouse buttons held down.
+///
+/// Windows reports the five named [`MouseButton`]s only. The scroll wheel has no state, its
+/// motion is reported by [`DeviceEvent::Scroll`](crate::DeviceEvent::Scroll) events instead.
+///
+/// ```
+/// use device_query::{MouseButton, MouseState};
+///
+/// let mut mouse = MouseState::default();
+/// mouse.button_pressed.insert(MouseButton::Back);
+/// assert!(mouse.is_pressed(MouseButton::Back));
+/// assert!(!mouse.is_pressed(MouseButton::Left));
+/// ```
 pub struct MouseState {
-    /// Coordinates in pixel.
+    /// Coordinates in pixels, relative to the top left corner of the desktop: the root window of
+    /// the X screen the pointer is on on X11, and points rather than pixels on macOS. See
+    /// [`get_pointer_location`](crate::DeviceQuery::get_pointer_location) for the screen and
+    /// monitor.
     pub coords: MousePosition,
-    /// State of each mouse button.
-    pub button_pressed: [bool; 5],
+    /// The buttons held down.
+    pub button_pressed: ButtonSet,
+}
+
+impl MouseState {
+    /// Whether `button` is held down.
+    pub fn is_pressed(&self, button: MouseButton) -> bool {
+        self.button_pressed.contains(button)
+    }
 }
@@ -0,0 +1,104 @@
+//! Monitors, and where the pointer is on the desktop.
+
+use MousePosition;
+
+/// A monitor: a rectangle of the desktop, in the coordinates of
+/// [`MouseState::coords`](crate::MouseState::coords).
+///
+/// ```
+/// use device_query::Monitor;
+///
+/// let monitor = Monitor {
+///     name: Some(String::from("HDMI-1")),
+///     screen: 0,
+///     position: (1920, 0),
+///     size: (1280, 1024),
+///     scale: 1.0,
+///     primary: false,
+/// };
+/// assert!(monitor.contains((1920, 0)));
+/// assert!(!monitor.contains((3200, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Monitor {
+    /// The name of the monitor, if the platform names it: the RandR output, e.g. `DP-1`, or the
+    /// Windows display device, e.g. `\\.\DISPLAY1`.
+    pub name: Option<String>,
+    /// The X screen of the monitor. Always 0 on other platforms, and on X servers with a single
+    /// screen spanning every monitor, which is the usual setup.
+    pub screen: usize,
+    /// The top left corner.
+    pub position: MousePosition,
+    /// Width and height, in pixels, or in points on macOS.
+    pub size: (u32, u32),
+    /// The ratio of physical pixels to the logical pixels applications draw with: 2.0 on a
+    /// display scaled at 200%.
+    pub scale: f64,
+    /// Whether this is the primary monitor.
+    pub primary: bool,
+}
+
+impl Monitor {
+    /// Whether `position`, on the same screen, is on this monitor.
+    pub fn contains(&self, position: MousePosition) -> bool {
+        let (x, y) = (i64::from(position.0), i64::from(position.1));
+        let (left, top) = (i64::from(self.position.0), i64::from(self.position.1));
+        x >= left
+            && y >= top
+            && x < left + i64::from(self.size.0)
+            && y < top + i64::from(self.size.1)
+    }
+}
+
+/// Where the pointer is: its coordinates, and the screen, monitor and window it is on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct PointerLocation {
+    /// Coordinates in pixels, relative to the top left corner of the screen: the same as
+    /// [`MouseState::coords`](crate::MouseState::coords).
+    pub coords: MousePosition,
+    /// The X screen the pointer is on. Always 0 on other platforms.
+    pub screen: usize,
+    /// The index of the monitor under the pointer in
+    /// [`get_monitors`](crate::DeviceQuery::get_monitors), if it is on one.
+    pub monitor: Option<usize>,
+    /// The top-level window under the pointer, if known: the X11 window ID, which is the
+    /// window manager frame of the window if there is one, or the Windows `HWND`.
+    pub window: Option<u64>,
+}
+
+impl PointerLocation {
+    /// The location of a pointer at `coords` on `screen`, on the first of `monitors` containing
+    /// it, over no known window.
+    ///
+    /// ```
+    /// use device_query::{Monitor, PointerLocation};
+    ///
+    /// let left = Monitor {
+    ///     name: None,
+    ///     screen: 0,
+    ///     position: (0, 0),
+    ///     size: (1920, 1080),
+    ///     scale: 1.0,
+    ///     primary: true,
+    /// };
+    /// let right = Monitor {
+    ///     position: (1920, 0),
+    ///     primary: false,
+    ///     ..left.clone()
+    /// };
+    /// let location = PointerLocation::locate((2000, 10), 0, &[left, right]);
+    /// assert_eq!(location.monitor, Some(1));
+    /// ```
+    pub fn locate(coords: MousePosition, screen: usize, monitors: &[Monitor]) -> PointerLocation {
+        PointerLocation {
+            coords,
+            screen,
+            monitor: monitors
+                .iter()
+                .position(|monitor| monitor.screen == screen && monitor.contains(coords)),
+            window: None,
+        }
+    }
+}
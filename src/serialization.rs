@@ -0,0 +1,183 @@
+//! `Serialize` and `Deserialize` implementations, behind the `serde` feature.
+//!
+//! Keycodes are serialized as their [`Display`](std::fmt::Display) names and deserialized with
+//! [`FromStr`](std::str::FromStr), like hotkeys and key sequences. Key sets are sequences of
+//! keycodes, and button sets sequences of mouse buttons. The `observed` instant of a
+//! [`TimedEvent`] isn't serialized: deserialized events are observed when they are deserialized.
+//!
+//! ```
+//! # extern crate device_query;
+//! # extern crate serde_json;
+//! use device_query::Keycode;
+//!
+//! # fn main() {
+//! for key in Keycode::ALL.iter() {
+//!     let json = serde_json::to_string(key).unwrap();
+//!     assert_eq!(json, format!("\"{}\"", key));
+//!     assert_eq!(serde_json::from_str::<Keycode>(&json).unwrap(), *key);
+//! }
+//!
+//! let unknown = Keycode::Unknown { platform_code: 0x1d3 };
+//! let json = serde_json::to_string(&unknown).unwrap();
+//! assert_eq!(json, "\"Unknown(0x1d3)\"");
+//! assert_eq!(serde_json::from_str::<Keycode>(&json).unwrap(), unknown);
+//! assert!(serde_json::from_str::<Keycode>("\"NotAKey\"").is_err());
+//! # }
+//! ```
+//!
+//! ```
+//! # extern crate device_query;
+//! # extern crate serde_json;
+//! use device_query::{DeviceEvent, Hotkey, KeySequence, KeySet, Keycode, MouseButton};
+//! use device_query::{MouseState, ScrollDelta, TimedEvent};
+//! use std::time::Duration;
+//!
+//! # fn main() {
+//! let mouse = MouseState {
+//!     coords: (-20, 300),
+//!     button_pressed: vec![MouseButton::Left, MouseButton::Other(3)].into_iter().collect(),
+//! };
+//! let json = serde_json::to_string(&mouse).unwrap();
+//! assert_eq!(serde_json::from_str::<MouseState>(&json).unwrap(), mouse);
+//!
+//! let events = vec![
+//!     DeviceEvent::KeyDown(Keycode::A),
+//!     DeviceEvent::KeyUp(Keycode::Unknown { platform_code: 0x2a0 }),
+//!     DeviceEvent::MouseMove((12, -4)),
+//!     DeviceEvent::MouseDown(MouseButton::Left),
+//!     DeviceEvent::MouseUp(MouseButton::Other(2)),
+//!     DeviceEvent::Scroll(ScrollDelta::from_notches(0, -1)),
+//!     DeviceEvent::MouseMotion((-3, 7)),
+//! ];
+//! for event in events {
+//!     let timed = TimedEvent::new(event, Some(Duration::from_millis(1500)));
+//!     let json = serde_json::to_string(&timed).unwrap();
+//!     let deserialized: TimedEvent = serde_json::from_str(&json).unwrap();
+//!     assert_eq!(deserialized.event, event);
+//!     assert_eq!(deserialized.device_time, timed.device_time);
+//! }
+//!
+//! let keys: KeySet = vec![Keycode::LShift, Keycode::F24].into_iter().collect();
+//! let json = serde_json::to_string(&keys).unwrap();
+//! assert_eq!(json, "[\"LShift\",\"F24\"]");
+//! assert_eq!(serde_json::from_str::<KeySet>(&json).unwrap(), keys);
+//!
+//! let hotkey: Hotkey = "Ctrl+Shift+K".parse().unwrap();
+//! let json = serde_json::to_string(&hotkey).unwrap();
+//! assert_eq!(json, "\"LControl+LShift+K\"");
+//! assert_eq!(serde_json::from_str::<Hotkey>(&json).unwrap(), hotkey);
+//!
+//! let sequence: KeySequence = "Ctrl+X Ctrl+S".parse().unwrap();
+//! let json = serde_json::to_string(&sequence).unwrap();
+//! assert_eq!(serde_json::from_str::<KeySequence>(&json).unwrap(), sequence);
+//! # }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use {ButtonSet, Hotkey, KeySequence, KeySet, Keycode};
+
+/// Deserializes a string with the `FromStr` implementation of `T`.
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T: FromStr<Err = String>> Visitor<'de> for FromStrVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        s.parse().map_err(E::custom)
+    }
+}
+
+/// Implements `Serialize` with `Display` and `Deserialize` with `FromStr`.
+macro_rules! serde_with_str {
+    ($type:ty, $expecting:expr) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FromStrVisitor {
+                    expecting: $expecting,
+                    marker: PhantomData,
+                })
+            }
+        }
+    };
+}
+
+serde_with_str!(Keycode, "a key name");
+serde_with_str!(Hotkey, "a hotkey such as `Ctrl+Shift+K`");
+serde_with_str!(KeySequence, "a key sequence such as `Ctrl+X Ctrl+S`");
+
+impl Serialize for KeySet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+struct KeySetVisitor;
+
+impl<'de> Visitor<'de> for KeySetVisitor {
+    type Value = KeySet;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of key names")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<KeySet, A::Error> {
+        let mut keys = KeySet::new();
+        while let Some(key) = seq.next_element()? {
+            keys.insert(key);
+        }
+        Ok(keys)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(KeySetVisitor)
+    }
+}
+
+impl Serialize for ButtonSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+struct ButtonSetVisitor;
+
+impl<'de> Visitor<'de> for ButtonSetVisitor {
+    type Value = ButtonSet;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of mouse buttons")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ButtonSet, A::Error> {
+        let mut buttons = ButtonSet::new();
+        while let Some(button) = seq.next_element()? {
+            buttons.insert(button);
+        }
+        Ok(buttons)
+    }
+}
+
+impl<'de> Deserialize<'de> for ButtonSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(ButtonSetVisitor)
+    }
+}
@@ -0,0 +1,220 @@
+//! Runs the evdev backend against a virtual device created through uinput.
+//!
+//! Requires write access to `/dev/uinput`, run with `cargo test -- --ignored`.
+
+#![cfg(target_os = "linux")]
+
+extern crate device_query;
+extern crate libc;
+
+use device_query::{DeviceBackend, DeviceEvent, DeviceQuery, DeviceState, Keycode, MouseButton};
+use std::ffi::CStr;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::raw::c_char;
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+use std::{mem, slice};
+
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const SYN_REPORT: u16 = 0x00;
+const KEY_A: u16 = 30;
+const BTN_LEFT: u16 = 0x110;
+const BTN_SIDE: u16 = 0x113;
+const BTN_EXTRA: u16 = 0x114;
+const BTN_FORWARD: u16 = 0x115;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const BUS_VIRTUAL: u16 = 0x06;
+
+const UI_DEV_CREATE: libc::Ioctl = libc::_IO(b'U' as u32, 1);
+const UI_DEV_DESTROY: libc::Ioctl = libc::_IO(b'U' as u32, 2);
+const UI_DEV_SETUP: libc::Ioctl = libc::_IOW::<libc::uinput_setup>(b'U' as u32, 3);
+const UI_SET_EVBIT: libc::Ioctl = libc::_IOW::<libc::c_int>(b'U' as u32, 100);
+const UI_SET_KEYBIT: libc::Ioctl = libc::_IOW::<libc::c_int>(b'U' as u32, 101);
+const UI_SET_RELBIT: libc::Ioctl = libc::_IOW::<libc::c_int>(b'U' as u32, 102);
+const UI_GET_SYSNAME: libc::Ioctl = libc::_IOR::<[c_char; 64]>(b'U' as u32, 44);
+
+/// A keyboard and mouse created through uinput, destroyed when dropped.
+struct VirtualDevice {
+    uinput: File,
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+impl VirtualDevice {
+    fn create() -> io::Result<VirtualDevice> {
+        let uinput = OpenOptions::new().write(true).open("/dev/uinput")?;
+        let fd = uinput.as_raw_fd();
+        let mut setup: libc::uinput_setup = unsafe { mem::zeroed() };
+        setup.id.bustype = BUS_VIRTUAL;
+        for (name, byte) in setup.name.iter_mut().zip(b"device_query test device") {
+            *name = *byte as c_char;
+        }
+        unsafe {
+            check(libc::ioctl(fd, UI_SET_EVBIT, EV_KEY as libc::c_int))?;
+            check(libc::ioctl(fd, UI_SET_KEYBIT, KEY_A as libc::c_int))?;
+            for button in [BTN_LEFT, BTN_SIDE, BTN_EXTRA, BTN_FORWARD] {
+                check(libc::ioctl(fd, UI_SET_KEYBIT, button as libc::c_int))?;
+            }
+            check(libc::ioctl(fd, UI_SET_EVBIT, EV_REL as libc::c_int))?;
+            check(libc::ioctl(fd, UI_SET_RELBIT, REL_X as libc::c_int))?;
+            check(libc::ioctl(fd, UI_SET_RELBIT, REL_Y as libc::c_int))?;
+            check(libc::ioctl(fd, UI_DEV_SETUP, &setup))?;
+            check(libc::ioctl(fd, UI_DEV_CREATE))?;
+        }
+        Ok(VirtualDevice { uinput })
+    }
+
+    /// The `/dev/input/event*` node of the device, once udev created it.
+    fn event_path(&self) -> io::Result<PathBuf> {
+        let mut sysname = [0 as c_char; 64];
+        unsafe {
+            check(libc::ioctl(
+                self.uinput.as_raw_fd(),
+                UI_GET_SYSNAME,
+                sysname.as_mut_ptr(),
+            ))?;
+        }
+        let sysname = unsafe { CStr::from_ptr(sysname.as_ptr()) };
+        let sys_path = PathBuf::from("/sys/devices/virtual/input").join(sysname.to_str().unwrap());
+        for _ in 0..100 {
+            let event = fs::read_dir(&sys_path)?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name())
+                .find(|name| name.to_string_lossy().starts_with("event"));
+            if let Some(event) = event {
+                let path = PathBuf::from("/dev/input").join(event);
+                if path.exists() {
+                    return Ok(path);
+                }
+            }
+            sleep(Duration::from_millis(10));
+        }
+        Err(io::Error::new(io::ErrorKind::NotFound, "no event device"))
+    }
+
+    /// Writes `events` followed by a `SYN_REPORT`.
+    fn emit(&mut self, events: &[(u16, u16, i32)]) -> io::Result<()> {
+        let mut input: Vec<libc::input_event> = events
+            .iter()
+            .chain(&[(EV_SYN, SYN_REPORT, 0)])
+            .map(|&(type_, code, value)| {
+                let mut event: libc::input_event = unsafe { mem::zeroed() };
+                event.type_ = type_;
+                event.code = code;
+                event.value = value;
+                event
+            })
+            .collect();
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                input.as_mut_ptr() as *const u8,
+                input.len() * mem::size_of::<libc::input_event>(),
+            )
+        };
+        self.uinput.write_all(bytes)
+    }
+}
+
+impl Drop for VirtualDevice {
+    fn drop(&mut self) {
+        unsafe {
+            libc::ioctl(self.uinput.as_raw_fd(), UI_DEV_DESTROY);
+        }
+    }
+}
+
+#[test]
+#[ignore = "requires write access to /dev/uinput"]
+fn reads_virtual_device() {
+    let mut device = VirtualDevice::create().expect("Couldn't create a uinput device");
+    let path = device
+        .event_path()
+        .expect("Couldn't find the uinput event device");
+    let device_state = DeviceState::from_evdev_paths(&[path]).expect("Couldn't open the device");
+
+    device.emit(&[(EV_KEY, KEY_A, 1)]).unwrap();
+    device
+        .emit(&[(EV_REL, REL_X, 10), (EV_REL, REL_Y, 5)])
+        .unwrap();
+    device.emit(&[(EV_REL, REL_X, -3)]).unwrap();
+    device.emit(&[(EV_KEY, BTN_LEFT, 1)]).unwrap();
+    assert_eq!(device_state.get_keys(), vec![Keycode::A]);
+    let mouse = device_state.get_mouse();
+    assert_eq!(mouse.coords, (7, 5));
+    assert!(mouse.is_pressed(MouseButton::Left));
+
+    device
+        .emit(&[(EV_KEY, KEY_A, 0), (EV_KEY, BTN_LEFT, 0)])
+        .unwrap();
+    assert_eq!(device_state.get_keys(), vec![]);
+    assert!(!device_state.get_mouse().is_pressed(MouseButton::Left));
+}
+
+#[test]
+#[ignore = "requires write access to /dev/uinput"]
+fn queries_keep_events_for_the_event_loop() {
+    let mut device = VirtualDevice::create().expect("Couldn't create a uinput device");
+    let path = device
+        .event_path()
+        .expect("Couldn't find the uinput event device");
+    let device_state = DeviceState::from_evdev_paths(&[path]).expect("Couldn't open the device");
+    let mut timed_events = vec![];
+    assert!(device_state.read_events(Duration::from_millis(10), &mut timed_events));
+
+    device.emit(&[(EV_KEY, KEY_A, 1)]).unwrap();
+    device.emit(&[(EV_REL, REL_X, 4)]).unwrap();
+    device.emit(&[(EV_KEY, KEY_A, 0)]).unwrap();
+    assert_eq!(device_state.get_mouse().coords, (4, 0));
+    assert!(device_state.read_events(Duration::from_millis(10), &mut timed_events));
+    let events: Vec<DeviceEvent> = timed_events.iter().map(|timed| timed.event).collect();
+    assert_eq!(
+        events,
+        [
+            DeviceEvent::KeyDown(Keycode::A),
+            DeviceEvent::MouseMove((4, 0)),
+            DeviceEvent::MouseMotion((4, 0)),
+            DeviceEvent::KeyUp(Keycode::A),
+        ]
+    );
+}
+
+#[test]
+#[ignore = "requires write access to /dev/uinput"]
+fn side_buttons_are_mapped() {
+    let mut device = VirtualDevice::create().expect("Couldn't create a uinput device");
+    let path = device
+        .event_path()
+        .expect("Couldn't find the uinput event device");
+    let device_state = DeviceState::from_evdev_paths(&[path]).expect("Couldn't open the device");
+    let mut timed_events = vec![];
+    assert!(device_state.read_events(Duration::from_millis(10), &mut timed_events));
+
+    for button in [BTN_SIDE, BTN_EXTRA, BTN_FORWARD] {
+        device.emit(&[(EV_KEY, button, 1)]).unwrap();
+        device.emit(&[(EV_KEY, button, 0)]).unwrap();
+    }
+    assert!(device_state.read_events(Duration::from_millis(10), &mut timed_events));
+    let events: Vec<DeviceEvent> = timed_events.iter().map(|timed| timed.event).collect();
+    let mut expected = vec![];
+    for button in [
+        MouseButton::Back,
+        MouseButton::Forward,
+        MouseButton::Other(0),
+    ] {
+        expected.push(DeviceEvent::MouseDown(button));
+        expected.push(DeviceEvent::MouseUp(button));
+    }
+    assert_eq!(events, expected);
+}
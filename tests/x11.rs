@@ -0,0 +1,254 @@
+//! Round trips through an X server, such as Xvfb: input injected with XTest is read back by the
+//! X11 and XInput2 backends, and translated into text. The pointer is located on every screen of
+//! the server, such as the two screens of `Xvfb -screen 0 1024x768x24 -screen 1 800x600x24`.
+//!
+//! Skipped when `DISPLAY` is unset. The tests share the pointer and keyboard of the server, so
+//! they run one at a time.
+
+#![cfg(target_os = "linux")]
+
+extern crate device_query;
+extern crate x11;
+extern crate x11_dl;
+
+use device_query::{DeviceBackend, DeviceEvent, DeviceQuery, DeviceSimulate, DeviceState};
+use device_query::{InputSimulator, KeyTranslator, Keycode, LinuxBackend};
+use device_query::{MouseButton, ScrollDelta};
+use std::env;
+use std::os::raw::c_int;
+use std::ptr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use x11::xlib;
+// x11-dl names the XTest library after the XF86VidMode extension.
+use x11_dl::xtest::Xf86vmode as XTest;
+
+static SERVER: Mutex<()> = Mutex::new(());
+
+/// Locks the X server for the calling test, or returns `None` if there is no server.
+fn x_server() -> Option<MutexGuard<'static, ()>> {
+    if env::var_os("DISPLAY").is_none() {
+        eprintln!("skipped: DISPLAY is not set");
+        return None;
+    }
+    Some(SERVER.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+fn simulator() -> InputSimulator {
+    InputSimulator::new().expect("Couldn't open the display with XTest")
+}
+
+/// The events read from `device_state` until none arrive for 200 ms, motion excepted.
+fn read_events(device_state: &DeviceState) -> Vec<DeviceEvent> {
+    let mut timed_events = vec![];
+    loop {
+        let read = timed_events.len();
+        assert!(device_state.read_events(Duration::from_millis(200), &mut timed_events));
+        if timed_events.len() == read {
+            break;
+        }
+    }
+    timed_events
+        .iter()
+        .map(|timed| timed.event)
+        .filter(|event| !matches!(event, DeviceEvent::MouseMotion(_)))
+        .collect()
+}
+
+#[test]
+fn xinput2_reads_injected_events() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let simulator = simulator();
+    let device_state =
+        DeviceState::with_backend(LinuxBackend::XInput2).expect("Couldn't open XInput2");
+    simulator.mouse_move_to((10, 10));
+    read_events(&device_state);
+
+    simulator.key_press(Keycode::A);
+    simulator.mouse_move_to((20, 30));
+    simulator.mouse_click(MouseButton::Left);
+    // Moving to where the pointer already is isn't a move.
+    simulator.mouse_move_to((20, 30));
+    simulator.scroll(0, 1);
+    assert_eq!(
+        read_events(&device_state),
+        [
+            DeviceEvent::KeyDown(Keycode::A),
+            DeviceEvent::KeyUp(Keycode::A),
+            DeviceEvent::MouseMove((20, 30)),
+            DeviceEvent::MouseDown(MouseButton::Left),
+            DeviceEvent::MouseUp(MouseButton::Left),
+            DeviceEvent::Scroll(ScrollDelta::from_notches(0, 1)),
+        ]
+    );
+}
+
+#[test]
+fn simulated_keys_are_queried() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let simulator = simulator();
+    let device_state = DeviceState::with_backend(LinuxBackend::X11).expect("Couldn't open X11");
+
+    simulator.key_down(Keycode::LShift);
+    simulator.key_down(Keycode::A);
+    assert_eq!(device_state.get_keys(), [Keycode::A, Keycode::LShift]);
+    simulator.key_up(Keycode::A);
+    simulator.key_up(Keycode::LShift);
+    assert_eq!(device_state.get_keys(), []);
+}
+
+#[test]
+fn typed_text_is_read_back() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let simulator = simulator();
+    let device_state =
+        DeviceState::with_backend(LinuxBackend::XInput2).expect("Couldn't open XInput2");
+    read_events(&device_state);
+
+    simulator.type_text("Hi!").unwrap();
+    let shifted = |key| {
+        [
+            DeviceEvent::KeyDown(Keycode::LShift),
+            DeviceEvent::KeyDown(key),
+            DeviceEvent::KeyUp(key),
+            DeviceEvent::KeyUp(Keycode::LShift),
+        ]
+    };
+    let mut expected = shifted(Keycode::H).to_vec();
+    expected.extend([
+        DeviceEvent::KeyDown(Keycode::I),
+        DeviceEvent::KeyUp(Keycode::I),
+    ]);
+    expected.extend(shifted(Keycode::Key1));
+    assert_eq!(read_events(&device_state), expected);
+    assert_eq!(device_state.get_keys(), []);
+}
+
+#[test]
+fn typed_text_is_translated() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let simulator = simulator();
+    let device_state =
+        DeviceState::with_backend(LinuxBackend::XInput2).expect("Couldn't open XInput2");
+    let mut translator = KeyTranslator::new().expect("Couldn't open the display");
+    read_events(&device_state);
+
+    simulator.type_text("Hi, 42!").unwrap();
+    let mut text = String::new();
+    for event in read_events(&device_state) {
+        match event {
+            DeviceEvent::KeyDown(key) => text.extend(translator.key_down(key)),
+            DeviceEvent::KeyUp(key) => translator.key_up(key),
+            _ => {}
+        }
+    }
+    assert_eq!(text, "Hi, 42!");
+}
+
+#[test]
+fn simulated_mouse_is_queried() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let simulator = simulator();
+    let device_state = DeviceState::with_backend(LinuxBackend::X11).expect("Couldn't open X11");
+
+    simulator.mouse_move_to((100, 200));
+    assert_eq!(device_state.get_mouse().coords, (100, 200));
+    simulator.mouse_move_by(-30, 5);
+    assert_eq!(device_state.get_mouse().coords, (70, 205));
+
+    simulator.mouse_down(MouseButton::Right);
+    let mouse = device_state.get_mouse();
+    assert!(mouse.is_pressed(MouseButton::Right));
+    assert!(!mouse.is_pressed(MouseButton::Left));
+    simulator.mouse_up(MouseButton::Right);
+    assert!(!device_state.get_mouse().is_pressed(MouseButton::Right));
+}
+
+/// Warps the pointer to `(x, y)` on X screen `screen`, which XTest can't move it to.
+fn warp_pointer(screen: c_int, (x, y): (i32, i32)) {
+    unsafe {
+        let display = xlib::XOpenDisplay(ptr::null());
+        assert!(!display.is_null(), "Couldn't open the display");
+        let root = xlib::XRootWindow(display, screen);
+        xlib::XWarpPointer(display, 0, root, 0, 0, 0, 0, x, y);
+        xlib::XSync(display, xlib::False);
+        xlib::XCloseDisplay(display);
+    }
+}
+
+#[test]
+fn side_buttons_are_mapped() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let device_state =
+        DeviceState::with_backend(LinuxBackend::XInput2).expect("Couldn't open XInput2");
+    let xtest = XTest::open().expect("Couldn't load libXtst");
+    let display = unsafe { xlib::XOpenDisplay(ptr::null()) };
+    assert!(!display.is_null(), "Couldn't open the display");
+    read_events(&device_state);
+
+    // X11 buttons 8, 9 and 10, clicked through XTest.
+    for x11_button in 8..=10 {
+        for down in [xlib::True, xlib::False] {
+            unsafe {
+                (xtest.XTestFakeButtonEvent)(
+                    display as *mut _,
+                    x11_button,
+                    down,
+                    xlib::CurrentTime,
+                );
+            }
+        }
+    }
+    unsafe {
+        xlib::XSync(display, xlib::False);
+        xlib::XCloseDisplay(display);
+    }
+    let mut expected = vec![];
+    for button in [
+        MouseButton::Back,
+        MouseButton::Forward,
+        MouseButton::Other(0),
+    ] {
+        expected.push(DeviceEvent::MouseDown(button));
+        expected.push(DeviceEvent::MouseUp(button));
+    }
+    assert_eq!(read_events(&device_state), expected);
+}
+
+#[test]
+fn pointer_is_located_on_every_screen() {
+    let Some(_server) = x_server() else {
+        return;
+    };
+    let device_state = DeviceState::with_backend(LinuxBackend::X11).expect("Couldn't open X11");
+    let monitors = device_state.get_monitors();
+    let screens = monitors.iter().map(|monitor| monitor.screen + 1).max();
+    assert!(screens.is_some(), "no monitor listed");
+
+    for screen in 0..screens.unwrap_or(0) {
+        let monitor = monitors
+            .iter()
+            .position(|monitor| monitor.screen == screen)
+            .unwrap_or_else(|| panic!("no monitor listed on screen {}", screen));
+        let (x, y) = monitors[monitor].position;
+        let coords = (x + 10, y + 20);
+        warp_pointer(screen as c_int, coords);
+        let location = device_state.get_pointer_location();
+        assert_eq!(location.screen, screen);
+        assert_eq!(location.coords, coords);
+        assert_eq!(location.monitor, Some(monitor));
+        assert_eq!(device_state.get_mouse().coords, coords);
+    }
+}